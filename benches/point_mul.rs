@@ -0,0 +1,138 @@
+//! Row count and proving time of `ECChip::point_mul` on Grumpkin.
+//!
+//! For each k, the circuit is filled with as many point muls as fit in its
+//! usable rows, and a proof is created 10 times.
+//!
+//! Run with `cargo bench --bench point_mul`.
+//!
+//! Only this crate is measured. The numbers are meant to be set against a
+//! non-native implementation, e.g., the Grumpkin scalar mul of halo2-lib, run
+//! from its own checkout with the same k and number of trials.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use ark_std::test_rng;
+use halo2_native_ecc::ECChip;
+use halo2_native_ecc::ECConfig;
+use halo2_native_ecc::NativeECOps;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::plonk::create_proof;
+use halo2_proofs::plonk::keygen_pk;
+use halo2_proofs::plonk::keygen_vk;
+use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Error;
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2_proofs::poly::kzg::multiopen::ProverSHPLONK;
+use halo2_proofs::transcript::Blake2bWrite;
+use halo2_proofs::transcript::Challenge255;
+use halo2_proofs::transcript::TranscriptWriterBuffer;
+use halo2curves::bn256::Bn256;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+const TRIALS: usize = 10;
+
+#[derive(Default, Debug, Clone)]
+struct PointMulCircuit {
+    pairs: Vec<(G1Affine, Fr)>,
+}
+
+impl Circuit<Fq> for PointMulCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            pairs: vec![(G1Affine::generator(), Fr::one()); self.pairs.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "point mul bench",
+            |mut region| {
+                let mut offset = 0;
+                for (p, s) in self.pairs.iter() {
+                    ec_chip.point_mul(&mut region, &config, p, s, &mut offset)?;
+                }
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+fn main() {
+    let mut rng = test_rng();
+
+    // the rows of a single point mul
+    let mut meta = ConstraintSystem::<Fq>::default();
+    let config = ECChip::<G1Affine, Fq>::configure(&mut meta);
+    let ec_chip = ECChip::construct(config);
+    let p = G1Affine::generator();
+    let rows = ec_chip
+        .dry_run_offset(|chip, region, config, offset| {
+            chip.point_mul(region, config, &p, &Fr::one(), offset)
+        })
+        .unwrap();
+    println!("point_mul: {rows} rows");
+
+    for k in [14, 17] {
+        let usable_rows =
+            ECConfig::<G1Affine, Fq>::usable_rows(k) - ECChip::<G1Affine, Fq>::min_blinding_rows();
+        let num_muls = usable_rows / rows;
+        let pairs = (0..num_muls)
+            .map(|_| (G1::random(&mut rng).to_affine(), Fr::random(&mut rng)))
+            .collect::<Vec<_>>();
+        let circuit = PointMulCircuit { pairs };
+
+        let params = ParamsKZG::<Bn256>::setup(k, &mut rng);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+        let mut total = Duration::ZERO;
+        for _ in 0..TRIALS {
+            let start = Instant::now();
+            let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+            create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+                &params,
+                &pk,
+                &[circuit.clone()],
+                &[&[&[]]],
+                &mut rng,
+                &mut transcript,
+            )
+            .unwrap();
+            transcript.finalize();
+            total += start.elapsed();
+        }
+
+        let per_proof = total / TRIALS as u32;
+        println!(
+            "k = {k}: {num_muls} point muls, {:?} per proof, {:?} per point mul ({TRIALS} trials)",
+            per_proof,
+            per_proof / num_muls as u32,
+        );
+    }
+}
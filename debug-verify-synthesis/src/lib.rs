@@ -0,0 +1,85 @@
+//! The `#[debug_verify_synthesis]` attribute of `halo2-native-ecc`, exported
+//! with its `debug_verify` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::parse_quote;
+use syn::punctuated::Punctuated;
+use syn::Error;
+use syn::ImplItemFn;
+use syn::MetaNameValue;
+use syn::Token;
+
+/// Wrap the body of `Circuit::synthesize`, so that a successful synthesis of a
+/// circuit with its witness is followed by a run of the mock prover on it:
+///
+/// ```ignore
+/// #[cfg_attr(
+///     feature = "debug_verify",
+///     debug_verify_synthesis(k = 10, witnessed = self.has_witness(), instances = vec![self.public()])
+/// )]
+/// fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+///     ...
+/// }
+/// ```
+///
+/// `k` is the log size of the mock prover, `witnessed` whether the circuit
+/// carries its witness, and `instances` the values of the instance columns.
+/// They are evaluated in `synthesize`, after the body, so they may use `self`.
+/// `instances` defaults to a single empty column.
+///
+/// The key generation synthesizes the circuit of `Circuit::without_witnesses`,
+/// for which `witnessed` must be false: the check is skipped.
+/// If the circuit is not satisfied, the failures are printed to stderr and
+/// `synthesize` returns `Error::Synthesis`. The mock prover calls `synthesize`
+/// again; that nested call is not verified.
+#[proc_macro_attribute]
+pub fn debug_verify_synthesis(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args =
+        parse_macro_input!(attr with Punctuated::<MetaNameValue, Token![,]>::parse_terminated);
+    let mut f = parse_macro_input!(item as ImplItemFn);
+
+    let mut k = None;
+    let mut witnessed = None;
+    let mut instances = None;
+    for arg in args {
+        if arg.path.is_ident("k") {
+            k = Some(arg.value);
+        } else if arg.path.is_ident("witnessed") {
+            witnessed = Some(arg.value);
+        } else if arg.path.is_ident("instances") {
+            instances = Some(arg.value);
+        } else {
+            return Error::new_spanned(arg.path, "expected `k`, `witnessed` or `instances`")
+                .to_compile_error()
+                .into();
+        }
+    }
+    let (Some(k), Some(witnessed)) = (k, witnessed) else {
+        return Error::new(
+            Span::call_site(),
+            "expected `k = ...` and `witnessed = ...`",
+        )
+        .to_compile_error()
+        .into();
+    };
+    let instances = match instances {
+        Some(instances) => quote!(#instances),
+        None => quote!(::std::vec![::std::vec![]]),
+    };
+
+    // the body runs in a closure, so that a `return` in it yields the result
+    let output = &f.sig.output;
+    let body = &f.block;
+    f.block = parse_quote!({
+        let res = (move || #output #body)();
+        if res.is_ok() && (#witnessed) {
+            ::halo2_native_ecc::debug_verify_synthesize(#k, self, #instances)?;
+        }
+        res
+    });
+
+    quote!(#f).into()
+}
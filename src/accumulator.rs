@@ -0,0 +1,71 @@
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// A running accumulator A_{i+1} = A_i + s_i * G over the steps of a long
+/// computation, where G is the generator and s_i are scalars from another chip,
+/// e.g., hash outputs.
+///
+/// The accumulator owns the current point: each `update` adds to the point
+/// returned by the previous one, so no accumulator is witnessed twice.
+#[derive(Debug, Clone)]
+pub struct EcAccumulator<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    acc: AssignedECPoint<C, F>,
+}
+
+impl<C, F> EcAccumulator<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Start from the point A_0, e.g., a constant or a public input.
+    ///
+    /// Caller must check A_0 is on curve.
+    pub fn new(init: AssignedECPoint<C, F>) -> Self {
+        Self { acc: init }
+    }
+
+    /// The current point.
+    pub fn point(&self) -> &AssignedECPoint<C, F> {
+        &self.acc
+    }
+
+    /// A_{i+1} = A_i + s_i * G, for s_i as 256 little endian bit cells.
+    ///
+    /// Caller must check the bits are binary, and A_i != +/- s_i * G.
+    /// Returns a synthesis error if the number of bits is not 256.
+    pub fn update(
+        &mut self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        scalar_bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let gen_assigned = chip.load_generator(region, config, offset)?;
+        let product =
+            chip.point_mul_with_bits(region, config, &gen_assigned, scalar_bits, offset)?;
+        self.acc = chip.point_add(region, config, &self.acc, &product, offset)?;
+        Ok(())
+    }
+
+    /// The final point.
+    pub fn into_point(self) -> AssignedECPoint<C, F> {
+        self.acc
+    }
+}
@@ -0,0 +1,90 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::forged_seed;
+use crate::test_circuit::mock_run;
+use crate::test_circuit::overwrite_point;
+use crate::EcAccumulator;
+use crate::NativeECOps;
+
+#[test]
+fn test_ec_accumulator() {
+    let k = 15;
+
+    let mut rng = test_rng();
+    let init = G1::random(&mut rng).to_affine();
+    let scalars = (0..8).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+
+    // the native fold A_{i+1} = A_i + s_i * G
+    let expected = scalars
+        .iter()
+        .fold(G1::from(init), |acc, s| acc + G1::generator() * s)
+        .to_affine();
+
+    // tampered_step: overwrite the accumulator after the given number of updates
+    // seed: overwrites the seed of the double-then-add loop of s_0 * G, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run_with_seed =
+        |expected: G1Affine, tampered_step: Option<usize>, seed: Option<G1Affine>| {
+            mock_run(k, |ec_chip, region, config, offset| {
+                let init_assigned = ec_chip.load_private_point(region, config, &init, offset)?;
+                let mut acc = EcAccumulator::new(init_assigned);
+
+                let mut tampered_offset = None;
+                for (i, s) in scalars.iter().enumerate() {
+                    let bits = ec_chip.decompose_scalar(region, config, s, offset)?;
+                    if i == 0 {
+                        // G takes a row before the loop
+                        seed_row.set(*offset + 1);
+                    }
+                    acc.update(ec_chip, region, config, &bits, offset)?;
+                    if tampered_step == Some(i + 1) {
+                        tampered_offset = Some(acc.point().offset());
+                    }
+                }
+                let res = acc.into_point();
+
+                if let Some(mut p_offset) = tampered_offset {
+                    // overwrite the intermediate accumulator with another point
+                    let q = (init + G1Affine::generator()).to_affine();
+                    ec_chip.load_private_point_unchecked(region, config, &q, &mut p_offset)?;
+                }
+                if let Some(q) = seed {
+                    overwrite_point(region, config, seed_row.get(), &q)?;
+                }
+
+                let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+                region.constrain_equal(res.x.cell(), expected.x.cell())?;
+                region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+                Ok(())
+            })
+            .unwrap()
+        };
+    let run = |expected, tampered_step| run_with_seed(expected, tampered_step, None);
+
+    run(expected, None).assert_satisfied();
+
+    // error case: a wrong final point
+    assert!(run((expected + G1Affine::generator()).to_affine(), None)
+        .verify()
+        .is_err());
+
+    // error case: an intermediate accumulator is overwritten
+    assert!(run(expected, Some(4)).verify().is_err());
+
+    // error case: a wrong final point, and a seed Q that shifts s_0 * G to it;
+    // the seed is a constant
+    {
+        let wrong = (expected + G1Affine::generator()).to_affine();
+        let seed = forged_seed(G1::generator());
+        let failures = run_with_seed(wrong, None, Some(seed)).verify().unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+}
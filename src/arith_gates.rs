@@ -6,6 +6,8 @@ use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::halo2curves::CurveAffine;
 use halo2_proofs::plonk::Error;
 
+use crate::util::field_decompose_u128;
+use crate::util::leak;
 use crate::ECChip;
 use crate::ECConfig;
 
@@ -44,6 +46,17 @@ pub trait ArithOps<F: Field> {
         offset: &mut usize,
     ) -> Result<AssignedCell<F, F>, Error>;
 
+    /// Copy two assigned cells into an add gate and return the sum.
+    /// Unlike `add`, the operands are copy constrained to the input cells.
+    fn add_cells(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
     /// Multiply two cells and return the product
     fn mul(
         &self,
@@ -54,10 +67,23 @@ pub trait ArithOps<F: Field> {
         offset: &mut usize,
     ) -> Result<AssignedCell<F, F>, Error>;
 
+    /// Copy two assigned cells into a mul gate and return the product.
+    /// Unlike `mul`, the operands are copy constrained to the input cells.
+    fn mul_cells(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
     /// Input x1, y1, x2, y2, x3, y3
     /// Assert that
-    /// - x3 = x1 + 2y1 + 4x2 + 8y2 + 16y3
+    /// - y3 = x1 + 2y1 + 4x2 + 8y2 + 16x3
     /// - x1, y1, x2, y2 are all binary
+    ///
+    /// Returns a synthesis error if the input length is not 6.
     fn partial_bit_decomp(
         &self,
         region: &mut Region<F>,
@@ -66,6 +92,16 @@ pub trait ArithOps<F: Field> {
         offset: &mut usize,
     ) -> Result<Vec<AssignedCell<F, F>>, Error>;
 
+    /// As `partial_bit_decomp`, for exactly 6 inputs, with the length
+    /// enforced by the type.
+    fn partial_bit_decomp_array(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        inputs: &[F; 6],
+        offset: &mut usize,
+    ) -> Result<[AssignedCell<F, F>; 6], Error>;
+
     /// Input a u128,
     /// Output
     /// - its bit decomposition cells in little endian
@@ -78,6 +114,289 @@ pub trait ArithOps<F: Field> {
         input: &u128,
         offset: &mut usize,
     ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error>;
+
+    /// Input a field element cell,
+    /// Output its 256 bit decomposition cells in little endian.
+    ///
+    /// The decomposition is canonical, i.e., the bits encode an integer
+    /// that is smaller than the modulus.
+    /// The layout does not depend on the value of a.
+    fn decompose_field(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>;
+
+    /// Clamp 256 little endian scalar bits as in X25519:
+    /// bits 0, 1, 2 and 255 are cleared, and bit 254 is set.
+    ///
+    /// The overridden bits are replaced by constant cells, and all resulting
+    /// bits are enforced to be binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn clamp_scalar_x25519(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        scalar_bits: &mut [AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Input two cells a and b that hold u128 values,
+    /// Output
+    /// - the cell that contains sum = (a + b) mod 2^128
+    /// - the carry bit
+    ///
+    /// Asserts that a + b = sum + carry * 2^128, that the carry is binary
+    /// and that sum fits in 128 bits.
+    /// Caller must check a and b are u128.
+    fn add_u128_with_carry(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+
+    /// Input cells that each hold a value smaller than 2^per_item_bits,
+    /// Output
+    /// - the cell that contains the sum of the inputs
+    /// - the number of bits the sum is guaranteed to fit in, i.e.,
+    ///   per_item_bits + ceil(log2(n)) for n inputs
+    ///
+    /// Returns a synthesis error if the bound exceeds the capacity of the field,
+    /// since the sum could then wrap around the modulus.
+    /// Caller must check each input is smaller than 2^per_item_bits.
+    fn sum_with_bound(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        cells: &[AssignedCell<F, F>],
+        per_item_bits: usize,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, usize), Error>;
+
+    /// Input two field element cells a and b,
+    /// Output a bit cell that is 1 iff a < b as integers.
+    ///
+    /// Both inputs are canonically decomposed into u128 limbs, which are
+    /// then compared with a borrow chain.
+    fn is_less_than(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Input non-zero cells a_1, ..., a_n,
+    /// Output the cells of their inverses, with a single inversion.
+    ///
+    /// With the prefix products c_i = a_1 * ... * a_i, one inverse
+    /// inv = 1 / c_n is witnessed and checked by c_n * inv = 1; then
+    /// from i = n down to 1
+    ///  - 1 / a_i = inv * c_{i-1}
+    ///  - inv     = inv * a_i
+    ///
+    /// Returns a synthesis error if any input is zero.
+    fn batch_invert(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        cells: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>;
+
+    /// Input a field element cell,
+    /// Output a bit cell that is 1 iff the canonical representation of a is even.
+    ///
+    /// The bit is the complement of the lowest bit of the canonical decomposition;
+    /// without canonicity a + p would decompose with the opposite parity, as p is odd.
+    fn is_even(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Input three assigned cells a, b and c,
+    /// Assert a + b = c.
+    ///
+    /// The cells are copied into an add gate; no new value is assigned.
+    fn assert_sum(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Input three assigned cells a, b and c,
+    /// Assert a * b = c.
+    ///
+    /// The cells are copied into a mul gate; no new value is assigned.
+    fn assert_product(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Input a cell a of a signed encoding, and a bound in bits,
+    /// Output the cells (|a|, sign) such that
+    /// - a = |a| if sign == 0
+    /// - a = p - |a| if sign == 1
+    ///
+    /// |a| is range checked to `bound_bits` bits, and the sign of 0 is 0.
+    /// Since 2^(bound_bits + 1) < p, |a| and p - |a| are not both in range,
+    /// so the output is unique.
+    /// Returns a synthesis error if bound_bits is 0 or larger than the
+    /// bit length of the modulus minus 2.
+    fn abs_and_sign(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        bound_bits: usize,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+
+    /// Input three assigned cells a, b and c,
+    /// Output the cell of d = a + b + c.
+    ///
+    ///  index  |  a  |  b
+    ///  -------|-----|-----
+    ///         |  a  |  b
+    ///         |  c  |  d
+    fn add3(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Input 256 little endian bit cells,
+    /// Output the cells of the two u128 limbs (low, high) they encode.
+    ///
+    /// The bits are copied into the same partial decompose blocks as in
+    /// `decompose_scalar`, which also re-enforces they are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn compose_u128_pair(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+
+    /// Input a cell a and a number of bits n,
+    /// Output the little endian chunks of a, each of `range_width` bits,
+    /// i.e., bytes with the default width.
+    ///
+    /// Each chunk is looked up in the range table, which must be loaded via
+    /// `ECChip::load_range_table`, and a running sum recomposes a:
+    ///
+    /// |index  |     a     |     b     |
+    /// |-------|-----------|-----------|
+    /// |offset |  chunk_0  |  acc_0 = a|
+    /// |       |    ...    |    ...    |
+    /// |       | chunk_m-1 |  acc_m-1  |
+    /// |       |     0     |  acc_m = 0|
+    ///
+    /// where acc_i = acc_{i+1} * 2^range_width + chunk_i. If n is not a multiple of
+    /// the width, the final partial chunk of r bits is shifted by width - r bits
+    /// and looked up again, so a < 2^n.
+    ///
+    /// Returns a synthesis error if n is 0 or not smaller than the field size,
+    /// or if a does not fit in n bits.
+    fn decompose_bytes_lookup(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        num_bits: usize,
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>;
+
+    /// Enforces a < 2^n, via the chunks of `decompose_bytes_lookup`.
+    ///
+    /// Returns a synthesis error if n is 0 or not smaller than the field size,
+    /// or if a does not fit in n bits.
+    fn range_check(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        num_bits: usize,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Enforces each cell is a bit, 4 bits per partial decompose block,
+    /// i.e., 3 rows per 4 bits.
+    ///
+    /// The cells are copied into the bit positions of `assign_bit_blocks`; the
+    /// accumulated value is ignored. If the number of cells is not a multiple of 4,
+    /// the remaining bit positions are fixed to the constant 0.
+    fn enforce_booleans(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        cells: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Input bit cells, e.g., as returned by `decompose_scalar`,
+    /// Output the cell of their hamming weight, i.e., the number of set bits.
+    ///
+    /// The weight is the `sum_with_bound` of the bits.
+    /// Caller must check the bits are binary.
+    fn hamming_weight(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Enforces a weight cell is at most k.
+    ///
+    /// k - weight is decomposed into the bits of k, rounded up to a multiple of 4,
+    /// which fails if the weight exceeds k since the difference wraps around the modulus.
+    fn enforce_weight_at_most(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        weight: &AssignedCell<F, F>,
+        k: usize,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Input the value of the challenge of a chip configured via
+    /// `ECChip::configure_second_phase`, i.e., `Layouter::get_challenge(config.challenge())`,
+    /// Output a cell constrained to the challenge, e.g., to be decomposed via `decompose_field`.
+    ///
+    /// The value is unknown while the first phase is assigned; the chip columns
+    /// are second phase, so the cells derived from it are discarded in that phase.
+    /// Returns a synthesis error if the chip is not configured with a challenge.
+    fn load_challenge(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        r: Value<F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
 }
 
 impl<C, F> ArithOps<F> for ECChip<C, F>
@@ -157,6 +476,29 @@ where
         res
     }
 
+    /// Copy two assigned cells into an add gate and return the sum.
+    /// Unlike `add`, the operands are copy constrained to the input cells.
+    fn add_cells(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // |         add |   2  |       0      | 0  | 1  | 0  | a1 = a0 + b0
+        config.q2.enable(region, *offset)?;
+        a.copy_advice(|| "a", region, config.a, *offset)?;
+        b.copy_advice(|| "b", region, config.b, *offset)?;
+
+        let c = a.value().copied() + b.value().copied();
+        let res = region.assign_advice(|| "a + b", config.a, *offset + 1, || c)?;
+        region.assign_advice(|| "pad", config.b, *offset + 1, || Value::known(F::ZERO))?;
+
+        *offset += 2;
+        Ok(res)
+    }
+
     // Multiply two cells and return the product
     fn mul(
         &self,
@@ -189,10 +531,35 @@ where
         res
     }
 
+    /// Copy two assigned cells into a mul gate and return the product.
+    /// Unlike `mul`, the operands are copy constrained to the input cells.
+    fn mul_cells(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // |         mul |   2  |       0      | 0  | 0  | 1  | a1 = a0 * b0
+        config.q3.enable(region, *offset)?;
+        a.copy_advice(|| "a", region, config.a, *offset)?;
+        b.copy_advice(|| "b", region, config.b, *offset)?;
+
+        let c = a.value().copied() * b.value().copied();
+        let res = region.assign_advice(|| "a * b", config.a, *offset + 1, || c)?;
+        region.assign_advice(|| "pad", config.b, *offset + 1, || Value::known(F::ZERO))?;
+
+        *offset += 2;
+        Ok(res)
+    }
+
     /// Input x1, y1, x2, y2, x3, y3
     /// Assert that
-    /// - x3 = x1 + 2y1 + 4x2 + 8y2 + 16y3
+    /// - y3 = x1 + 2y1 + 4x2 + 8y2 + 16x3
     /// - x1, y1, x2, y2 are all binary
+    ///
+    /// Returns a synthesis error if the input length is not 6.
     fn partial_bit_decomp(
         &self,
         region: &mut Region<F>,
@@ -200,38 +567,31 @@ where
         inputs: &[F],
         offset: &mut usize,
     ) -> Result<Vec<AssignedCell<F, F>>, Error> {
-        assert_eq!(inputs.len(), 6, "input length is not 6");
+        let inputs: &[F; 6] = inputs.try_into().map_err(|_| Error::Synthesis)?;
+        let res = self.partial_bit_decomp_array(region, config, inputs, offset)?;
+        Ok(res.to_vec())
+    }
 
-        let mut res = vec![];
-        // |     partial |   3  |       0      | 1  | 0  | 0  | y3 = x1 + y1 + x2 + y2 + x3 and
+    /// As `partial_bit_decomp`, for exactly 6 inputs, with the length
+    /// enforced by the type.
+    fn partial_bit_decomp_array(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        inputs: &[F; 6],
+        offset: &mut usize,
+    ) -> Result<[AssignedCell<F, F>; 6], Error> {
+        // |     partial |   3  |       0      | 1  | 0  | 0  | y3 = x1 + 2y1 + 4x2 + 8y2 + 16x3 and
         // |   decompose |      |              |    |    |    | x1, y1, x2, y2 are all binary
         config.q1.enable(region, *offset)?;
-        res.push(region.assign_advice(|| "x0", config.a, *offset, || Value::known(inputs[0]))?);
-        res.push(region.assign_advice(|| "y0", config.b, *offset, || Value::known(inputs[1]))?);
-        res.push(region.assign_advice(
-            || "x1",
-            config.a,
-            *offset + 1,
-            || Value::known(inputs[2]),
-        )?);
-        res.push(region.assign_advice(
-            || "y1",
-            config.b,
-            *offset + 1,
-            || Value::known(inputs[3]),
-        )?);
-        res.push(region.assign_advice(
-            || "x2",
-            config.a,
-            *offset + 2,
-            || Value::known(inputs[4]),
-        )?);
-        res.push(region.assign_advice(
-            || "y2",
-            config.b,
-            *offset + 2,
-            || Value::known(inputs[5]),
-        )?);
+        let res = [
+            region.assign_advice(|| "x0", config.a, *offset, || Value::known(inputs[0]))?,
+            region.assign_advice(|| "y0", config.b, *offset, || Value::known(inputs[1]))?,
+            region.assign_advice(|| "x1", config.a, *offset + 1, || Value::known(inputs[2]))?,
+            region.assign_advice(|| "y1", config.b, *offset + 1, || Value::known(inputs[3]))?,
+            region.assign_advice(|| "x2", config.a, *offset + 2, || Value::known(inputs[4]))?,
+            region.assign_advice(|| "y2", config.b, *offset + 2, || Value::known(inputs[5]))?,
+        ];
 
         *offset += 3;
         Ok(res)
@@ -248,29 +608,890 @@ where
         input: &u128,
         offset: &mut usize,
     ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
-        let input_le_vec = crate::util::decompose_u128(input);
-        let input_field_vec = input_le_vec
+        let input_le_vec = crate::util::decompose_u128(input)
             .iter()
-            .rev()
             .map(|&x| F::from(x))
             .collect::<Vec<_>>();
 
+        let (res, acc) = self.assign_bit_blocks(region, config, &input_le_vec, offset)?;
+
+        // sanity check
+        let acc_value = leak(&acc.value());
+        debug_assert_eq!(acc_value, F::from_u128(*input));
+        if acc_value != F::from_u128(*input) {
+            return Err(Error::Synthesis);
+        }
+
+        Ok((res, acc))
+    }
+
+    /// Input a field element cell,
+    /// Output its 256 bit decomposition cells in little endian.
+    ///
+    /// The decomposition is canonical, i.e., the bits encode an integer
+    /// that is smaller than the modulus.
+    /// The layout does not depend on the value of a.
+    fn decompose_field(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let (bits, _high_cell, _low_cell) =
+            self.decompose_field_limbs(region, config, a, offset)?;
+        Ok(bits)
+    }
+
+    /// Clamp 256 little endian scalar bits as in X25519:
+    /// bits 0, 1, 2 and 255 are cleared, and bit 254 is set.
+    ///
+    /// The overridden bits are replaced by constant cells, and all resulting
+    /// bits are enforced to be binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn clamp_scalar_x25519(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        scalar_bits: &mut [AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        if scalar_bits.len() != 256 {
+            return Err(Error::Synthesis);
+        }
+
+        for i in [0, 1, 2, 255] {
+            scalar_bits[i] = self.load_constant(region, config, &F::ZERO, offset)?;
+        }
+        scalar_bits[254] = self.load_constant(region, config, &F::ONE, offset)?;
+
+        // enforce booleanity, 4 bits per partial decompose block
+        self.compose_bits(region, config, scalar_bits, offset)?;
+
+        Ok(())
+    }
+
+    /// Input two cells a and b that hold u128 values,
+    /// Output
+    /// - the cell that contains sum = (a + b) mod 2^128
+    /// - the carry bit
+    ///
+    /// Asserts that a + b = sum + carry * 2^128, that the carry is binary
+    /// and that sum fits in 128 bits.
+    /// Caller must check a and b are u128.
+    fn add_u128_with_carry(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let two_to_128 = F::from_u128(1 << 127) * F::from(2);
+
+        let (_, a_u128) = field_decompose_u128(&leak(&a.value()));
+        let (_, b_u128) = field_decompose_u128(&leak(&b.value()));
+        let (sum, carry) = a_u128.overflowing_add(b_u128);
+
+        // sum is a u128
+        let (_, sum_cell) = self.decompose_u128(region, config, &sum, offset)?;
+
+        // carry is a bit
+        let carry_cell = self.load_private_field(region, config, &F::from(carry as u64), offset)?;
+        self.enforce_bit(region, config, &carry_cell, offset)?;
+
+        // a + b = sum + carry * 2^128
+        let lhs = self.add_cells(region, config, a, b, offset)?;
+        let rhs = self.mul_const(region, config, &carry_cell, &two_to_128, offset)?;
+        let rhs = self.add_cells(region, config, &sum_cell, &rhs, offset)?;
+        region.constrain_equal(lhs.cell(), rhs.cell())?;
+
+        Ok((sum_cell, carry_cell))
+    }
+
+    /// Input cells that each hold a value smaller than 2^per_item_bits,
+    /// Output
+    /// - the cell that contains the sum of the inputs
+    /// - the number of bits the sum is guaranteed to fit in, i.e.,
+    ///   per_item_bits + ceil(log2(n)) for n inputs
+    ///
+    /// Returns a synthesis error if the bound exceeds the capacity of the field,
+    /// since the sum could then wrap around the modulus.
+    /// Caller must check each input is smaller than 2^per_item_bits.
+    fn sum_with_bound(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        cells: &[AssignedCell<F, F>],
+        per_item_bits: usize,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, usize), Error> {
+        if cells.is_empty() {
+            let zero = self.load_constant(region, config, &F::ZERO, offset)?;
+            return Ok((zero, 0));
+        }
+
+        let log_n = (usize::BITS - (cells.len() - 1).leading_zeros()) as usize;
+        let bound = per_item_bits + log_n;
+        if bound > F::CAPACITY as usize {
+            return Err(Error::Synthesis);
+        }
+
+        let mut sum = cells[0].clone();
+        for cell in cells.iter().skip(1) {
+            sum = self.add_cells(region, config, &sum, cell, offset)?;
+        }
+
+        Ok((sum, bound))
+    }
+
+    /// Input two field element cells a and b,
+    /// Output a bit cell that is 1 iff a < b as integers.
+    ///
+    /// Both inputs are canonically decomposed into u128 limbs, which are
+    /// then compared with a borrow chain.
+    fn is_less_than(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (_, a_high, a_low) = self.decompose_field_limbs(region, config, a, offset)?;
+        let (_, b_high, b_low) = self.decompose_field_limbs(region, config, b, offset)?;
+        self.limbs_less_than(region, config, &[a_low, a_high], &[b_low, b_high], offset)
+    }
+
+    /// Input non-zero cells a_1, ..., a_n,
+    /// Output the cells of their inverses, with a single inversion.
+    ///
+    /// With the prefix products c_i = a_1 * ... * a_i, one inverse
+    /// inv = 1 / c_n is witnessed and checked by c_n * inv = 1; then
+    /// from i = n down to 1
+    ///  - 1 / a_i = inv * c_{i-1}
+    ///  - inv     = inv * a_i
+    ///
+    /// Returns a synthesis error if any input is zero.
+    fn batch_invert(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        cells: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if cells.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // c_i = a_1 * ... * a_i
+        let mut prefix = vec![cells[0].clone()];
+        for a in cells.iter().skip(1) {
+            let c = self.mul_cells(region, config, prefix.last().unwrap(), a, offset)?;
+            prefix.push(c);
+        }
+
+        // inv = 1 / c_n
+        let c_n = prefix.last().unwrap();
+        let inv_value = leak(&c_n.value()).invert();
+        // the values are unknown when the floor planner measures the region
+        c_n.value()
+            .error_if_known_and(|_| bool::from(inv_value.is_none()))?;
+        let inv_value = inv_value.unwrap_or(F::ZERO);
+        let mut inv = self.load_private_field(region, config, &inv_value, offset)?;
+        let check = self.mul_cells(region, config, prefix.last().unwrap(), &inv, offset)?;
+        region.constrain_constant(check.cell(), F::ONE)?;
+
+        let mut res = vec![];
+        for i in (1..cells.len()).rev() {
+            res.push(self.mul_cells(region, config, &inv, &prefix[i - 1], offset)?);
+            inv = self.mul_cells(region, config, &inv, &cells[i], offset)?;
+        }
+        res.push(inv);
+        res.reverse();
+
+        Ok(res)
+    }
+
+    /// Input a field element cell,
+    /// Output a bit cell that is 1 iff the canonical representation of a is even.
+    ///
+    /// The bit is the complement of the lowest bit of the canonical decomposition;
+    /// without canonicity a + p would decompose with the opposite parity, as p is odd.
+    fn is_even(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let bits = self.decompose_field(region, config, a, offset)?;
+
+        // 1 - bit_0
+        let neg_bit = self.mul_const(region, config, &bits[0], &-F::ONE, offset)?;
+        self.add_const(region, config, &neg_bit, &F::ONE, offset)
+    }
+
+    /// Input three assigned cells a, b and c,
+    /// Assert a + b = c.
+    ///
+    /// The cells are copied into an add gate; no new value is assigned.
+    fn assert_sum(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        // |         add |   2  |       0      | 0  | 1  | 0  | a1 = a0 + b0
+        config.q2.enable(region, *offset)?;
+        a.copy_advice(|| "a", region, config.a, *offset)?;
+        b.copy_advice(|| "b", region, config.b, *offset)?;
+        c.copy_advice(|| "a + b", region, config.a, *offset + 1)?;
+        region.assign_advice(|| "pad", config.b, *offset + 1, || Value::known(F::ZERO))?;
+
+        *offset += 2;
+        Ok(())
+    }
+
+    /// Input three assigned cells a, b and c,
+    /// Assert a * b = c.
+    ///
+    /// The cells are copied into a mul gate; no new value is assigned.
+    fn assert_product(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        // |         mul |   2  |       0      | 0  | 0  | 1  | a1 = a0 * b0
+        config.q3.enable(region, *offset)?;
+        a.copy_advice(|| "a", region, config.a, *offset)?;
+        b.copy_advice(|| "b", region, config.b, *offset)?;
+        c.copy_advice(|| "a * b", region, config.a, *offset + 1)?;
+        region.assign_advice(|| "pad", config.b, *offset + 1, || Value::known(F::ZERO))?;
+
+        *offset += 2;
+        Ok(())
+    }
+
+    /// Input a cell a of a signed encoding, and a bound in bits,
+    /// Output the cells (|a|, sign) such that
+    /// - a = |a| if sign == 0
+    /// - a = p - |a| if sign == 1
+    ///
+    /// |a| is range checked to `bound_bits` bits, and the sign of 0 is 0.
+    /// Since 2^(bound_bits + 1) < p, |a| and p - |a| are not both in range,
+    /// so the output is unique.
+    /// Returns a synthesis error if bound_bits is 0 or larger than the
+    /// bit length of the modulus minus 2.
+    fn abs_and_sign(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        bound_bits: usize,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        if bound_bits == 0 || bound_bits + 2 > F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        // v < 2^bound_bits
+        let in_range = |v: &F| {
+            let (high, low) = field_decompose_u128(v);
+            if bound_bits >= 128 {
+                high >> (bound_bits - 128) == 0
+            } else {
+                high == 0 && low >> bound_bits == 0
+            }
+        };
+        let a_value = leak(&a.value());
+        let (abs_value, sign_value) = if in_range(&a_value) {
+            (a_value, F::ZERO)
+        } else {
+            (-a_value, F::ONE)
+        };
+        let [abs, sign] =
+            self.load_two_private_fields(region, config, &abs_value, &sign_value, offset)?;
+        self.enforce_bit(region, config, &sign, offset)?;
+
+        // |a| < 2^bound_bits, with the bits rounded up to a multiple of 4
+        let (high, low) = field_decompose_u128(&abs_value);
+        let num_bits = (bound_bits + 3) / 4 * 4;
+        let abs_bits = (0..num_bits)
+            .map(|i| {
+                let limb = if i < 128 { low >> i } else { high >> (i - 128) };
+                F::from((limb & 1) as u64)
+            })
+            .collect::<Vec<_>>();
+        let (abs_bits, acc) = self.assign_bit_blocks(region, config, &abs_bits, offset)?;
+        region.constrain_equal(abs.cell(), acc.cell())?;
+        for bit in abs_bits.iter().skip(bound_bits) {
+            region.constrain_constant(bit.cell(), F::ZERO)?;
+        }
+
+        // a = |a| - 2 * sign * |a|
+        let t = self.mul_cells(region, config, &sign, &abs, offset)?;
+        let t = self.mul_const(region, config, &t, &-F::from(2), offset)?;
+        let a_rec = self.add_cells(region, config, &abs, &t, offset)?;
+        region.constrain_equal(a.cell(), a_rec.cell())?;
+
+        // the sign of 0 is 0
+        let abs_is_zero = self.is_zero(region, config, &abs, offset)?;
+        let t = self.mul_cells(region, config, &abs_is_zero, &sign, offset)?;
+        region.constrain_constant(t.cell(), F::ZERO)?;
+
+        Ok((abs, sign))
+    }
+
+    /// Input three assigned cells a, b and c,
+    /// Output the cell of d = a + b + c.
+    ///
+    ///  index  |  a  |  b
+    ///  -------|-----|-----
+    ///         |  a  |  b
+    ///         |  c  |  d
+    fn add3(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        config.q_add3.enable(region, *offset)?;
+        a.copy_advice(|| "a", region, config.a, *offset)?;
+        b.copy_advice(|| "b", region, config.b, *offset)?;
+        c.copy_advice(|| "c", region, config.a, *offset + 1)?;
+
+        let d = a.value().copied() + b.value().copied() + c.value().copied();
+        let res = region.assign_advice(|| "a + b + c", config.b, *offset + 1, || d)?;
+
+        *offset += 2;
+        Ok(res)
+    }
+
+    /// Input 256 little endian bit cells,
+    /// Output the cells of the two u128 limbs (low, high) they encode.
+    ///
+    /// The bits are copied into the same partial decompose blocks as in
+    /// `decompose_scalar`, which also re-enforces they are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn compose_u128_pair(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let bit_values = bits.iter().map(|b| leak(&b.value())).collect::<Vec<_>>();
+        let (bit_cells, low, high) =
+            self.assign_u128_pair_blocks(region, config, &bit_values, offset)?;
+        for (b, b_copied) in bits.iter().zip(bit_cells.iter()) {
+            region.constrain_equal(b.cell(), b_copied.cell())?;
+        }
+        Ok((low, high))
+    }
+
+    /// Input a cell a and a number of bits n,
+    /// Output the little endian chunks of a, each of `range_width` bits,
+    /// i.e., bytes with the default width.
+    ///
+    /// Each chunk is looked up in the range table, which must be loaded via
+    /// `ECChip::load_range_table`, and a running sum recomposes a:
+    ///
+    /// |index  |     a     |     b     |
+    /// |-------|-----------|-----------|
+    /// |offset |  chunk_0  |  acc_0 = a|
+    /// |       |    ...    |    ...    |
+    /// |       | chunk_m-1 |  acc_m-1  |
+    /// |       |     0     |  acc_m = 0|
+    ///
+    /// where acc_i = acc_{i+1} * 2^range_width + chunk_i. If n is not a multiple of
+    /// the width, the final partial chunk of r bits is shifted by width - r bits
+    /// and looked up again, so a < 2^n.
+    ///
+    /// Returns a synthesis error if n is 0 or not smaller than the field size,
+    /// or if a does not fit in n bits.
+    fn decompose_bytes_lookup(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        num_bits: usize,
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if num_bits == 0 || num_bits >= F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+        let width = config.range_width;
+        let num_chunks = (num_bits + width - 1) / width;
+
+        let (high, low) = field_decompose_u128(&leak(&a.value()));
+        let bit = |i: usize| -> u64 {
+            let limb = match i {
+                0..=127 => low >> i,
+                128..=255 => high >> (i - 128),
+                _ => 0,
+            };
+            (limb & 1) as u64
+        };
+        a.value()
+            .error_if_known_and(|_| (num_bits..256).any(|i| bit(i) == 1))?;
+
+        let chunks = (0..num_chunks)
+            .map(|i| F::from((0..width).map(|j| bit(i * width + j) << j).sum::<u64>()))
+            .collect::<Vec<_>>();
+        let two_to_width = F::from(1 << width);
+        let mut accs = vec![F::ZERO; num_chunks + 1];
+        for i in (0..num_chunks).rev() {
+            accs[i] = accs[i + 1] * two_to_width + chunks[i];
+        }
+
+        let mut res = vec![];
+        for (i, (chunk, acc)) in chunks.iter().zip(accs.iter()).enumerate() {
+            config.q_range.enable(region, *offset)?;
+            config.q_range_acc.enable(region, *offset)?;
+            res.push(region.assign_advice(
+                || "chunk",
+                config.a,
+                *offset,
+                || Value::known(*chunk),
+            )?);
+            let acc = region.assign_advice(|| "acc", config.b, *offset, || Value::known(*acc))?;
+            if i == 0 {
+                region.constrain_equal(a.cell(), acc.cell())?;
+            }
+            *offset += 1;
+        }
+
+        // the running sum ends at 0
+        region.assign_advice(|| "pad", config.a, *offset, || Value::known(F::ZERO))?;
+        region.assign_advice_from_constant(|| "acc", config.b, *offset, F::ZERO)?;
+        *offset += 1;
+
+        // the final partial chunk of r bits is < 2^r iff chunk * 2^(width - r) is in the table
+        let r = num_bits - (num_chunks - 1) * width;
+        if r < width {
+            let shift = F::from(1 << (width - r));
+            self.mul_const(region, config, &res[num_chunks - 1], &shift, offset)?;
+            config.q_range.enable(region, *offset - 1)?;
+        }
+
+        Ok(res)
+    }
+
+    /// Enforces a < 2^n, via the chunks of `decompose_bytes_lookup`.
+    ///
+    /// Returns a synthesis error if n is 0 or not smaller than the field size,
+    /// or if a does not fit in n bits.
+    fn range_check(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        a: &AssignedCell<F, F>,
+        num_bits: usize,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        self.decompose_bytes_lookup(region, config, a, num_bits, offset)?;
+        Ok(())
+    }
+
+    /// Enforces each cell is a bit, 4 bits per partial decompose block,
+    /// i.e., 3 rows per 4 bits.
+    ///
+    /// The cells are copied into the bit positions of `assign_bit_blocks`; the
+    /// accumulated value is ignored. If the number of cells is not a multiple of 4,
+    /// the remaining bit positions are fixed to the constant 0.
+    fn enforce_booleans(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        cells: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        if cells.is_empty() {
+            return Ok(());
+        }
+
+        let num_bits = (cells.len() + 3) / 4 * 4;
+        let bits = (0..num_bits)
+            .map(|i| cells.get(i).map(|c| leak(&c.value())).unwrap_or(F::ZERO))
+            .collect::<Vec<_>>();
+        let (bit_cells, _acc) = self.assign_bit_blocks(region, config, &bits, offset)?;
+        for (i, bit) in bit_cells.iter().enumerate() {
+            match cells.get(i) {
+                Some(cell) => region.constrain_equal(cell.cell(), bit.cell())?,
+                None => region.constrain_constant(bit.cell(), F::ZERO)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Input bit cells, e.g., as returned by `decompose_scalar`,
+    /// Output the cell of their hamming weight, i.e., the number of set bits.
+    ///
+    /// The weight is the `sum_with_bound` of the bits.
+    /// Caller must check the bits are binary.
+    fn hamming_weight(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (weight, _bound) = self.sum_with_bound(region, config, bits, 1, offset)?;
+        Ok(weight)
+    }
+
+    /// Enforces a weight cell is at most k.
+    ///
+    /// k - weight is decomposed into the bits of k, rounded up to a multiple of 4,
+    /// which fails if the weight exceeds k since the difference wraps around the modulus.
+    fn enforce_weight_at_most(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        weight: &AssignedCell<F, F>,
+        k: usize,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let neg_weight = self.mul_const(region, config, weight, &-F::ONE, offset)?;
+        let diff = self.add_const(region, config, &neg_weight, &F::from(k as u64), offset)?;
+
+        let num_bits = ((usize::BITS - k.leading_zeros()) as usize + 3) / 4 * 4;
+        let (_, diff_value) = field_decompose_u128(&leak(&diff.value()));
+        let bits = (0..num_bits.max(4))
+            .map(|i| F::from(((diff_value >> i) & 1) as u64))
+            .collect::<Vec<_>>();
+        let (_bits, acc) = self.assign_bit_blocks(region, config, &bits, offset)?;
+        region.constrain_equal(acc.cell(), diff.cell())?;
+
+        Ok(())
+    }
+
+    /// Input the value of the challenge of a chip configured via
+    /// `ECChip::configure_second_phase`, i.e., `Layouter::get_challenge(config.challenge())`,
+    /// Output a cell constrained to the challenge, e.g., to be decomposed via `decompose_field`.
+    ///
+    /// The value is unknown while the first phase is assigned; the chip columns
+    /// are second phase, so the cells derived from it are discarded in that phase.
+    /// Returns a synthesis error if the chip is not configured with a challenge.
+    fn load_challenge(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        r: Value<F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (_, q_challenge) = config.challenge.ok_or(Error::Synthesis)?;
+        q_challenge.enable(region, *offset)?;
+        let r = region.assign_advice(|| "challenge", config.a, *offset, || r)?;
+        region.assign_advice(|| "pad", config.b, *offset, || Value::known(F::ZERO))?;
+        *offset += 1;
+        Ok(r)
+    }
+}
+
+impl<C, F> ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Load a constant field element
+    pub(crate) fn load_constant(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        c: &F,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let res = region.assign_advice_from_constant(|| "constant", config.a, *offset, *c)?;
+        region.assign_advice(|| "pad", config.b, *offset, || Value::known(F::ZERO))?;
+
+        *offset += 1;
+        Ok(res)
+    }
+
+    /// Copy a cell into an add gate and return the sum with a constant
+    pub(crate) fn add_const(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        a: &AssignedCell<F, F>,
+        b: &F,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // |         add |   2  |       0      | 0  | 1  | 0  | a1 = a0 + b0
+        config.q2.enable(region, *offset)?;
+        a.copy_advice(|| "a", region, config.a, *offset)?;
+        region.assign_advice_from_constant(|| "b", config.b, *offset, *b)?;
+
+        let c = a.value().map(|a| *a + *b);
+        let res = region.assign_advice(|| "a + b", config.a, *offset + 1, || c)?;
+        region.assign_advice(|| "pad", config.b, *offset + 1, || Value::known(F::ZERO))?;
+
+        *offset += 2;
+        Ok(res)
+    }
+
+    /// Copy a cell into a mul gate and return the product with a constant
+    pub(crate) fn mul_const(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        a: &AssignedCell<F, F>,
+        b: &F,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // |         mul |   2  |       0      | 0  | 0  | 1  | a1 = a0 * b0
+        config.q3.enable(region, *offset)?;
+        a.copy_advice(|| "a", region, config.a, *offset)?;
+        region.assign_advice_from_constant(|| "b", config.b, *offset, *b)?;
+
+        let c = a.value().map(|a| *a * *b);
+        let res = region.assign_advice(|| "a * b", config.a, *offset + 1, || c)?;
+        region.assign_advice(|| "pad", config.b, *offset + 1, || Value::known(F::ZERO))?;
+
+        *offset += 2;
+        Ok(res)
+    }
+
+    /// Enforce the cell is either 0 or 1, via a * a = a
+    pub(crate) fn enforce_bit(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        a: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let a_squared = self.mul_cells(region, config, a, a, offset)?;
+        region.constrain_equal(a.cell(), a_squared.cell())
+    }
+
+    /// Input the cells of two u128 limbs (high, low) and a constant m = (m_high, m_low),
+    /// Assert that high * 2^128 + low <= m.
+    ///
+    /// Caller must check high and low are u128.
+    pub(crate) fn enforce_u256_le_const(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        high_cell: &AssignedCell<F, F>,
+        low_cell: &AssignedCell<F, F>,
+        m: &(u128, u128),
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let two_to_128 = F::from_u128(1 << 127) * F::from(2);
+        let (m_high, m_low) = *m;
+        let (_, high) = field_decompose_u128(&leak(&high_cell.value()));
+        let (_, low) = field_decompose_u128(&leak(&low_cell.value()));
+
+        // with a borrow bit, this is
+        //  - d_low  = m_low + borrow * 2^128 - low  is a u128
+        //  - d_high = m_high - borrow - high        is a u128
+        let borrow = low > m_low;
+        let d_low = m_low.wrapping_sub(low);
+        let d_high = m_high.wrapping_sub(high).wrapping_sub(borrow as u128);
+
+        let borrow_cell =
+            self.load_private_field(region, config, &F::from(borrow as u64), offset)?;
+        self.enforce_bit(region, config, &borrow_cell, offset)?;
+        let (_, d_low_cell) = self.decompose_u128(region, config, &d_low, offset)?;
+        let (_, d_high_cell) = self.decompose_u128(region, config, &d_high, offset)?;
+
+        // d_low + low = borrow * 2^128 + m_low
+        let lhs = self.add_cells(region, config, &d_low_cell, low_cell, offset)?;
+        let rhs = self.mul_const(region, config, &borrow_cell, &two_to_128, offset)?;
+        let rhs = self.add_const(region, config, &rhs, &F::from_u128(m_low), offset)?;
+        region.constrain_equal(lhs.cell(), rhs.cell())?;
+
+        // d_high + high + borrow = m_high
+        let lhs = self.add_cells(region, config, &d_high_cell, high_cell, offset)?;
+        let lhs = self.add_cells(region, config, &lhs, &borrow_cell, offset)?;
+        region.constrain_constant(lhs.cell(), F::from_u128(m_high))?;
+
+        Ok(())
+    }
+
+    /// Input a field element cell,
+    /// Output
+    /// - its 256 bit decomposition cells in little endian
+    /// - the cells of its u128 limbs (high, low)
+    ///
+    /// The decomposition is canonical, i.e., the limbs encode an integer
+    /// that is smaller than the modulus.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn decompose_field_limbs(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        a: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<
+        (
+            Vec<AssignedCell<F, F>>,
+            AssignedCell<F, F>,
+            AssignedCell<F, F>,
+        ),
+        Error,
+    > {
+        let two_to_128 = F::from_u128(1 << 127) * F::from(2);
+
+        let (high, low) = field_decompose_u128(&leak(&a.value()));
+        let (low_bits, low_cell) = self.decompose_u128(region, config, &low, offset)?;
+        let (high_bits, high_cell) = self.decompose_u128(region, config, &high, offset)?;
+
+        // a = high * 2^128 + low
+        let high_shifted = self.mul_const(region, config, &high_cell, &two_to_128, offset)?;
+        let a_rec = self.add_cells(region, config, &high_shifted, &low_cell, offset)?;
+        region.constrain_equal(a.cell(), a_rec.cell())?;
+
+        // canonicity: high * 2^128 + low <= p - 1
+        self.enforce_u256_le_const(
+            region,
+            config,
+            &high_cell,
+            &low_cell,
+            &field_decompose_u128(&-F::ONE),
+            offset,
+        )?;
+
+        let bits = [low_bits.as_slice(), high_bits.as_slice()].concat();
+        Ok((bits, high_cell, low_cell))
+    }
+
+    /// Input the cells of the u128 limbs of two integers a and b in little endian,
+    /// Output a bit cell that is 1 iff a < b.
+    ///
+    /// With a borrow chain, for each limb i
+    ///  a_i + borrow_i * 2^128 = d_i + b_i + borrow_{i-1}
+    /// where d_i is a u128, borrow_i is binary and borrow_{-1} = 0;
+    /// a < b iff the last borrow is 1.
+    ///
+    /// Caller must check all limbs are u128.
+    /// Returns a synthesis error if the numbers of limbs are different or zero.
+    pub(crate) fn limbs_less_than(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        a: &[AssignedCell<F, F>],
+        b: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if a.is_empty() || a.len() != b.len() {
+            return Err(Error::Synthesis);
+        }
+        let two_to_128 = F::from_u128(1 << 127) * F::from(2);
+
+        let mut borrow_cell: Option<AssignedCell<F, F>> = None;
+        for (a_i, b_i) in a.iter().zip(b.iter()) {
+            let (_, a_value) = field_decompose_u128(&leak(&a_i.value()));
+            let (_, b_value) = field_decompose_u128(&leak(&b_i.value()));
+            let prev_borrow = borrow_cell
+                .as_ref()
+                .map(|c| leak(&c.value()) == F::ONE)
+                .unwrap_or(false);
+
+            let (d, borrow_1) = a_value.overflowing_sub(b_value);
+            let (d, borrow_2) = d.overflowing_sub(prev_borrow as u128);
+            let borrow = borrow_1 || borrow_2;
+
+            let new_borrow_cell =
+                self.load_private_field(region, config, &F::from(borrow as u64), offset)?;
+            self.enforce_bit(region, config, &new_borrow_cell, offset)?;
+            let (_, d_cell) = self.decompose_u128(region, config, &d, offset)?;
+
+            // a_i + borrow_i * 2^128 = d_i + b_i + borrow_{i-1}
+            let lhs = self.mul_const(region, config, &new_borrow_cell, &two_to_128, offset)?;
+            let lhs = self.add_cells(region, config, a_i, &lhs, offset)?;
+            let rhs = self.add_cells(region, config, &d_cell, b_i, offset)?;
+            let rhs = match &borrow_cell {
+                Some(prev) => self.add_cells(region, config, &rhs, prev, offset)?,
+                None => rhs,
+            };
+            region.constrain_equal(lhs.cell(), rhs.cell())?;
+
+            borrow_cell = Some(new_borrow_cell);
+        }
+
+        Ok(borrow_cell.unwrap())
+    }
+
+    /// Input a cell a,
+    /// Output a bit cell that is 1 iff a == 0.
+    ///
+    /// With a witness inv that is the inverse of a, or 0 if a == 0,
+    ///  - out = 1 - a * inv
+    ///  - a * out = 0
+    pub(crate) fn is_zero(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        a: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let inv = leak(&a.value()).invert().unwrap_or(F::ZERO);
+        let inv = self.load_private_field(region, config, &inv, offset)?;
+
+        let a_inv = self.mul_cells(region, config, a, &inv, offset)?;
+        let out = self.mul_const(region, config, &a_inv, &-F::ONE, offset)?;
+        let out = self.add_const(region, config, &out, &F::ONE, offset)?;
+
+        let a_out = self.mul_cells(region, config, a, &out, offset)?;
+        region.constrain_constant(a_out.cell(), F::ZERO)?;
+
+        Ok(out)
+    }
+
+    /// Input little endian bits, the length of which is a multiple of 4.
+    /// Output
+    /// - the bit cells in little endian
+    /// - the cell that contains the accumulated value
+    ///
+    /// The bits are absorbed 4 at a time, most significant first, via partial decompose:
+    ///
+    /// |index  |  a   |  b   |
+    /// |-------|------|------|
+    /// |       |  b0  |  b1  |
+    /// |       |  b2  |  b3  |
+    /// |offset | prev |  acc |
+    ///
+    /// where acc = b0 + 2b1 + 4b2 + 8b3 + 16prev. The first prev is fixed to 0
+    /// and each prev is copied from the acc of the previous block.
+    pub(crate) fn assign_bit_blocks(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        bits: &[F],
+        offset: &mut usize,
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
+        // the length must be a positive multiple of 4
+        if bits.is_empty() || bits.len() % 4 != 0 {
+            return Err(Error::Synthesis);
+        }
+
         let two = F::from(2);
         let four = F::from(4);
         let eight = F::from(8);
         let sixteen = F::from(16);
 
-        let mut acc;
         let mut prev_acc = F::ZERO;
+        let mut prev_acc_cell: Option<AssignedCell<F, F>> = None;
 
         let mut res = vec![];
-        let mut acc_cells = vec![];
-        // we assert the decomposition via 32 calls of partial decomp
-        // each call we absorb 4 bits
-        for i in 0..32 {
-            // |     partial |   3  |       0      | 1  | 0  | 0  | y3 = x1 + y1 + x2 + y2 + x3 and
+        // each block absorbs 4 bits, starting from the most significant ones
+        for chunk in bits.chunks(4).rev() {
+            // |     partial |   3  |       0      | 1  | 0  | 0  | y3 = x1 + 2y1 + 4x2 + 8y2 + 16x3 and
             // |   decompose |      |              |    |    |    | x1, y1, x2, y2 are all binary
-
             config.q1.enable(region, *offset)?;
 
             // allocate the four bits to be absorbed
@@ -278,67 +1499,103 @@ where
                 || "b2",
                 config.b,
                 *offset + 1,
-                || Value::known(input_field_vec[4 * i]),
+                || Value::known(chunk[3]),
             )?);
             res.push(region.assign_advice(
                 || "a2",
                 config.a,
                 *offset + 1,
-                || Value::known(input_field_vec[4 * i + 1]),
+                || Value::known(chunk[2]),
             )?);
             res.push(region.assign_advice(
                 || "b1",
                 config.b,
                 *offset,
-                || Value::known(input_field_vec[4 * i + 2]),
+                || Value::known(chunk[1]),
             )?);
             res.push(region.assign_advice(
                 || "a1",
                 config.a,
                 *offset,
-                || Value::known(input_field_vec[4 * i + 3]),
+                || Value::known(chunk[0]),
             )?);
 
             // compute the accumulated value
-            acc = input_field_vec[4 * i + 3]
-                + input_field_vec[4 * i + 2] * two
-                + input_field_vec[4 * i + 1] * four
-                + input_field_vec[4 * i] * eight
-                + prev_acc * sixteen;
+            let acc =
+                chunk[0] + chunk[1] * two + chunk[2] * four + chunk[3] * eight + prev_acc * sixteen;
 
             // assign accumulator
-            acc_cells.push(region.assign_advice(
-                || "a3",
-                config.a,
-                *offset + 2,
-                || Value::known(prev_acc),
-            )?);
-            acc_cells.push(region.assign_advice(
-                || "b3",
-                config.b,
-                *offset + 2,
-                || Value::known(acc),
-            )?);
+            let prev_acc_assigned =
+                region.assign_advice(|| "a3", config.a, *offset + 2, || Value::known(prev_acc))?;
+            match prev_acc_cell {
+                // the accumulation starts from 0
+                None => region.constrain_constant(prev_acc_assigned.cell(), F::ZERO)?,
+                // constrain the accumulators are well-formed
+                Some(ref cell) => region.constrain_equal(cell.cell(), prev_acc_assigned.cell())?,
+            }
+            prev_acc_cell =
+                Some(region.assign_advice(|| "b3", config.b, *offset + 2, || Value::known(acc))?);
+
             prev_acc = acc;
             *offset += 3;
         }
 
-        // sanity check
-        assert_eq!(prev_acc, F::from_u128(*input));
-
-        // constrain the accumulators are well-formed
-        for i in 0..31 {
-            region.constrain_equal(
-                // acc in the previous round
-                acc_cells[i * 2 + 1].cell(),
-                // prev_acc in the current round
-                acc_cells[(i + 1) * 2].cell(),
-            )?;
-        }
-
         // format the result in little endian format
         res.reverse();
 
-        Ok((res, acc_cells.last().unwrap().clone()))
+        Ok((res, prev_acc_cell.unwrap()))
+    }
+
+    /// Input 256 little endian bits,
+    /// Output
+    /// - the bit cells in little endian
+    /// - the cells of the two u128 limbs (low, high)
+    ///
+    /// The low limb is absorbed first, then the high limb, each via `assign_bit_blocks`.
+    /// Returns a synthesis error if the number of bits is not 256.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn assign_u128_pair_blocks(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        bits: &[F],
+        offset: &mut usize,
+    ) -> Result<
+        (
+            Vec<AssignedCell<F, F>>,
+            AssignedCell<F, F>,
+            AssignedCell<F, F>,
+        ),
+        Error,
+    > {
+        if bits.len() != 256 {
+            return Err(Error::Synthesis);
+        }
+
+        let (low_cells, low) = self.assign_bit_blocks(region, config, &bits[..128], offset)?;
+        let (high_cells, high) = self.assign_bit_blocks(region, config, &bits[128..], offset)?;
+        let res = [low_cells.as_slice(), high_cells.as_slice()].concat();
+
+        Ok((res, low, high))
+    }
+
+    /// Input little endian bit cells, the length of which is a multiple of 4.
+    /// Output the cell that contains the composed value.
+    ///
+    /// The bits are copied into the partial decompose blocks,
+    /// which also re-enforces they are binary.
+    pub(crate) fn compose_bits(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let bit_values = bits.iter().map(|b| leak(&b.value())).collect::<Vec<_>>();
+        let (bit_cells, acc) = self.assign_bit_blocks(region, config, &bit_values, offset)?;
+        for (b, b_copied) in bits.iter().zip(bit_cells.iter()) {
+            region.constrain_equal(b.cell(), b_copied.cell())?;
+        }
+        Ok(acc)
     }
 }
@@ -2,7 +2,10 @@ use ark_std::test_rng;
 use halo2_proofs::arithmetic::Field;
 use halo2_proofs::circuit::Layouter;
 use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::circuit::Value;
 use halo2_proofs::dev::MockProver;
+use halo2_proofs::dev::VerifyFailure;
+use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::plonk::Circuit;
 use halo2_proofs::plonk::ConstraintSystem;
 use halo2_proofs::plonk::Error;
@@ -13,6 +16,7 @@ use crate::arith_gates::ArithOps;
 use crate::chip::ECChip;
 use crate::config::ECConfig;
 use crate::ec_gates::NativeECOps;
+use crate::util::field_decompose_u128;
 
 #[derive(Default, Debug, Clone, Copy)]
 struct ArithTestCircuit {
@@ -83,6 +87,16 @@ impl Circuit<Fq> for ArithTestCircuit {
                     )?;
                 }
 
+                // unit test: partial bit decompose, with the length enforced by the type
+                {
+                    let _cells = field_chip.partial_bit_decomp_array(
+                        &mut region,
+                        &config,
+                        &self.f5,
+                        &mut offset,
+                    )?;
+                }
+
                 // unit test: decompose u128
                 {
                     let bytes = (0..16).map(|x| x).collect::<Vec<u8>>();
@@ -123,7 +137,7 @@ fn test_field_ops() {
         ];
         let circuit = ArithTestCircuit { f1, f2, f3, f4, f5 };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         prover.assert_satisfied();
     }
 
@@ -140,7 +154,7 @@ fn test_field_ops() {
         ];
         let circuit = ArithTestCircuit { f1, f2, f3, f4, f5 };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         assert!(prover.verify().is_err());
     }
     // error case: multiplication fails
@@ -156,7 +170,7 @@ fn test_field_ops() {
         ];
         let circuit = ArithTestCircuit { f1, f2, f3, f4, f5 };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         assert!(prover.verify().is_err());
     }
     // error case: not binary
@@ -171,7 +185,7 @@ fn test_field_ops() {
         ];
         let circuit = ArithTestCircuit { f1, f2, f3, f4, f5 };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         assert!(prover.verify().is_err());
     }
     // error case: sum not equal
@@ -186,7 +200,1437 @@ fn test_field_ops() {
         ];
         let circuit = ArithTestCircuit { f1, f2, f3, f4, f5 };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+struct DecomposeTestCircuit {
+    a: u128,
+    partial: Vec<Fq>, // partial bit decom inputs
+}
+
+impl Circuit<Fq> for DecomposeTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test decompose circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let _cells =
+                    field_chip.decompose_u128(&mut region, &config, &self.a, &mut offset)?;
+                let _cells = field_chip.partial_bit_decomp(
+                    &mut region,
+                    &config,
+                    &self.partial,
+                    &mut offset,
+                )?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_decompose_no_panic() {
+    let k = 10;
+
+    let partial = vec![
+        Fq::one(),
+        Fq::zero(),
+        Fq::zero(),
+        Fq::one(),
+        Fq::one(),
+        Fq::from(16 + 9),
+    ];
+
+    // the sanity checks pass on correct inputs
+    for a in [0, 1, u128::MAX >> 1, u128::MAX] {
+        let circuit = DecomposeTestCircuit {
+            a,
+            partial: partial.clone(),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong input length returns an error instead of panicking
+    {
+        let circuit = DecomposeTestCircuit {
+            a: 1,
+            partial: partial[..5].to_vec(),
+        };
+
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+
+    // error case: too many inputs
+    {
+        let circuit = DecomposeTestCircuit {
+            a: 1,
+            partial: [partial.clone(), vec![Fq::zero()]].concat(),
+        };
+
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct AddWithCarryTestCircuit {
+    a: u128,
+    b: u128,
+    sum: Fq,   // expected sum
+    carry: Fq, // expected carry
+}
+
+impl Circuit<Fq> for AddWithCarryTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test add with carry circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let (_, a) =
+                    field_chip.decompose_u128(&mut region, &config, &self.a, &mut offset)?;
+                let (_, b) =
+                    field_chip.decompose_u128(&mut region, &config, &self.b, &mut offset)?;
+                let (sum, carry) =
+                    field_chip.add_u128_with_carry(&mut region, &config, &a, &b, &mut offset)?;
+
+                let expected = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.sum,
+                    &self.carry,
+                    &mut offset,
+                )?;
+                region.constrain_equal(sum.cell(), expected[0].cell())?;
+                region.constrain_equal(carry.cell(), expected[1].cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_add_u128_with_carry() {
+    let k = 10;
+
+    let half = 1u128 << 127;
+    for (a, b, sum, carry) in [
+        (0, 0, Fq::zero(), Fq::zero()),
+        (0, u128::MAX, Fq::from_u128(u128::MAX), Fq::zero()),
+        (half - 1, half, Fq::from_u128(u128::MAX), Fq::zero()),
+        // wrap-around boundary
+        (half, half, Fq::zero(), Fq::one()),
+        (
+            u128::MAX,
+            u128::MAX,
+            Fq::from_u128(u128::MAX - 1),
+            Fq::one(),
+        ),
+    ] {
+        let circuit = AddWithCarryTestCircuit { a, b, sum, carry };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: the sum is reduced modulo 2^128
+    {
+        let circuit = AddWithCarryTestCircuit {
+            a: half,
+            b: half,
+            sum: Fq::from_u128(half) * Fq::from(2),
+            carry: Fq::zero(),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+struct SumWithBoundTestCircuit {
+    items: Vec<Fq>,
+    per_item_bits: usize,
+    sum: Fq,      // expected sum
+    bound: usize, // expected bound
+}
+
+impl Circuit<Fq> for SumWithBoundTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test sum with bound circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let items = self
+                    .items
+                    .iter()
+                    .map(|item| {
+                        field_chip.load_private_field(&mut region, &config, item, &mut offset)
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let (sum, bound) = field_chip.sum_with_bound(
+                    &mut region,
+                    &config,
+                    &items,
+                    self.per_item_bits,
+                    &mut offset,
+                )?;
+                assert_eq!(bound, self.bound);
+
+                let expected =
+                    field_chip.load_private_field(&mut region, &config, &self.sum, &mut offset)?;
+                region.constrain_equal(sum.cell(), expected.cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sum_with_bound() {
+    let k = 10;
+
+    // bytes
+    for n in [1, 2, 3, 255, 256, 257] {
+        let circuit = SumWithBoundTestCircuit {
+            items: vec![Fq::from(255); n],
+            per_item_bits: 8,
+            sum: Fq::from(255 * n as u64),
+            bound: 8 + (n as f64).log2().ceil() as usize,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // the capacity of Fq is 253 bits: 8 items of 250 bits are fine
+    let max_item = Fq::from(2).pow_vartime([250]) - Fq::one();
+    {
+        let circuit = SumWithBoundTestCircuit {
+            items: vec![max_item; 8],
+            per_item_bits: 250,
+            sum: max_item * Fq::from(8),
+            bound: 253,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: 9 items of 250 bits may overflow
+    {
+        let circuit = SumWithBoundTestCircuit {
+            items: vec![max_item; 9],
+            per_item_bits: 250,
+            sum: max_item * Fq::from(9),
+            bound: 254,
+        };
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+
+    // error case: wrong sum
+    {
+        let circuit = SumWithBoundTestCircuit {
+            items: vec![Fq::one(); 3],
+            per_item_bits: 1,
+            sum: Fq::from(4),
+            bound: 3,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct LessThanTestCircuit {
+    a: Fq,
+    b: Fq,
+    less_than: bool, // expected a < b
+}
+
+impl Circuit<Fq> for LessThanTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test less than circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let [a, b] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.a,
+                    &self.b,
+                    &mut offset,
+                )?;
+                let less_than =
+                    field_chip.is_less_than(&mut region, &config, &a, &b, &mut offset)?;
+                let expected = field_chip.load_private_field(
+                    &mut region,
+                    &config,
+                    &Fq::from(self.less_than as u64),
+                    &mut offset,
+                )?;
+                region.constrain_equal(less_than.cell(), expected.cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_is_less_than() {
+    let k = 11;
+
+    let two_to_128 = Fq::from_u128(1 << 127).double();
+    for (a, b, less_than) in [
+        (Fq::zero(), Fq::one(), true),
+        (Fq::one(), Fq::zero(), false),
+        (Fq::one(), Fq::one(), false),
+        // the low limbs differ the other way
+        (two_to_128, Fq::from(2), false),
+        (Fq::from(2), two_to_128, true),
+        // p - 1 is the largest integer
+        (-Fq::one(), Fq::zero(), false),
+        (Fq::zero(), -Fq::one(), true),
+    ] {
+        let circuit = LessThanTestCircuit { a, b, less_than };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong result
+    {
+        let circuit = LessThanTestCircuit {
+            a: Fq::zero(),
+            b: -Fq::one(),
+            less_than: false,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         assert!(prover.verify().is_err());
     }
 }
+
+#[derive(Default, Debug, Clone, Copy)]
+struct IsEvenTestCircuit {
+    a: Fq,
+    even: bool,          // expected parity of a
+    non_canonical: bool, // decompose a + p instead of a
+}
+
+impl Circuit<Fq> for IsEvenTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test is even circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let a =
+                    field_chip.load_private_field(&mut region, &config, &self.a, &mut offset)?;
+                let even = if self.non_canonical {
+                    // the decomposition of `decompose_field`, with the limbs of a + p
+                    let two_to_128 = Fq::from_u128(1 << 127).double();
+                    let (m_hi, m_lo) = field_decompose_u128(&-Fq::one());
+                    let (hi, lo) = field_decompose_u128(&self.a);
+                    let (lo, carry) = lo.overflowing_add(m_lo + 1);
+                    let hi = hi + m_hi + carry as u128;
+
+                    let (low_bits, low_cell) =
+                        field_chip.decompose_u128(&mut region, &config, &lo, &mut offset)?;
+                    let (_, high_cell) =
+                        field_chip.decompose_u128(&mut region, &config, &hi, &mut offset)?;
+                    let high_shifted = field_chip.mul_const(
+                        &mut region,
+                        &config,
+                        &high_cell,
+                        &two_to_128,
+                        &mut offset,
+                    )?;
+                    let a_rec = field_chip.add_cells(
+                        &mut region,
+                        &config,
+                        &high_shifted,
+                        &low_cell,
+                        &mut offset,
+                    )?;
+                    region.constrain_equal(a.cell(), a_rec.cell())?;
+                    field_chip.enforce_u256_le_const(
+                        &mut region,
+                        &config,
+                        &high_cell,
+                        &low_cell,
+                        &(m_hi, m_lo),
+                        &mut offset,
+                    )?;
+
+                    let neg_bit = field_chip.mul_const(
+                        &mut region,
+                        &config,
+                        &low_bits[0],
+                        &-Fq::one(),
+                        &mut offset,
+                    )?;
+                    field_chip.add_const(&mut region, &config, &neg_bit, &Fq::one(), &mut offset)?
+                } else {
+                    field_chip.is_even(&mut region, &config, &a, &mut offset)?
+                };
+                let expected = field_chip.load_private_field(
+                    &mut region,
+                    &config,
+                    &Fq::from(self.even as u64),
+                    &mut offset,
+                )?;
+                region.constrain_equal(even.cell(), expected.cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_is_even() {
+    let k = 10;
+
+    for (a, even) in [
+        (Fq::zero(), true),
+        (Fq::one(), false),
+        (Fq::from(2), true),
+        // p - 1 is even, as p is odd
+        (-Fq::one(), true),
+        (-Fq::from(2), false),
+    ] {
+        let circuit = IsEvenTestCircuit {
+            a,
+            even,
+            non_canonical: false,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong parity
+    {
+        let circuit = IsEvenTestCircuit {
+            a: Fq::one(),
+            even: true,
+            non_canonical: false,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: 1 + p is even, and is rejected as it is not canonical
+    {
+        let circuit = IsEvenTestCircuit {
+            a: Fq::one(),
+            even: true,
+            non_canonical: true,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct AssertRelationTestCircuit {
+    a: Fq,
+    b: Fq,
+    sum: Fq,     // claimed a + b
+    product: Fq, // claimed a * b
+}
+
+impl Circuit<Fq> for AssertRelationTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test assert relation circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let [a, b] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.a,
+                    &self.b,
+                    &mut offset,
+                )?;
+                let [sum, product] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.sum,
+                    &self.product,
+                    &mut offset,
+                )?;
+                field_chip.assert_sum(&mut region, &config, &a, &b, &sum, &mut offset)?;
+                field_chip.assert_product(&mut region, &config, &a, &b, &product, &mut offset)?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_assert_sum_and_product() {
+    let k = 5;
+
+    let mut rng = test_rng();
+    let a = Fq::random(&mut rng);
+    let b = Fq::random(&mut rng);
+
+    let circuit = AssertRelationTestCircuit {
+        a,
+        b,
+        sum: a + b,
+        product: a * b,
+    };
+
+    {
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong sum
+    {
+        let circuit = AssertRelationTestCircuit {
+            sum: a + b + Fq::one(),
+            ..circuit
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: wrong product
+    {
+        let circuit = AssertRelationTestCircuit {
+            product: a * b + Fq::one(),
+            ..circuit
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct AbsSignTestCircuit {
+    a: Fq,
+    abs: Fq,          // expected |a|
+    sign: bool,       // expected sign
+    wrong_sign: bool, // claim (|a|, sign) = (a, 0)
+}
+
+impl Circuit<Fq> for AbsSignTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test abs and sign circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let a =
+                    field_chip.load_private_field(&mut region, &config, &self.a, &mut offset)?;
+                let (abs, sign) =
+                    field_chip.abs_and_sign(&mut region, &config, &a, 64, &mut offset)?;
+                if self.wrong_sign {
+                    // (|a|, sign) are in a same row
+                    let row = sign.cell().row_offset;
+                    region.assign_advice(|| "abs", config.a, row, || Value::known(self.a))?;
+                    region.assign_advice(|| "sign", config.b, row, || Value::known(Fq::zero()))?;
+                }
+
+                let [abs_expected, sign_expected] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.abs,
+                    &Fq::from(self.sign as u64),
+                    &mut offset,
+                )?;
+                region.constrain_equal(abs.cell(), abs_expected.cell())?;
+                region.constrain_equal(sign.cell(), sign_expected.cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_abs_and_sign() {
+    let k = 8;
+
+    let five = Fq::from(5);
+    let max = Fq::from(u64::MAX);
+    for (a, abs, sign) in [
+        (five, five, false),
+        (-five, five, true),
+        (Fq::zero(), Fq::zero(), false),
+        // the largest values in range
+        (max, max, false),
+        (-max, max, true),
+    ] {
+        let circuit = AbsSignTestCircuit {
+            a,
+            abs,
+            sign,
+            wrong_sign: false,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: the wrong expected sign
+    {
+        let circuit = AbsSignTestCircuit {
+            a: -five,
+            abs: five,
+            sign: false,
+            wrong_sign: false,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: a witness claiming -5 = p - 5 is positive is out of range
+    {
+        let circuit = AbsSignTestCircuit {
+            a: -five,
+            abs: -five,
+            sign: false,
+            wrong_sign: true,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: out of range
+    {
+        let a = max + Fq::one();
+        let circuit = AbsSignTestCircuit {
+            a,
+            abs: a,
+            sign: false,
+            wrong_sign: false,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct Add3TestCircuit {
+    a: Fq,
+    b: Fq,
+    c: Fq,
+    d: Fq,                 // expected a + b + c
+    corrupted: Option<Fq>, // overwrites the output cell
+}
+
+impl Circuit<Fq> for Add3TestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test add3 circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let [a, b] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.a,
+                    &self.b,
+                    &mut offset,
+                )?;
+                let [c, d_expected] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.c,
+                    &self.d,
+                    &mut offset,
+                )?;
+                let d = field_chip.add3(&mut region, &config, &a, &b, &c, &mut offset)?;
+                if let Some(d) = self.corrupted {
+                    let row = d_expected.cell().row_offset;
+                    region.assign_advice(|| "d", config.b, offset - 1, || Value::known(d))?;
+                    region.assign_advice(|| "d", config.b, row, || Value::known(d))?;
+                }
+                region.constrain_equal(d.cell(), d_expected.cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_add3() {
+    let k = 5;
+
+    let mut rng = test_rng();
+    let a = Fq::random(&mut rng);
+    let b = Fq::random(&mut rng);
+    let c = Fq::random(&mut rng);
+
+    let circuit = Add3TestCircuit {
+        a,
+        b,
+        c,
+        d: a + b + c,
+        corrupted: None,
+    };
+
+    {
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong expected sum
+    {
+        let circuit = Add3TestCircuit {
+            d: a + b,
+            ..circuit
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: the output cell is a + b, and is rejected by the add3 gate
+    {
+        let circuit = Add3TestCircuit {
+            d: a + b,
+            corrupted: Some(a + b),
+            ..circuit
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct ComposeU128PairTestCircuit {
+    a: Fq,
+    low: Fq,  // expected low limb of a
+    high: Fq, // expected high limb of a
+}
+
+impl Circuit<Fq> for ComposeU128PairTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test compose u128 pair circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let a =
+                    field_chip.load_private_field(&mut region, &config, &self.a, &mut offset)?;
+                let bits = field_chip.decompose_field(&mut region, &config, &a, &mut offset)?;
+                let (low, high) =
+                    field_chip.compose_u128_pair(&mut region, &config, &bits, &mut offset)?;
+
+                let [low_expected, high_expected] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.low,
+                    &self.high,
+                    &mut offset,
+                )?;
+                region.constrain_equal(low.cell(), low_expected.cell())?;
+                region.constrain_equal(high.cell(), high_expected.cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_compose_u128_pair() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    for a in [Fq::zero(), Fq::one(), -Fq::one(), Fq::random(&mut rng)] {
+        let (high, low) = field_decompose_u128(&a);
+        let circuit = ComposeU128PairTestCircuit {
+            a,
+            low: Fq::from_u128(low),
+            high: Fq::from_u128(high),
+        };
+
+        {
+            let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // error case: the limbs are swapped
+        if low != high {
+            let circuit = ComposeU128PairTestCircuit {
+                low: circuit.high,
+                high: circuit.low,
+                ..circuit
+            };
+            let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct CellOpsTestCircuit {
+    a: Fq,
+    b: Fq,
+    sum: Fq,     // expected a + b
+    product: Fq, // expected a * b
+    // overwrites the copy of a in both gates
+    corrupted_a: Option<Fq>,
+}
+
+impl Circuit<Fq> for CellOpsTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test cell ops circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let [a, b] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.a,
+                    &self.b,
+                    &mut offset,
+                )?;
+                let [sum_expected, product_expected] = field_chip.load_two_private_fields(
+                    &mut region,
+                    &config,
+                    &self.sum,
+                    &self.product,
+                    &mut offset,
+                )?;
+
+                let sum = field_chip.add_cells(&mut region, &config, &a, &b, &mut offset)?;
+                let product = field_chip.mul_cells(&mut region, &config, &a, &b, &mut offset)?;
+
+                if let Some(a) = self.corrupted_a {
+                    // the gates still hold for the corrupted operand
+                    let sum_row = sum.cell().row_offset;
+                    let product_row = product.cell().row_offset;
+                    let a = Value::known(a);
+                    let b = Value::known(self.b);
+                    region.assign_advice(|| "a", config.a, sum_row - 1, || a)?;
+                    region.assign_advice(|| "a + b", config.a, sum_row, || a + b)?;
+                    region.assign_advice(|| "a", config.a, product_row - 1, || a)?;
+                    region.assign_advice(|| "a * b", config.a, product_row, || a * b)?;
+                }
+
+                region.constrain_equal(sum.cell(), sum_expected.cell())?;
+                region.constrain_equal(product.cell(), product_expected.cell())?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_add_and_mul_cells() {
+    let k = 5;
+
+    let mut rng = test_rng();
+    let a = Fq::random(&mut rng);
+    let b = Fq::random(&mut rng);
+
+    {
+        let circuit = CellOpsTestCircuit {
+            a,
+            b,
+            sum: a + b,
+            product: a * b,
+            corrupted_a: None,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong results
+    {
+        let circuit = CellOpsTestCircuit {
+            a,
+            b,
+            sum: a * b,
+            product: a + b,
+            corrupted_a: None,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: the operand in the gates is not a copy of a
+    {
+        let a_prime = a + Fq::one();
+        let circuit = CellOpsTestCircuit {
+            a,
+            b,
+            sum: a_prime + b,
+            product: a_prime * b,
+            corrupted_a: Some(a_prime),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct RangeCheckTestCircuit<const W: usize> {
+    a: Fq,
+    num_bits: usize,
+    // overwrite the chunks of a = 2^W with (2^W, 0), which keeps the running sum
+    corrupted: bool,
+}
+
+impl<const W: usize> Circuit<Fq> for RangeCheckTestCircuit<W> {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            num_bits: self.num_bits,
+            corrupted: self.corrupted,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure_with_range_width(meta, W)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+        field_chip.load_range_table(&mut layouter)?;
+
+        layouter.assign_region(
+            || "test range check circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let a =
+                    field_chip.load_private_field(&mut region, &config, &self.a, &mut offset)?;
+                let chunks = field_chip.decompose_bytes_lookup(
+                    &mut region,
+                    &config,
+                    &a,
+                    self.num_bits,
+                    &mut offset,
+                )?;
+                if self.corrupted {
+                    let row = chunks[0].cell().row_offset;
+                    let two_to_width = Value::known(Fq::from(1 << W));
+                    let zero = Value::known(Fq::zero());
+                    region.assign_advice(|| "chunk", config.a, row, || two_to_width)?;
+                    region.assign_advice(|| "chunk", config.a, row + 1, || zero)?;
+                    region.assign_advice(|| "acc", config.b, row + 1, || zero)?;
+                }
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+fn range_check_test<const W: usize>() {
+    let k = W as u32 + 1;
+
+    for num_bits in [8, 12, 20, 64] {
+        let two_to_n = Fq::from(2).pow_vartime([num_bits as u64]);
+
+        for a in [Fq::zero(), Fq::from(200), two_to_n - Fq::one()] {
+            let circuit = RangeCheckTestCircuit::<W> {
+                a,
+                num_bits,
+                corrupted: false,
+            };
+            let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // error case: a does not fit in n bits
+        let circuit = RangeCheckTestCircuit::<W> {
+            a: two_to_n,
+            num_bits,
+            corrupted: false,
+        };
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+
+    // error case: a chunk is out of the table, even though the running sum holds
+    {
+        let circuit = RangeCheckTestCircuit::<W> {
+            a: Fq::from(1 << W),
+            num_bits: 2 * W,
+            corrupted: true,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Lookup { .. })));
+    }
+}
+
+#[test]
+fn test_range_check() {
+    range_check_test::<8>();
+    range_check_test::<12>();
+}
+
+#[test]
+fn test_range_check_lookups() {
+    // (width, num_bits, lookups): one lookup per chunk, and one more for a partial chunk
+    let cases = [
+        (8, 20, 4),
+        (8, 64, 8),
+        (12, 20, 3),
+        (12, 64, 7),
+        (12, 24, 2),
+    ];
+
+    for (width, num_bits, lookups) in cases {
+        let mut meta = ConstraintSystem::<Fq>::default();
+        let config = ECChip::<G1Affine, Fq>::configure_with_range_width(&mut meta, width);
+        assert_eq!(config.range_width(), width);
+        assert_eq!(config.num_range_lookups(num_bits), lookups);
+
+        // a row per chunk, a row to end the running sum, and a mul gate for a partial chunk
+        let num_chunks = (num_bits + width - 1) / width;
+        let partial = num_bits % width != 0;
+        let field_chip = ECChip::construct(config);
+        let rows = field_chip
+            .dry_run_offset(|chip, region, config, offset| {
+                let a = chip.load_private_field(region, config, &Fq::zero(), offset)?;
+                chip.range_check(region, config, &a, num_bits, offset)
+            })
+            .unwrap();
+        assert_eq!(rows, 1 + num_chunks + 1 + 2 * partial as usize);
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+struct BooleansTestCircuit {
+    bits: Vec<Fq>,
+}
+
+impl Circuit<Fq> for BooleansTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: vec![Fq::zero(); self.bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test booleans circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let cells = self
+                    .bits
+                    .iter()
+                    .map(|b| field_chip.load_private_field(&mut region, &config, b, &mut offset))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                // 3 rows per 4 bits
+                let start = offset;
+                field_chip.enforce_booleans(&mut region, &config, &cells, &mut offset)?;
+                assert_eq!(offset - start, (cells.len() + 3) / 4 * 3);
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_enforce_booleans() {
+    let k = 7;
+
+    let mut rng = test_rng();
+    for n in [1, 5, 16] {
+        let bits = (0..n)
+            .map(|_| Fq::from(Fq::random(&mut rng).is_odd().unwrap_u8() as u64))
+            .collect::<Vec<_>>();
+
+        let circuit = BooleansTestCircuit { bits: bits.clone() };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+
+        // error case: a non-boolean input
+        let mut bits = bits;
+        bits[n - 1] = Fq::from(2);
+        let circuit = BooleansTestCircuit { bits };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+struct HammingWeightTestCircuit {
+    bits: Vec<Fq>,
+    weight: u64, // expected result
+    max_weight: usize,
+}
+
+impl Circuit<Fq> for HammingWeightTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: vec![Fq::zero(); self.bits.len()],
+            weight: 0,
+            max_weight: self.max_weight,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let field_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test hamming weight circuit",
+            |mut region| {
+                let mut offset = 0;
+
+                let bits = self
+                    .bits
+                    .iter()
+                    .map(|b| field_chip.load_private_field(&mut region, &config, b, &mut offset))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let weight = field_chip.hamming_weight(&mut region, &config, &bits, &mut offset)?;
+                let expected = field_chip.load_private_field(
+                    &mut region,
+                    &config,
+                    &Fq::from(self.weight),
+                    &mut offset,
+                )?;
+                region.constrain_equal(weight.cell(), expected.cell())?;
+
+                field_chip.enforce_weight_at_most(
+                    &mut region,
+                    &config,
+                    &weight,
+                    self.max_weight,
+                    &mut offset,
+                )?;
+
+                // pad the last two rows
+                field_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_hamming_weight() {
+    let k = 10;
+
+    let mut rng = test_rng();
+    let random = (0..256)
+        .map(|_| Fq::random(&mut rng).is_odd().unwrap_u8() as u64)
+        .collect::<Vec<_>>();
+
+    for bits in [vec![0; 256], vec![1; 256], random] {
+        let weight = bits.iter().sum::<u64>();
+        let bits = bits.into_iter().map(Fq::from).collect::<Vec<_>>();
+
+        for max_weight in [weight as usize, 256] {
+            let circuit = HammingWeightTestCircuit {
+                bits: bits.clone(),
+                weight,
+                max_weight,
+            };
+            let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // error case: wrong weight
+        let circuit = HammingWeightTestCircuit {
+            bits: bits.clone(),
+            weight: weight + 1,
+            max_weight: 256,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // error case: the weight exceeds the maximum
+        if weight > 0 {
+            let circuit = HammingWeightTestCircuit {
+                bits,
+                weight,
+                max_weight: weight as usize - 1,
+            };
+            let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -1,13 +1,38 @@
 use std::marker::PhantomData;
 
 use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::layouter::RegionLayouter;
+use halo2_proofs::circuit::layouter::RegionShape;
 use halo2_proofs::circuit::Chip;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::circuit::Value;
+#[cfg(debug_assertions)]
+use halo2_proofs::dev::CellValue;
+#[cfg(debug_assertions)]
+use halo2_proofs::dev::MockProver;
+#[cfg(debug_assertions)]
+use halo2_proofs::halo2curves::ff::FromUniformBytes;
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::halo2curves::CurveAffine;
+#[cfg(debug_assertions)]
+use halo2_proofs::plonk::Circuit;
 use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Error;
 use halo2_proofs::plonk::Expression;
+use halo2_proofs::plonk::FirstPhase;
+use halo2_proofs::plonk::SecondPhase;
+use halo2_proofs::plonk::Selector;
+use halo2_proofs::poly::Rotation;
 
 use crate::config::ECConfig;
+use crate::config::DEFAULT_RANGE_WIDTH;
+use crate::config::MAX_NUM_QUERIES;
+use crate::config::MAX_ROTATION;
+use crate::EccError;
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Clone, Debug)]
 pub struct ECChip<C, F>
@@ -21,6 +46,19 @@ where
     _phantom: PhantomData<F>,
 }
 
+/// The resources used by the chip, so that a host circuit can budget its columns.
+#[derive(Clone, Debug)]
+pub struct ChipMeta {
+    /// number of advice columns created by the chip
+    pub num_advice_columns: usize,
+    /// number of fixed columns created by the chip, including lookup tables
+    pub num_fixed_columns: usize,
+    /// selectors created by the chip
+    pub selectors: Vec<Selector>,
+    /// degree of the constraint system after configuration
+    pub degree: usize,
+}
+
 impl<C, F> Chip<F> for ECChip<C, F>
 where
     C: CurveAffine<Base = F>,
@@ -43,6 +81,46 @@ where
     C: CurveAffine<Base = F>,
     F: PrimeField,
 {
+    /// The minimum number of rows to pad after the last enabled gate.
+    ///
+    /// The gates query up to `MAX_ROTATION` rows below the enabled row, and halo2
+    /// reserves `max(3, number of queries of a column) + 2` blinding rows plus one
+    /// last row at the end of the trace; the advice columns of the chip are queried
+    /// at up to `MAX_NUM_QUERIES` rotations. Host circuits that query a column at more
+    /// rotations make halo2 reserve more rows.
+    pub fn min_blinding_rows() -> usize {
+        let blinding_factors = 3.max(MAX_NUM_QUERIES) + 2;
+        (blinding_factors + 1).max(MAX_ROTATION)
+    }
+
+    /// The number of set bits of a scalar.
+    ///
+    /// In the double-then-add loop of `point_mul`, every bit uses the same rows,
+    /// but only the set bits trigger a native p1 + p2 during witness generation;
+    /// an unset bit copies the accumulator. The witness generation time of a
+    /// point mul therefore grows with the hamming weight of the scalar, while
+    /// the circuit size does not depend on it.
+    pub fn hamming_weight(s: &C::ScalarExt) -> u32 {
+        s.to_repr().as_ref().iter().map(|b| b.count_ones()).sum()
+    }
+
+    /// The number of rows an op would consume from `offset`, without assigning anything.
+    ///
+    /// The op is run from offset 0 against a `RegionShape`, as the floor planner
+    /// does when it measures a region: no cell is assigned and all values are unknown.
+    /// Cells that the op copies must be assigned within the op, so this suits ops
+    /// that take native inputs, such as `point_mul`.
+    pub fn dry_run_offset<T>(
+        &self,
+        op: impl FnOnce(&Self, &mut Region<F>, &ECConfig<C, F>, &mut usize) -> Result<T, Error>,
+    ) -> Result<usize, Error> {
+        let mut shape = RegionShape::new(0.into());
+        let mut region = Region::from(&mut shape as &mut dyn RegionLayouter<F>);
+        let mut offset = 0;
+        op(self, &mut region, &self.config, &mut offset)?;
+        Ok(offset)
+    }
+
     pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
         Self {
             config,
@@ -50,31 +128,171 @@ where
         }
     }
 
+    /// Reset the state the chip carries between regions, if any, so that a
+    /// chip can be reused for the next region as if it was just constructed.
+    ///
+    /// The chip holds nothing but its config, so this is a no-op for now.
+    /// Callers that reuse a chip across regions should still call it, so that
+    /// state added to the chip later is cleared for them.
+    pub fn reset(&mut self) {}
+
+    /// Configure the chip, and report the resources it uses.
+    pub fn configure_with_meta(
+        meta: &mut ConstraintSystem<F>,
+    ) -> (<Self as Chip<F>>::Config, ChipMeta) {
+        let num_advice_columns = meta.num_advice_columns();
+        let num_fixed_columns = meta.num_fixed_columns();
+
+        let config = Self::configure(meta);
+        let chip_meta = ChipMeta {
+            num_advice_columns: meta.num_advice_columns() - num_advice_columns,
+            num_fixed_columns: meta.num_fixed_columns() - num_fixed_columns,
+            selectors: config.selectors(),
+            degree: meta.degree(),
+        };
+        (config, chip_meta)
+    }
+
+    /// Configure the chip with a range table of `DEFAULT_RANGE_WIDTH` bits.
     pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
-        let a = meta.advice_column();
+        Self::configure_with_range_width(meta, DEFAULT_RANGE_WIDTH)
+    }
+
+    /// Configure the chip with a range table of `range_width` bits, i.e., 2^range_width rows.
+    /// `range_check` consumes its input in chunks of `range_width` bits.
+    ///
+    /// Panics if `range_width` is not within [1, 16].
+    pub fn configure_with_range_width(
+        meta: &mut ConstraintSystem<F>,
+        range_width: usize,
+    ) -> <Self as Chip<F>>::Config {
+        Self::configure_in_phase(meta, range_width, false)
+    }
+
+    /// Configure the chip with its advice columns in the second phase, and a challenge
+    /// usable after the first phase, e.g., a verifier challenge r of a folding scheme.
+    ///
+    /// All cells of the chip, including the scalar bits of `point_mul_with_bits` and
+    /// the points derived from them, are then assigned in the second phase, so that
+    /// r * T can be computed for a challenge r that is unknown in the first phase.
+    /// First phase witnesses such as T live in columns of the host circuit and are copied in.
+    ///
+    /// Panics if the host circuit has no first phase advice column yet.
+    pub fn configure_second_phase(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        Self::configure_in_phase(meta, DEFAULT_RANGE_WIDTH, true)
+    }
+
+    fn configure_in_phase(
+        meta: &mut ConstraintSystem<F>,
+        range_width: usize,
+        second_phase: bool,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(
+            (1..=16).contains(&range_width),
+            "range table: unsupported width {range_width}"
+        );
+
+        // the challenge must be created after the first phase columns
+        let challenge = second_phase.then(|| meta.challenge_usable_after(FirstPhase));
+
+        let (a, b) = if second_phase {
+            (
+                meta.advice_column_in(SecondPhase),
+                meta.advice_column_in(SecondPhase),
+            )
+        } else {
+            (meta.advice_column(), meta.advice_column())
+        };
         meta.enable_equality(a);
-        let b = meta.advice_column();
         meta.enable_equality(b);
 
         let f = meta.fixed_column();
         meta.enable_constant(f);
 
-        // ec is enabled
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        // ec is enabled; see `ECConfig` for the mapping of selectors to ops
         let q_ec_enable = meta.complex_selector();
-        // ec conditional add
+        // ec conditional add, or partial decompose
         let q1 = meta.complex_selector();
-        // ec double
+        // ec double, or add
         let q2 = meta.complex_selector();
-        // ec on curve
+        // ec on curve, or mul
         let q3 = meta.complex_selector();
 
+        // two adjacent points are equal
+        let q_point_eq = meta.selector();
+
+        // slope of a conditional ec add
+        let q_add_slope = meta.selector();
+
+        // ec double derived from the slope
+        let q_double_slope = meta.selector();
+
+        // ec conditional double
+        let q_cond_double = meta.selector();
+
+        // ec add derived from the slope
+        let q_add_with_slope = meta.selector();
+
+        // three-input add
+        let q_add3 = meta.selector();
+
+        // running sum of range chunks
+        let q_range_acc = meta.selector();
+
+        // a round of the double-then-add loop of point_mul
+        let q_mul_round = meta.selector();
+
+        // x-coordinate is in the fixed table
+        let q_x_table = meta.complex_selector();
+        let x_table_tag = meta.lookup_table_column();
+        let x_table = meta.lookup_table_column();
+
+        // (index, x, y) is in the fixed table of constant points
+        let q_point_table = meta.complex_selector();
+        let point_table_id = meta.lookup_table_column();
+        let point_table_index = meta.lookup_table_column();
+        let point_table_x = meta.lookup_table_column();
+        let point_table_y = meta.lookup_table_column();
+
+        // a range chunk is in the fixed table
+        let q_range = meta.complex_selector();
+        let range_table = meta.lookup_table_column();
+
+        // a cell is the challenge
+        let challenge = challenge.map(|challenge| (challenge, meta.selector()));
+
         let config = ECConfig {
             a,
             b,
+            instance,
             q_ec_enable,
             q1,
             q2,
             q3,
+            q_point_eq,
+            q_add_slope,
+            q_double_slope,
+            q_cond_double,
+            q_add_with_slope,
+            q_add3,
+            q_range_acc,
+            q_mul_round,
+            q_x_table,
+            x_table_tag,
+            x_table,
+            q_point_table,
+            point_table_id,
+            point_table_index,
+            point_table_x,
+            point_table_y,
+            point_tables: vec![],
+            q_range,
+            range_table,
+            range_width,
+            challenge,
             _phantom: PhantomData::default(),
         };
 
@@ -85,9 +303,9 @@ where
             // | ----------- |:----:|:------------:| -- | -- | -- | -------------
             // |      ec add |   4  |       1      | 1  | 0  | 0  | (x1, y1), (x2, y2) and (x3, -y3) are on a same line
             // |   ec double |   2  |       1      | 0  | 1  | 0  | (x1, y1) and (x3, -y3) are on a tangential line of the curve
-            // | is on curve |   1  |       1      | 0  | 0  | 1  | y1^2 = x1^3 - C::b()
+            // | is on curve |   1  |       1      | 0  | 0  | 1  | y1^2 = x1^3 + C::b()
             //
-            // |     partial |   3  |       0      | 1  | 0  | 0  | y3 = x1 + y1 + x2 + y2 + x3 and
+            // |     partial |   3  |       0      | 1  | 0  | 0  | y3 = x1 + 2y1 + 4x2 + 8y2 + 16x3 and
             // |   decompose |      |              |    |    |    | x1, y1, x2, y2 are all binary
             // |         add |   2  |       0      | 0  | 1  | 0  | a1 = a0 + b0
             // |         mul |   2  |       0      | 0  | 0  | 1  | a1 = a0 * b0
@@ -120,8 +338,285 @@ where
                     + mul_gate * (one - q_ec_enable) * q3,
             ]
         });
+
+        meta.create_gate("point equality", |meta| {
+            let q = meta.query_selector(config.q_point_eq);
+            config
+                .point_eq_gate(meta)
+                .into_iter()
+                .map(|e| q.clone() * e)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("conditional ec add slope", |meta| {
+            let q = meta.query_selector(config.q_add_slope);
+            vec![q * config.add_slope_gate(meta)]
+        });
+
+        meta.create_gate("ec double with slope", |meta| {
+            let q = meta.query_selector(config.q_double_slope);
+            config
+                .double_slope_gate(meta)
+                .into_iter()
+                .map(|e| q.clone() * e)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("conditional ec double", |meta| {
+            let q = meta.query_selector(config.q_cond_double);
+            config
+                .conditional_ec_double_gate(meta)
+                .into_iter()
+                .map(|e| q.clone() * e)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("ec add with slope", |meta| {
+            let q = meta.query_selector(config.q_add_with_slope);
+            config
+                .add_with_slope_gate(meta)
+                .into_iter()
+                .map(|e| q.clone() * e)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("add3", |meta| {
+            let q = meta.query_selector(config.q_add3);
+            vec![q * config.add3_gate(meta)]
+        });
+
+        meta.create_gate("range accumulate", |meta| {
+            let q = meta.query_selector(config.q_range_acc);
+            vec![q * config.range_acc_gate(meta)]
+        });
+
+        meta.create_gate("ec mul round", |meta| {
+            let q = meta.query_selector(config.q_mul_round);
+            config
+                .mul_round_gate(meta)
+                .into_iter()
+                .map(|e| q.clone() * e)
+                .collect::<Vec<_>>()
+        });
+
+        if let Some((challenge, q_challenge)) = config.challenge {
+            meta.create_gate("challenge", |meta| {
+                let q = meta.query_selector(q_challenge);
+                let a0 = meta.query_advice(config.a, Rotation::cur());
+                let r = meta.query_challenge(challenge);
+                vec![q * (a0 - r)]
+            });
+        }
+
+        // (q, q * x) is in the table of (tag, x); when the selector is off
+        // the input (0, 0) hits the default row, which is never matched by an enabled row
+        meta.lookup("x in table", |meta| {
+            let q = meta.query_selector(config.q_x_table);
+            let x = meta.query_advice(config.a, Rotation::cur());
+            vec![(q.clone(), config.x_table_tag), (q * x, config.x_table)]
+        });
+
+        // |index  |    a   |    b    |
+        // |-------|--------|---------|
+        // |offset |  index | table id|
+        // |       |    x   |    y    |
+        //
+        // the default row (0, 0, 0, 0) is matched by disabled rows only,
+        // since enabled rows carry a non-zero table id
+        meta.lookup("point in table", |meta| {
+            let q = meta.query_selector(config.q_point_table);
+            let index = meta.query_advice(config.a, Rotation::cur());
+            let id = meta.query_advice(config.b, Rotation::cur());
+            let x = meta.query_advice(config.a, Rotation::next());
+            let y = meta.query_advice(config.b, Rotation::next());
+            vec![
+                (q.clone() * id, config.point_table_id),
+                (q.clone() * index, config.point_table_index),
+                (q.clone() * x, config.point_table_x),
+                (q * y, config.point_table_y),
+            ]
+        });
+
+        // the input 0 of a disabled row is in the table
+        meta.lookup("range chunk in table", |meta| {
+            let q = meta.query_selector(config.q_range);
+            let chunk = meta.query_advice(config.a, Rotation::cur());
+            vec![(q * chunk, config.range_table)]
+        });
+
         #[cfg(feature = "verbose")]
         println!("custom gate's degree {}", meta.degree());
         config
     }
+
+    /// Load the fixed table of allowed x-coordinates.
+    /// Can be called at most once per circuit.
+    pub fn load_x_table(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        table: &[F],
+    ) -> Result<(), EccError> {
+        let config = &self.config;
+        layouter.assign_table(
+            || "x table",
+            |mut t| {
+                // the default row
+                t.assign_cell(|| "tag", config.x_table_tag, 0, || Value::known(F::ZERO))?;
+                t.assign_cell(|| "x", config.x_table, 0, || Value::known(F::ZERO))?;
+                for (i, x) in table.iter().enumerate() {
+                    t.assign_cell(|| "tag", config.x_table_tag, i + 1, || Value::known(F::ONE))?;
+                    t.assign_cell(|| "x", config.x_table, i + 1, || Value::known(*x))?;
+                }
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Load the fixed tables of constant points registered via
+    /// `ECConfig::register_point_table`, all in one table.
+    /// Can be called at most once per circuit.
+    pub fn load_point_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), EccError> {
+        let config = &self.config;
+        layouter.assign_table(
+            || "point tables",
+            |mut t| {
+                // the default row
+                t.assign_cell(|| "id", config.point_table_id, 0, || Value::known(F::ZERO))?;
+                t.assign_cell(
+                    || "index",
+                    config.point_table_index,
+                    0,
+                    || Value::known(F::ZERO),
+                )?;
+                t.assign_cell(|| "x", config.point_table_x, 0, || Value::known(F::ZERO))?;
+                t.assign_cell(|| "y", config.point_table_y, 0, || Value::known(F::ZERO))?;
+
+                let mut row = 1;
+                for (id, table) in config.point_tables.iter().enumerate() {
+                    for (index, p) in table.iter().enumerate() {
+                        let coordinates = p.coordinates().unwrap();
+                        let id = F::from(id as u64 + 1);
+                        let index = F::from(index as u64);
+                        t.assign_cell(|| "id", config.point_table_id, row, || Value::known(id))?;
+                        t.assign_cell(
+                            || "index",
+                            config.point_table_index,
+                            row,
+                            || Value::known(index),
+                        )?;
+                        t.assign_cell(
+                            || "x",
+                            config.point_table_x,
+                            row,
+                            || Value::known(*coordinates.x()),
+                        )?;
+                        t.assign_cell(
+                            || "y",
+                            config.point_table_y,
+                            row,
+                            || Value::known(*coordinates.y()),
+                        )?;
+                        row += 1;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Load the fixed range table of [0, 2^range_width).
+    /// Can be called at most once per circuit.
+    pub fn load_range_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), EccError> {
+        let config = &self.config;
+        layouter.assign_table(
+            || "range table",
+            |mut t| {
+                for i in 0..1 << config.range_width {
+                    t.assign_cell(
+                        || "range",
+                        config.range_table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
+
+/// The rows of a circuit up to its last assigned advice cell, out of 2^k,
+/// as measured by `ECChip::assert_row_utilization`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowUtilization {
+    pub rows: usize,
+    pub k: u32,
+}
+
+impl RowUtilization {
+    /// The ratio above which a circuit is close to its blinding rows.
+    pub const NEAR_CAPACITY: f64 = 0.9;
+
+    /// The fraction of the 2^k rows that are used.
+    pub fn ratio(&self) -> f64 {
+        self.rows as f64 / (1u64 << self.k) as f64
+    }
+
+    /// Whether more than 90% of the rows are used: a few more rows, or a host
+    /// column queried at more rotations (see `ECChip::min_blinding_rows`), and
+    /// the circuit no longer fits.
+    pub fn is_near_capacity(&self) -> bool {
+        self.ratio() > Self::NEAR_CAPACITY
+    }
+}
+
+#[cfg(debug_assertions)]
+impl<C, F> ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField + Ord + FromUniformBytes<64>,
+{
+    /// Check the circuit uses at least `min_ratio * 2^k` rows, and return its
+    /// utilization. Only compiled with debug assertions.
+    ///
+    /// A circuit that uses a small fraction of its rows wastes SRS and prover time,
+    /// and would fit a smaller k. One that uses more than 90% of them passes, and
+    /// is flagged by `RowUtilization::is_near_capacity`.
+    ///
+    /// The rows are measured by the mock prover, up to the last assigned advice cell,
+    /// including the padding. The instance columns are left empty, as the circuit is
+    /// not checked to be satisfied.
+    ///
+    /// Returns `EccError::LowRowUtilization` below `min_ratio`, and the error of the
+    /// mock prover, e.g., if the circuit does not fit in 2^k rows.
+    pub fn assert_row_utilization<T: Circuit<F>>(
+        circuit: &T,
+        k: u32,
+        min_ratio: f64,
+    ) -> Result<RowUtilization, EccError> {
+        let mut meta = ConstraintSystem::<F>::default();
+        T::configure(&mut meta);
+        let instance = vec![vec![]; meta.num_instance_columns()];
+
+        let prover = MockProver::run(k, circuit, instance)?;
+        let rows = prover
+            .advice()
+            .iter()
+            .filter_map(|column| {
+                column
+                    .iter()
+                    .rposition(|cell| matches!(cell, CellValue::Assigned(_)))
+            })
+            .max()
+            .map_or(0, |row| row + 1);
+
+        let utilization = RowUtilization { rows, k };
+        if utilization.ratio() < min_ratio {
+            return Err(EccError::LowRowUtilization { rows, k, min_ratio });
+        }
+        Ok(utilization)
+    }
 }
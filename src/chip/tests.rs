@@ -0,0 +1,431 @@
+use std::marker::PhantomData;
+
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::floor_planner::V1;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Error;
+use halo2_proofs::plonk::FloorPlanner;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::chip::ECChip;
+use crate::chip::RowUtilization;
+use crate::config::ECConfig;
+use crate::util::field_decompose_u128;
+use crate::ArithOps;
+use crate::EccError;
+use crate::NativeECOps;
+
+#[test]
+fn test_configure_with_meta() {
+    let mut meta = ConstraintSystem::<Fq>::default();
+    // a column that belongs to the host circuit
+    let _host = meta.advice_column();
+
+    let (_config, chip_meta) = ECChip::<G1Affine, Fq>::configure_with_meta(&mut meta);
+
+    assert_eq!(chip_meta.num_advice_columns, meta.num_advice_columns() - 1);
+    assert_eq!(chip_meta.num_fixed_columns, meta.num_fixed_columns());
+    assert_eq!(chip_meta.selectors.len(), meta.num_selectors());
+    assert_eq!(chip_meta.degree, meta.degree());
+
+    assert_eq!(chip_meta.num_advice_columns, 2);
+
+    // the chip reserves as many rows as halo2 does
+    assert_eq!(
+        ECChip::<G1Affine, Fq>::min_blinding_rows(),
+        meta.blinding_factors() + 1
+    );
+}
+
+#[test]
+fn test_config_display() {
+    let mut meta = ConstraintSystem::<Fq>::default();
+    // a column that belongs to the host circuit
+    let _host = meta.advice_column();
+
+    let config = ECChip::<G1Affine, Fq>::configure(&mut meta);
+    let display = format!("{}", config);
+
+    assert!(display.starts_with("ECConfig:\n"));
+    assert!(display.contains("  Advice a: column 1\n"));
+    assert!(display.contains("  Advice b: column 2\n"));
+    assert!(display.contains("  Selector q1: idx"));
+    assert!(display.contains("  Selector q2: idx"));
+    assert!(!display.contains("q_challenge"));
+    for (name, _) in config.gate_degrees() {
+        assert!(display.contains(&format!("  Gate {}: degree", name)));
+    }
+
+    // the gates fit the degree of the constraint system, with their selectors
+    let degrees = config.gate_degrees();
+    assert!(!degrees.is_empty());
+    assert!(degrees.iter().all(|&(_, d)| d > 0 && d < meta.degree()));
+}
+
+#[cfg(feature = "bn256")]
+#[test]
+fn test_configure_bn256() {
+    use halo2curves::bn256;
+
+    use crate::Bn256ECChip;
+
+    let mut meta = ConstraintSystem::<bn256::Fr>::default();
+    let (_config, chip_meta) = Bn256ECChip::configure_with_meta(&mut meta);
+    assert_eq!(chip_meta.num_advice_columns, 2);
+
+    // the BN256 scalar field is the Grumpkin base field
+    let _: Fq = bn256::Fr::one();
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct UsableRowsTestCircuit {
+    // number of rows to fill before padding
+    rows: usize,
+}
+
+impl Circuit<Fq> for UsableRowsTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        *self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test usable rows circuit",
+            |mut region| {
+                let mut offset = 0;
+                for i in 0..self.rows {
+                    ec_chip.load_private_field(
+                        &mut region,
+                        &config,
+                        &Fq::from(i as u64),
+                        &mut offset,
+                    )?;
+                }
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_usable_rows() {
+    let k = 5;
+    let usable_rows = ECConfig::<G1Affine, Fq>::usable_rows(k);
+    let pad_rows = ECChip::<G1Affine, Fq>::min_blinding_rows();
+
+    // the rows and the padding fit exactly
+    {
+        let circuit = UsableRowsTestCircuit {
+            rows: usable_rows - pad_rows,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: one row too many
+    {
+        let circuit = UsableRowsTestCircuit {
+            rows: usable_rows - pad_rows + 1,
+        };
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_row_utilization() {
+    let k = 8;
+    let pad_rows = ECChip::<G1Affine, Fq>::min_blinding_rows();
+    let check = |rows: usize, min_ratio: f64| {
+        let circuit = UsableRowsTestCircuit { rows };
+        ECChip::<G1Affine, Fq>::assert_row_utilization(&circuit, k, min_ratio)
+    };
+
+    // half of the rows, including the padding
+    let utilization = check((1 << (k - 1)) - pad_rows, 0.5).unwrap();
+    assert_eq!(
+        utilization,
+        RowUtilization {
+            rows: 1 << (k - 1),
+            k
+        }
+    );
+    assert!(!utilization.is_near_capacity());
+
+    // all usable rows: passes, near capacity
+    let usable_rows = ECConfig::<G1Affine, Fq>::usable_rows(k);
+    let utilization = check(usable_rows - pad_rows, 0.9).unwrap();
+    assert_eq!(utilization.rows, usable_rows);
+    assert!(utilization.is_near_capacity());
+
+    // error case: one row short of half
+    assert!(matches!(
+        check((1 << (k - 1)) - pad_rows - 1, 0.5),
+        Err(EccError::LowRowUtilization { .. })
+    ));
+
+    // error case: one row too many
+    assert!(matches!(
+        check(usable_rows - pad_rows + 1, 0.5),
+        Err(EccError::Plonk(_))
+    ));
+}
+
+#[test]
+fn test_hamming_weight() {
+    let weight = ECChip::<G1Affine, Fq>::hamming_weight;
+
+    assert_eq!(weight(&Fr::zero()), 0);
+    assert_eq!(weight(&Fr::one()), 1);
+    assert_eq!(weight(&Fr::from(0b1011_0001)), 4);
+    assert_eq!(weight(&Fr::from(u64::MAX)), 64);
+    assert_eq!(weight(&Fr::from_u128(u128::MAX)), 128);
+    // 2^128 + 1
+    assert_eq!(weight(&(Fr::from_u128(1 << 127).double() + Fr::one())), 2);
+}
+
+// the planner types are neither `Clone` nor `Default`, so nothing is derived
+struct FloorPlannerTestCircuit<P: FloorPlanner> {
+    p: G1Affine,
+    q: G1Affine,
+    s: Fr,
+    _phantom: PhantomData<P>,
+}
+
+impl<P: FloorPlanner> Circuit<Fq> for FloorPlannerTestCircuit<P> {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = P;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            p: self.p,
+            q: self.q,
+            s: self.s,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        // each region is padded on its own, so the planner may place them in any order
+        let (p, sum, double) = layouter.assign_region(
+            || "test add and double",
+            |mut region| {
+                let mut offset = 0;
+                let p = ec_chip.load_private_point(&mut region, &config, &self.p, &mut offset)?;
+                let q = ec_chip.load_private_point(&mut region, &config, &self.q, &mut offset)?;
+                let sum = ec_chip.point_add(&mut region, &config, &p, &q, &mut offset)?;
+                let p_copied = ec_chip.copy_point(&mut region, &config, &p, &mut offset)?;
+                let double = ec_chip.point_double(&mut region, &config, &p_copied, &mut offset)?;
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok((p, sum, double))
+            },
+        )?;
+
+        let s = layouter.assign_region(
+            || "test decompose",
+            |mut region| {
+                let mut offset = 0;
+                let bits = ec_chip.decompose_scalar(&mut region, &config, &self.s, &mut offset)?;
+                let s = ec_chip.compose_u128_pair(&mut region, &config, &bits, &mut offset)?;
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(s)
+            },
+        )?;
+
+        // p is copied from the first region
+        let mul = layouter.assign_region(
+            || "test mul",
+            |mut region| {
+                let mut offset = 0;
+                let mul =
+                    ec_chip.point_mul_assigned(&mut region, &config, &p, &self.s, &mut offset)?;
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(mul)
+            },
+        )?;
+
+        for (i, cell) in [&sum.x, &sum.y, &double.x, &double.y, &mul.x, &mul.y]
+            .into_iter()
+            .chain([&s.0, &s.1])
+            .enumerate()
+        {
+            layouter.constrain_instance(cell.cell(), config.instance, i)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn floor_planner_test<P: FloorPlanner>() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let q = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+
+    let sum = (p + q).to_affine();
+    let double = (p + p).to_affine();
+    let mul = (p * s).to_affine();
+    let (high, low) = field_decompose_u128(&s);
+    let instance = vec![
+        sum.x,
+        sum.y,
+        double.x,
+        double.y,
+        mul.x,
+        mul.y,
+        Fq::from_u128(low),
+        Fq::from_u128(high),
+    ];
+
+    let circuit = FloorPlannerTestCircuit::<P> {
+        p,
+        q,
+        s,
+        _phantom: PhantomData,
+    };
+
+    {
+        let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: a wrong result is rejected
+    {
+        let mut instance = instance;
+        instance[4] += Fq::one();
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[test]
+fn test_simple_floor_planner() {
+    floor_planner_test::<SimpleFloorPlanner>();
+}
+
+#[test]
+fn test_v1_floor_planner() {
+    floor_planner_test::<V1>();
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct ResetTestCircuit {
+    p: G1Affine,
+    q: G1Affine,
+    sum: G1Affine, // p + q
+}
+
+impl Circuit<Fq> for ResetTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let mut ec_chip = ECChip::construct(config.clone());
+
+        // the same op in two regions, with the chip reset in between
+        for i in 0..2 {
+            ec_chip.reset();
+            layouter.assign_region(
+                || format!("test reset circuit {i}"),
+                |mut region| {
+                    let mut offset = 0;
+                    let p =
+                        ec_chip.load_private_point(&mut region, &config, &self.p, &mut offset)?;
+                    let q =
+                        ec_chip.load_private_point(&mut region, &config, &self.q, &mut offset)?;
+                    let sum = ec_chip.point_add(&mut region, &config, &p, &q, &mut offset)?;
+                    let expected =
+                        ec_chip.load_private_point(&mut region, &config, &self.sum, &mut offset)?;
+                    region.constrain_equal(sum.x().cell(), expected.x().cell())?;
+                    region.constrain_equal(sum.y().cell(), expected.y().cell())?;
+
+                    // pad the last two rows
+                    ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_reset() {
+    let k = 6;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let q = G1::random(&mut rng).to_affine();
+
+    {
+        let sum = (p + q).to_affine();
+        let circuit = ResetTestCircuit { p, q, sum };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong sum
+    {
+        let sum = (p + p).to_affine();
+        let circuit = ResetTestCircuit { p, q, sum };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
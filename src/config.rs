@@ -1,16 +1,59 @@
+use std::fmt;
 use std::marker::PhantomData;
 
 use halo2_proofs::arithmetic::Field;
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::halo2curves::CurveAffine;
 use halo2_proofs::plonk::Advice;
+use halo2_proofs::plonk::Challenge;
 use halo2_proofs::plonk::Column;
+use halo2_proofs::plonk::ConstraintSystem;
 use halo2_proofs::plonk::Expression;
+use halo2_proofs::plonk::Instance;
 use halo2_proofs::plonk::Selector;
+use halo2_proofs::plonk::TableColumn;
 use halo2_proofs::plonk::VirtualCells;
 use halo2_proofs::poly::Rotation;
 
-/// Three advices and two additions
+use crate::ECChip;
+
+/// The default width of the chunks of the range table, i.e., a table of 2^8 rows.
+pub const DEFAULT_RANGE_WIDTH: usize = 8;
+
+/// The number of try-and-increment candidates of the map to the curve in
+/// `derive_key_image`. About half of the candidates are on curve, so all of
+/// them miss with probability about 2^-64.
+pub const KEY_IMAGE_MAX_ITERATIONS: usize = 64;
+
+/// The domain separation tag added to the x-coordinate of PK in `derive_key_image`.
+/// It is non-zero and below 2^128, so no candidate of the map to the curve is
+/// the x-coordinate of PK itself.
+pub const KEY_IMAGE_DOMAIN: u128 = u128::from_be_bytes(*b"ecc key image v1");
+
+/// The largest rotation queried by the gates of the chip.
+pub(crate) const MAX_ROTATION: usize = 3;
+
+/// The largest number of distinct rotations an advice column is queried at:
+/// column b is queried at rotations -4, -3, 0, 1, 2 and 3.
+pub(crate) const MAX_NUM_QUERIES: usize = 6;
+
+/// Two advice columns, an instance column, and the selectors of the native ec chip gate.
+///
+/// The four selectors (q_ec_enable, q1, q2, q3) of the native ec chip gate are
+/// shared by the ec ops and the field ops. Exactly one of q1, q2, q3 is enabled
+/// on a row, and q_ec_enable chooses between the ec op and the field op:
+///
+/// |   op codes  | cost | q_ec_enable | q1 | q2 | q3 | enabled by
+/// | ----------- |:----:|:-----------:| -- | -- | -- | -------------
+/// | cond ec add |   4  |      1      | 1  | 0  | 0  | `conditional_point_add`
+/// |   ec double |   2  |      1      | 0  | 1  | 0  | `point_double`
+/// | is on curve |   1  |      1      | 0  | 0  | 1  | `enforce_on_curve`
+/// |     partial |   3  |      0      | 1  | 0  | 0  | `partial_bit_decomp`, `decompose_u128`
+/// |   decompose |      |             |    |    |    |
+/// |         add |   2  |      0      | 0  | 1  | 0  | `add`
+/// |         mul |   2  |      0      | 0  | 0  | 1  | `mul`
+///
+/// The cost is the number of rows queried by the op.
 #[derive(Clone, Debug)]
 pub struct ECConfig<C, F>
 where
@@ -23,11 +66,60 @@ where
     pub(crate) a: Column<Advice>,
     pub(crate) b: Column<Advice>,
 
+    // public inputs, e.g., the coordinates of public points
+    pub(crate) instance: Column<Instance>,
+
     // selectors
     pub(crate) q_ec_enable: Selector, // ec is enabled
-    pub(crate) q1: Selector,          // ec conditional add
-    pub(crate) q2: Selector,          // ec double
-    pub(crate) q3: Selector,          // ec on curve
+    pub(crate) q1: Selector,          // ec conditional add, or partial decompose
+    pub(crate) q2: Selector,          // ec double, or add
+    pub(crate) q3: Selector,          // ec on curve, or mul
+
+    // two adjacent points are equal
+    pub(crate) q_point_eq: Selector,
+
+    // slope of a conditional ec add
+    pub(crate) q_add_slope: Selector,
+
+    // ec double derived from the slope
+    pub(crate) q_double_slope: Selector,
+
+    // ec conditional double
+    pub(crate) q_cond_double: Selector,
+
+    // ec add derived from the slope
+    pub(crate) q_add_with_slope: Selector,
+
+    // three-input add
+    pub(crate) q_add3: Selector,
+
+    // running sum of range chunks
+    pub(crate) q_range_acc: Selector,
+
+    // a round of the double-then-add loop of point_mul
+    pub(crate) q_mul_round: Selector,
+
+    // lookup of x-coordinates in a fixed table
+    pub(crate) q_x_table: Selector,
+    pub(crate) x_table_tag: TableColumn, // 1 for table entries, 0 for the default row
+    pub(crate) x_table: TableColumn,
+
+    // lookup of (table id, index, x, y) in the fixed tables of constant points
+    pub(crate) q_point_table: Selector,
+    pub(crate) point_table_id: TableColumn, // 0 for the default row
+    pub(crate) point_table_index: TableColumn,
+    pub(crate) point_table_x: TableColumn,
+    pub(crate) point_table_y: TableColumn,
+    // the registered tables; the table with id i is stored at i - 1
+    pub(crate) point_tables: Vec<Vec<C>>,
+
+    // lookup of range chunks in the fixed table of [0, 2^range_width)
+    pub(crate) q_range: Selector,
+    pub(crate) range_table: TableColumn,
+    pub(crate) range_width: usize,
+
+    // a challenge of the second phase configuration, and the selector that binds a cell to it
+    pub(crate) challenge: Option<(Challenge, Selector)>,
 
     pub(crate) _phantom: PhantomData<C>,
 }
@@ -37,6 +129,126 @@ where
     C: CurveAffine<Base = F>,
     F: PrimeField,
 {
+    /// The number of rows a circuit of 2^k rows can assign, i.e., `2^k - min_blinding_rows`.
+    pub fn usable_rows(k: u32) -> usize {
+        (1 << k) - ECChip::<C, F>::min_blinding_rows()
+    }
+
+    /// All selectors used by the chip
+    pub(crate) fn selectors(&self) -> Vec<Selector> {
+        self.named_selectors().into_iter().map(|(_, q)| q).collect()
+    }
+
+    /// All selectors used by the chip, with the names of their fields
+    pub(crate) fn named_selectors(&self) -> Vec<(&'static str, Selector)> {
+        vec![
+            ("q_ec_enable", self.q_ec_enable),
+            ("q1", self.q1),
+            ("q2", self.q2),
+            ("q3", self.q3),
+            ("q_point_eq", self.q_point_eq),
+            ("q_add_slope", self.q_add_slope),
+            ("q_double_slope", self.q_double_slope),
+            ("q_cond_double", self.q_cond_double),
+            ("q_add_with_slope", self.q_add_with_slope),
+            ("q_add3", self.q_add3),
+            ("q_range_acc", self.q_range_acc),
+            ("q_mul_round", self.q_mul_round),
+            ("q_x_table", self.q_x_table),
+            ("q_point_table", self.q_point_table),
+            ("q_range", self.q_range),
+        ]
+        .into_iter()
+        .chain(self.challenge.map(|(_, q)| ("q_challenge", q)))
+        .collect()
+    }
+
+    /// The polynomials of the gate methods, without their selectors,
+    /// with the names of the methods.
+    pub(crate) fn gates(
+        &self,
+        meta: &mut VirtualCells<F>,
+    ) -> Vec<(&'static str, Vec<Expression<F>>)> {
+        vec![
+            (
+                "conditional_ec_add_gate",
+                vec![self.conditional_ec_add_gate(meta)],
+            ),
+            ("ec_double_gate", vec![self.ec_double_gate(meta)]),
+            (
+                "conditional_ec_double_gate",
+                self.conditional_ec_double_gate(meta),
+            ),
+            ("on_curve_gate", vec![self.on_curve_gate(meta)]),
+            (
+                "partial_bit_decom_gate",
+                vec![self.partial_bit_decom_gate(meta)],
+            ),
+            ("add_slope_gate", vec![self.add_slope_gate(meta)]),
+            ("add_with_slope_gate", self.add_with_slope_gate(meta)),
+            ("double_slope_gate", self.double_slope_gate(meta)),
+            ("point_eq_gate", self.point_eq_gate(meta)),
+            ("add_gate", vec![self.add_gate(meta)]),
+            ("mul_gate", vec![self.mul_gate(meta)]),
+            ("add3_gate", vec![self.add3_gate(meta)]),
+            ("range_acc_gate", vec![self.range_acc_gate(meta)]),
+            ("mul_round_gate", self.mul_round_gate(meta)),
+        ]
+    }
+
+    /// The degree of each gate method, i.e., of its polynomials without the selectors.
+    pub fn gate_degrees(&self) -> Vec<(&'static str, usize)> {
+        let mut degrees = vec![];
+
+        // the gate methods query cells through `VirtualCells`, which only
+        // a gate of a constraint system can provide
+        let mut meta = ConstraintSystem::<F>::default();
+        meta.create_gate("gate degrees", |meta| {
+            for (name, expressions) in self.gates(meta) {
+                let degree = expressions.iter().map(|e| e.degree()).max().unwrap_or(0);
+                degrees.push((name, degree));
+            }
+
+            // a gate must have a constraint; the constraint system is discarded
+            vec![Expression::Constant(F::ZERO)]
+        });
+
+        degrees
+    }
+
+    /// Register a table of constant points for `NativeECOps::lookup_constant_point`,
+    /// and return its table id. Must be called at configure time, and the tables
+    /// are loaded via `ECChip::load_point_tables`.
+    ///
+    /// Panics if a point is the identity.
+    pub fn register_point_table(&mut self, points: &[C]) -> usize {
+        assert!(
+            points.iter().all(|p| bool::from(p.coordinates().is_some())),
+            "point table: the identity is not representable"
+        );
+        self.point_tables.push(points.to_vec());
+        // id 0 is reserved for the default row
+        self.point_tables.len()
+    }
+
+    /// The challenge of a chip configured via `ECChip::configure_second_phase`,
+    /// to be queried with `Layouter::get_challenge` and loaded via `ArithOps::load_challenge`.
+    pub fn challenge(&self) -> Option<Challenge> {
+        self.challenge.map(|(challenge, _)| challenge)
+    }
+
+    /// The width of the chunks of the range table.
+    pub fn range_width(&self) -> usize {
+        self.range_width
+    }
+
+    /// The number of range table lookups of `range_check` for n bits:
+    /// one per chunk, and one more if the final chunk is partial.
+    pub fn num_range_lookups(&self, num_bits: usize) -> usize {
+        let num_chunks = (num_bits + self.range_width - 1) / self.range_width;
+        num_chunks + (num_bits % self.range_width != 0) as usize
+    }
+
     pub(crate) fn conditional_ec_add_gate(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
         let one = Expression::Constant(F::ONE);
         // FIXME: currently hardcoded for Grumpkin curve
@@ -74,6 +286,11 @@ where
             + (one.clone() - condition.clone()) * (a2.clone() - a0)
             + (one - condition) * (b2.clone() - b0)
             // enforce the result is on curve
+            //
+            // the term cannot be dropped when the result feeds another op: the gates
+            // constrain the points they output, not the points they take as inputs,
+            // so without it x3 is free and y3 follows from the line. Nor would it
+            // lower the degree, since `condition * add` above is cubic already.
             + a2.clone() * a2.clone() * a2
             - b2.clone() * b2
             + curve_param_b_expr
@@ -101,12 +318,52 @@ where
         // | x3 | y3 |
 
         two * b0.clone() * (b1.clone() + b0) + (three * a0.clone() * a0.clone()) * (a1.clone() - a0)
-        // enforce the result is on curve
+        // enforce the result is on curve; as in `conditional_ec_add_gate`, the
+        // tangent relation is cubic, and the term is the only check of (x3, y3)
         + a1.clone() * a1.clone() * a1
             - b1.clone() * b1
             + curve_param_b_expr
     }
 
+    /// Given (x1, y1) and a bit c,
+    /// - (x1, y1) and (x3, -y3) are on a tangential line of the curve and
+    ///   (x3, y3) is on curve if c is 1
+    /// - (x3, y3) = (x1, y1) if c is 0
+    pub(crate) fn conditional_ec_double_gate(
+        &self,
+        meta: &mut VirtualCells<F>,
+    ) -> Vec<Expression<F>> {
+        let one = Expression::Constant(F::ONE);
+        let two = Expression::Constant(F::from(2));
+        let three = Expression::Constant(F::from(3));
+        // FIXME: currently hardcoded for Grumpkin curve
+        let curve_param_b = -F::from(17);
+        let curve_param_b_expr = Expression::Constant(curve_param_b);
+
+        let a0 = meta.query_advice(self.a, Rotation::cur());
+        let b0 = meta.query_advice(self.b, Rotation::cur());
+        let condition = meta.query_advice(self.a, Rotation::next());
+        let a2 = meta.query_advice(self.a, Rotation(2));
+        let b2 = meta.query_advice(self.b, Rotation(2));
+
+        // | a  | b  |
+        // -----------
+        // | x1 | y1 |
+        // | c  |    |
+        // | x3 | y3 |
+        let double = two * b0.clone() * (b2.clone() + b0.clone())
+            + three * a0.clone() * a0.clone() * (a2.clone() - a0.clone());
+        let on_curve =
+            a2.clone() * a2.clone() * a2.clone() - b2.clone() * b2.clone() + curve_param_b_expr;
+
+        vec![
+            // c is binary
+            condition.clone() * (one.clone() - condition.clone()),
+            condition.clone() * double + (one.clone() - condition.clone()) * (a2 - a0),
+            condition.clone() * on_curve + (one - condition) * (b2 - b0),
+        ]
+    }
+
     /// (x1, y1) is on curve
     pub(crate) fn on_curve_gate(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
         // FIXME: currently hardcoded for Grumpkin curve
@@ -115,7 +372,7 @@ where
 
         let a0 = meta.query_advice(self.a, Rotation::cur());
         let b0 = meta.query_advice(self.b, Rotation::cur());
-        // (1 - q1) * q2 * (a^3 - b^2 - 17) == c
+        // q_ec_enable * q3 * (a^3 - b^2 - 17) == 0
         a0.clone() * a0.clone() * a0 - b0.clone() * b0 + curve_param_b_expr
     }
 
@@ -145,6 +402,90 @@ where
             + b1.clone() * (one - b1)
     }
 
+    /// the slope of a conditional ec add
+    /// - lambda * (x2 - x1) = y2 - y1 if condition is true
+    /// - lambda = 0 otherwise
+    pub(crate) fn add_slope_gate(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
+        let one = Expression::Constant(F::ONE);
+
+        let a0 = meta.query_advice(self.a, Rotation::cur());
+        let b0 = meta.query_advice(self.b, Rotation::cur());
+        let a1 = meta.query_advice(self.a, Rotation::next());
+        let b1 = meta.query_advice(self.b, Rotation::next());
+        let condition = meta.query_advice(self.a, Rotation(2));
+        let lambda = meta.query_advice(self.b, Rotation(2));
+
+        // | a    | b      |
+        // -----------------
+        // | x1   | y1     |
+        // | x2   | y2     |
+        // | c    | lambda |
+        // | x3   | y3     |
+        condition.clone() * (lambda.clone() * (a1 - a0) - (b1 - b0)) + (one - condition) * lambda
+    }
+
+    /// an ec add that derives the sum from the slope
+    /// - lambda * (x2 - x1) = y2 - y1
+    /// - x3 = lambda^2 - x1 - x2
+    /// - y3 = lambda * (x1 - x3) - y1
+    pub(crate) fn add_with_slope_gate(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
+        let a0 = meta.query_advice(self.a, Rotation::cur());
+        let b0 = meta.query_advice(self.b, Rotation::cur());
+        let a1 = meta.query_advice(self.a, Rotation::next());
+        let b1 = meta.query_advice(self.b, Rotation::next());
+        let lambda = meta.query_advice(self.a, Rotation(2));
+        let a3 = meta.query_advice(self.a, Rotation(3));
+        let b3 = meta.query_advice(self.b, Rotation(3));
+
+        // | a      | b  |
+        // ---------------
+        // | x1     | y1 |
+        // | x2     | y2 |
+        // | lambda |    |
+        // | x3     | y3 |
+        vec![
+            lambda.clone() * (a1.clone() - a0.clone()) - (b1 - b0.clone()),
+            a3.clone() - (lambda.clone() * lambda.clone() - a0.clone() - a1),
+            b3 - (lambda * (a0 - a3) - b0),
+        ]
+    }
+
+    /// an ec double that derives the result from the slope
+    /// - 2y1 * lambda = 3x1^2
+    /// - x3 = lambda^2 - 2x1
+    /// - y3 = lambda * (x1 - x3) - y1
+    pub(crate) fn double_slope_gate(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
+        let two = Expression::Constant(F::from(2));
+        let three = Expression::Constant(F::from(3));
+
+        let lambda = meta.query_advice(self.a, Rotation::cur());
+        let a1 = meta.query_advice(self.a, Rotation::next());
+        let b1 = meta.query_advice(self.b, Rotation::next());
+        let a2 = meta.query_advice(self.a, Rotation(2));
+        let b2 = meta.query_advice(self.b, Rotation(2));
+
+        // | a      | b  |
+        // ---------------
+        // | lambda |    |
+        // | x1     | y1 |
+        // | x3     | y3 |
+        vec![
+            two.clone() * b1.clone() * lambda.clone() - three * a1.clone() * a1.clone(),
+            a2.clone() - (lambda.clone() * lambda.clone() - two * a1.clone()),
+            b2 - (lambda * (a1 - a2) - b1),
+        ]
+    }
+
+    /// (x1, y1) == (x2, y2), for two points in adjacent rows
+    pub(crate) fn point_eq_gate(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
+        let a0 = meta.query_advice(self.a, Rotation::cur());
+        let b0 = meta.query_advice(self.b, Rotation::cur());
+        let a1 = meta.query_advice(self.a, Rotation::next());
+        let b1 = meta.query_advice(self.b, Rotation::next());
+
+        vec![a0 - a1, b0 - b1]
+    }
+
     /// additional gate
     pub(crate) fn add_gate(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
         let a0 = meta.query_advice(self.a, Rotation::cur());
@@ -162,4 +503,90 @@ where
 
         a0 * b0 - a1
     }
+
+    /// three-input addition gate
+    pub(crate) fn add3_gate(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
+        let a0 = meta.query_advice(self.a, Rotation::cur());
+        let b0 = meta.query_advice(self.b, Rotation::cur());
+        let a1 = meta.query_advice(self.a, Rotation::next());
+        let b1 = meta.query_advice(self.b, Rotation::next());
+
+        // | a | b |
+        // ---------
+        // | a | b |
+        // | c | d |
+        a0 + b0 + a1 - b1
+    }
+
+    /// running sum of range chunks gate
+    pub(crate) fn range_acc_gate(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
+        let two_to_width = Expression::Constant(F::from(1 << self.range_width));
+
+        let a0 = meta.query_advice(self.a, Rotation::cur());
+        let b0 = meta.query_advice(self.b, Rotation::cur());
+        let b1 = meta.query_advice(self.b, Rotation::next());
+
+        // |    a    |     b     |
+        // -----------------------
+        // | chunk_i |   acc_i   |
+        // |         | acc_{i+1} |
+        b0 - b1 * two_to_width - a0
+    }
+
+    /// a round of the double-then-add loop of `point_mul`, anchored at the row of p
+    /// - p is carried over from the previous round, 4 rows above
+    /// - the bit is binary
+    /// - acc = 2 * acc_prev + bit, where acc_prev is 4 rows above acc
+    pub(crate) fn mul_round_gate(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
+        let one = Expression::Constant(F::ONE);
+        let two = Expression::Constant(F::from(2));
+
+        let x_prev = meta.query_advice(self.a, Rotation(-4));
+        let y_prev = meta.query_advice(self.b, Rotation(-4));
+        let acc_prev = meta.query_advice(self.b, Rotation(-3));
+        let x = meta.query_advice(self.a, Rotation::cur());
+        let y = meta.query_advice(self.b, Rotation::cur());
+        let bit = meta.query_advice(self.a, Rotation::next());
+        let acc = meta.query_advice(self.b, Rotation::next());
+
+        // | a    | b        |
+        // -------------------
+        // | px   | py       | <- previous round
+        // | bit' | acc_prev |
+        // | x3'  | y3'      |
+        // | x2   | y2       |
+        // | px   | py       | <- enabled
+        // | bit  | acc      |
+        // | x3   | y3       |
+        vec![
+            x - x_prev,
+            y - y_prev,
+            bit.clone() * (one - bit.clone()),
+            acc - two * acc_prev - bit,
+        ]
+    }
+}
+
+/// A table of the columns, selectors and gates of the chip, e.g., to see which
+/// columns of a host circuit the chip uses.
+impl<C, F> fmt::Display for ECConfig<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ECConfig:")?;
+        writeln!(f, "  Advice a: column {}", self.a.index())?;
+        writeln!(f, "  Advice b: column {}", self.b.index())?;
+        writeln!(f, "  Instance instance: column {}", self.instance.index())?;
+        for (name, q) in self.named_selectors() {
+            writeln!(f, "  Selector {}: idx {}", name, q.index())?;
+        }
+        writeln!(f, "  Range width: {}", self.range_width)?;
+        writeln!(f, "  Point tables: {}", self.point_tables.len())?;
+        for (name, degree) in self.gate_degrees() {
+            writeln!(f, "  Gate {}: degree {}", name, degree)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,64 @@
+use std::cell::Cell;
+
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::halo2curves::ff::FromUniformBytes;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::Error;
+
+/// Run the mock prover on the circuit and check it is satisfied.
+/// Only compiled with the `debug_verify` feature.
+///
+/// The failures are printed to stderr, and reported as `Error::Synthesis`.
+/// Call it right after building the circuit with its witness, before proof
+/// generation, or annotate `synthesize` with `#[debug_verify_synthesis]` to
+/// call it at the end of every synthesis with a witness.
+pub fn debug_verify<F, C>(k: u32, circuit: &C, instance: Vec<Vec<F>>) -> Result<(), Error>
+where
+    F: PrimeField + Ord + FromUniformBytes<64>,
+    C: Circuit<F>,
+{
+    let prover = MockProver::run(k, circuit, instance)?;
+    prover.verify().map_err(|failures| {
+        for failure in failures.iter() {
+            eprintln!("debug verify: {}", failure);
+        }
+        Error::Synthesis
+    })
+}
+
+thread_local! {
+    // set while the mock prover of `debug_verify_synthesize` runs
+    static VERIFYING: Cell<bool> = Cell::new(false);
+}
+
+/// `debug_verify` at the end of a `synthesize` annotated with
+/// `#[debug_verify_synthesis]`.
+///
+/// The mock prover synthesizes the circuit again; the nested call returns
+/// right away, as does any synthesis on the thread until the check is done.
+#[doc(hidden)]
+pub fn debug_verify_synthesize<F, C>(
+    k: u32,
+    circuit: &C,
+    instance: Vec<Vec<F>>,
+) -> Result<(), Error>
+where
+    F: PrimeField + Ord + FromUniformBytes<64>,
+    C: Circuit<F>,
+{
+    if VERIFYING.with(|v| v.replace(true)) {
+        return Ok(());
+    }
+
+    // reset the flag on a panic too, so that the thread can verify again
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            VERIFYING.with(|v| v.set(false));
+        }
+    }
+    let _reset = Reset;
+
+    debug_verify(k, circuit, instance)
+}
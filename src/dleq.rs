@@ -0,0 +1,87 @@
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// The DLEQ gadgets of the chip, on top of `NativeECOps`.
+pub trait DleqOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Prove equality of discrete logs: h1 = s * g1 and h2 = s * g2 for the
+    /// same secret s.
+    ///
+    /// s is decomposed once, and both point muls run over the same bit cells.
+    /// Returns the bits of s, for callers that use s further.
+    ///
+    /// Caller must check g1, h1, g2 and h2 are on curve.
+    #[allow(clippy::too_many_arguments)]
+    fn prove_dleq<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        g1: &AssignedECPoint<C, F>,
+        h1: &AssignedECPoint<C, F>,
+        g2: &AssignedECPoint<C, F>,
+        h2: &AssignedECPoint<C, F>,
+        s: &C::ScalarExt,
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
+}
+
+impl<C, F> DleqOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Prove equality of discrete logs: h1 = s * g1 and h2 = s * g2 for the
+    /// same secret s.
+    ///
+    /// s is decomposed once, and both point muls run over the same bit cells.
+    /// Returns the bits of s, for callers that use s further.
+    ///
+    /// Caller must check g1, h1, g2 and h2 are on curve.
+    #[allow(clippy::too_many_arguments)]
+    fn prove_dleq<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        g1: &AssignedECPoint<C, F>,
+        h1: &AssignedECPoint<C, F>,
+        g2: &AssignedECPoint<C, F>,
+        h2: &AssignedECPoint<C, F>,
+        s: &C::ScalarExt,
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        let s_bits = self.decompose_scalar(region, config, s, offset)?;
+
+        // h1 = s * g1
+        let h1_rec = self.point_mul_with_bits(region, config, g1, &s_bits, offset)?;
+        region.constrain_equal(h1_rec.x.cell(), h1.x.cell())?;
+        region.constrain_equal(h1_rec.y.cell(), h1.y.cell())?;
+
+        // h2 = s * g2
+        let h2_rec = self.point_mul_with_bits(region, config, g2, &s_bits, offset)?;
+        region.constrain_equal(h2_rec.x.cell(), h2.x.cell())?;
+        region.constrain_equal(h2_rec.y.cell(), h2.y.cell())?;
+
+        Ok(s_bits)
+    }
+}
@@ -0,0 +1,82 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::mock_run;
+use crate::test_circuit::overwrite_point;
+use crate::DleqOps;
+use crate::NativeECOps;
+
+#[test]
+fn test_prove_dleq() {
+    let k = 12;
+
+    // seed: overwrites the seed of the double-then-add loop of s * g2, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run_with_seed =
+        |g1: G1Affine, h1: G1Affine, g2: G1Affine, h2: G1Affine, s: Fr, seed: Option<G1Affine>| {
+            mock_run(k, |ec_chip, region, config, offset| {
+                let points =
+                    ec_chip.load_private_points(region, config, &[g1, h1, g2, h2], offset)?;
+
+                // the bits of s and the loop of s * g1 come before the loop
+                let rows = ec_chip.dry_run_offset(|chip, region, config, offset| {
+                    let s_bits = chip.decompose_scalar(region, config, &s, offset)?;
+                    chip.point_mul_with_bits(region, config, &points[0], &s_bits, offset)
+                })?;
+                seed_row.set(*offset + rows);
+
+                let s_bits = ec_chip.prove_dleq(
+                    region, config, &points[0], &points[1], &points[2], &points[3], &s, offset,
+                )?;
+                assert_eq!(s_bits.len(), 256);
+
+                if let Some(q) = seed {
+                    overwrite_point(region, config, seed_row.get(), &q)?;
+                }
+
+                Ok(())
+            })
+            .unwrap()
+        };
+    let run = |g1, h1, g2, h2, s| run_with_seed(g1, h1, g2, h2, s, None);
+
+    let mut rng = test_rng();
+    let g1 = G1::random(&mut rng).to_affine();
+    let g2 = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+    let h1 = (g1 * s).to_affine();
+    let h2 = (g2 * s).to_affine();
+
+    run(g1, h1, g2, h2, s).assert_satisfied();
+
+    // error case: h2 is for a different secret
+    {
+        let h2 = (g2 * (s + Fr::one())).to_affine();
+        assert!(run(g1, h1, g2, h2, s).verify().is_err());
+    }
+
+    // error case: the witness secret matches neither point
+    assert!(run(g1, h1, g2, h2, s + Fr::one()).verify().is_err());
+
+    // error case: h2 = s' * g2 for a different secret s', and a seed Q that
+    // makes 2^256 * (Q - G) + s * g2 = h2; the seed is a constant
+    {
+        let s_prime = Fr::random(&mut rng);
+        let h2 = (g2 * s_prime).to_affine();
+
+        let gen = G1Affine::generator();
+        let two_to_256_inv = Fr::from(2).pow_vartime([256]).invert().unwrap();
+        let seed = (gen + (h2 - g2 * s) * two_to_256_inv).to_affine();
+
+        let failures = run_with_seed(g1, h1, g2, h2, s, Some(seed))
+            .verify()
+            .unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+}
@@ -0,0 +1,235 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::FromUniformBytes;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+use halo2_proofs::transcript::Blake2bWrite;
+use halo2_proofs::transcript::Challenge255;
+use halo2_proofs::transcript::Transcript;
+
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// A non-interactive Schnorr proof (R, e, s) of the knowledge of sk with
+/// pk = sk * G, for a fixed base G; made by `prove_dlog_fixed_base`.
+#[derive(Debug, Clone)]
+pub struct DLogProof<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    /// the commitment R = k * G
+    pub r: C,
+    /// the challenge e = H(G, pk, R)
+    pub e: C::ScalarExt,
+    /// the response s = k + e * sk
+    pub s: C::ScalarExt,
+    _phantom: PhantomData<F>,
+}
+
+impl<C, F> DLogProof<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    pub fn new(r: C, e: C::ScalarExt, s: C::ScalarExt) -> Self {
+        Self {
+            r,
+            e,
+            s,
+            _phantom: PhantomData::default(),
+        }
+    }
+}
+
+impl<C, F> DLogProof<C, F>
+where
+    C: CurveAffine<Base = F>,
+    C::ScalarExt: FromUniformBytes<64>,
+    F: Field,
+{
+    /// The Fiat-Shamir challenge e = H(G, pk, R), squeezed from a Blake2b transcript.
+    pub fn challenge(g: &C, pk: &C, r: &C) -> C::ScalarExt {
+        let mut transcript = Blake2bWrite::<_, C, Challenge255<C>>::init(vec![]);
+        transcript.common_point(*g).unwrap();
+        transcript.common_point(*pk).unwrap();
+        transcript.common_point(*r).unwrap();
+        *transcript.squeeze_challenge_scalar::<()>()
+    }
+
+    /// Verify the proof natively: e = H(G, pk, R) and s * G = R + e * pk.
+    pub fn verify(&self, g: &C, pk: &C) -> bool {
+        self.e == Self::challenge(g, pk, &self.r)
+            && (*g * self.s).to_affine() == (*pk * self.e + self.r).to_affine()
+    }
+}
+
+/// Prove the knowledge of sk with pk = sk * G, for a fixed base G, i.e.,
+/// a Schnorr proof made non-interactive with the challenge of `DLogProof::challenge`.
+///
+/// The nonce k is squeezed from a transcript of G and sk, so no randomness is
+/// needed; the proof is the same for every call with the same sk and G.
+pub fn prove_dlog_fixed_base<C, F>(sk: C::ScalarExt, g: &C) -> DLogProof<C, F>
+where
+    C: CurveAffine<Base = F>,
+    C::ScalarExt: FromUniformBytes<64>,
+    F: Field,
+{
+    let pk = (*g * sk).to_affine();
+
+    let mut transcript = Blake2bWrite::<_, C, Challenge255<C>>::init(vec![]);
+    transcript.common_point(*g).unwrap();
+    transcript.common_scalar(sk).unwrap();
+    let k = *transcript.squeeze_challenge_scalar::<()>();
+
+    let r = (*g * k).to_affine();
+    let e = DLogProof::<C, F>::challenge(g, &pk, &r);
+    DLogProof::new(r, e, k + e * sk)
+}
+
+/// The discrete log gadgets of the chip, on top of `NativeECOps`.
+pub trait DLogOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Verifies a proof of knowledge of the discrete log of pk to the base G,
+    /// i.e., a Schnorr proof (R, s) with challenge e: s * G = R + e * pk.
+    ///
+    /// The crate has no transcript chip, so the challenge is an input: callers
+    /// derive e = H(R, pk, msg) with a hash chip and pass in its bit cells.
+    /// The commitment R = k * G is not checked, as k is known to the prover only;
+    /// R is bound to the proof through the challenge and the equation above.
+    ///
+    /// Caller must check R and pk are on curve and the bits are binary.
+    /// Returns a synthesis error if the number of bits of e or s is not 256.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_dlog_proof(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        r: &AssignedECPoint<C, F>,
+        pk: &AssignedECPoint<C, F>,
+        e_bits: &[AssignedCell<F, F>],
+        s_bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Verifies a proof of knowledge of the discrete log of pk to a fixed base G,
+    /// as made by `prove_dlog_fixed_base`: s * G = R + e * pk.
+    ///
+    /// R is loaded and checked on curve, and G is fixed to a constant, so G must
+    /// not be the identity. As in `verify_dlog_proof`, the circuit has no hash, so
+    /// the challenge e of the proof is a witness; returns R and the bits of e, for
+    /// the caller to bind e = H(G, pk, R), e.g., by exposing both and checking
+    /// `DLogProof::challenge` outside the circuit.
+    ///
+    /// Caller must check pk is on curve.
+    /// Returns a synthesis error if G is the identity.
+    fn verify_dlog_fixed_base<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        pk: &AssignedECPoint<C, F>,
+        proof: &DLogProof<C, F>,
+        g: &C,
+        offset: &mut usize,
+    ) -> Result<(AssignedECPoint<C, F>, Vec<AssignedCell<F, F>>), Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
+}
+
+impl<C, F> DLogOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Verifies a proof of knowledge of the discrete log of pk to the base G,
+    /// i.e., a Schnorr proof (R, s) with challenge e: s * G = R + e * pk.
+    ///
+    /// The crate has no transcript chip, so the challenge is an input: callers
+    /// derive e = H(R, pk, msg) with a hash chip and pass in its bit cells.
+    /// The commitment R = k * G is not checked, as k is known to the prover only;
+    /// R is bound to the proof through the challenge and the equation above.
+    ///
+    /// Caller must check R and pk are on curve and the bits are binary.
+    /// Returns a synthesis error if the number of bits of e or s is not 256.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_dlog_proof(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        r: &AssignedECPoint<C, F>,
+        pk: &AssignedECPoint<C, F>,
+        e_bits: &[AssignedCell<F, F>],
+        s_bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        // s * G, with G fixed to the generator
+        let gen_assigned = self.load_generator(region, config, offset)?;
+        let lhs = self.point_mul_with_bits(region, config, &gen_assigned, s_bits, offset)?;
+
+        // R + e * pk
+        let e_pk = self.point_mul_with_bits(region, config, pk, e_bits, offset)?;
+        let rhs = self.point_add(region, config, r, &e_pk, offset)?;
+
+        region.constrain_equal(lhs.x.cell(), rhs.x.cell())?;
+        region.constrain_equal(lhs.y.cell(), rhs.y.cell())?;
+        Ok(())
+    }
+
+    /// Verifies a proof of knowledge of the discrete log of pk to a fixed base G,
+    /// as made by `prove_dlog_fixed_base`: s * G = R + e * pk.
+    ///
+    /// R is loaded and checked on curve, and G is fixed to a constant, so G must
+    /// not be the identity. As in `verify_dlog_proof`, the circuit has no hash, so
+    /// the challenge e of the proof is a witness; returns R and the bits of e, for
+    /// the caller to bind e = H(G, pk, R), e.g., by exposing both and checking
+    /// `DLogProof::challenge` outside the circuit.
+    ///
+    /// Caller must check pk is on curve.
+    /// Returns a synthesis error if G is the identity.
+    fn verify_dlog_fixed_base<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        pk: &AssignedECPoint<C, F>,
+        proof: &DLogProof<C, F>,
+        g: &C,
+        offset: &mut usize,
+    ) -> Result<(AssignedECPoint<C, F>, Vec<AssignedCell<F, F>>), Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        let r = self.load_private_point(region, config, &proof.r, offset)?;
+        let e_bits = self.decompose_scalar(region, config, &proof.e, offset)?;
+        let s_bits = self.decompose_scalar(region, config, &proof.s, offset)?;
+
+        // s * G, with G fixed to a constant
+        let g_coordinates = Option::<_>::from(g.coordinates()).ok_or(Error::Synthesis)?;
+        let g_assigned = self.load_private_point_unchecked(region, config, g, offset)?;
+        region.constrain_constant(g_assigned.x.cell(), *g_coordinates.x())?;
+        region.constrain_constant(g_assigned.y.cell(), *g_coordinates.y())?;
+        let lhs = self.point_mul_with_bits(region, config, &g_assigned, &s_bits, offset)?;
+
+        // R + e * pk
+        let e_pk = self.point_mul_with_bits(region, config, pk, &e_bits, offset)?;
+        let rhs = self.point_add(region, config, &r, &e_pk, offset)?;
+
+        region.constrain_equal(lhs.x.cell(), rhs.x.cell())?;
+        region.constrain_equal(lhs.y.cell(), rhs.y.cell())?;
+        Ok((r, e_bits))
+    }
+}
@@ -0,0 +1,183 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::plonk::Error;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::prove_dlog_fixed_base;
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::mock_run;
+use crate::test_circuit::mock_run_with_instance;
+use crate::test_circuit::overwrite_point;
+use crate::util::field_decompose_u128;
+use crate::ArithOps;
+use crate::DLogOps;
+use crate::DLogProof;
+use crate::NativeECOps;
+
+#[test]
+fn test_verify_dlog_proof() {
+    let k = 13;
+
+    // seed: overwrites the seed of the double-then-add loop of s * G, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run = |r: G1Affine, pk: G1Affine, e: Fr, s: Fr, seed: Option<G1Affine>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let e_bits = ec_chip.decompose_scalar(region, config, &e, offset)?;
+            let s_bits = ec_chip.decompose_scalar(region, config, &s, offset)?;
+            let r = ec_chip.load_private_point(region, config, &r, offset)?;
+            let pk = ec_chip.load_private_point(region, config, &pk, offset)?;
+            // the generator takes a row before the loop
+            seed_row.set(*offset + 1);
+            ec_chip.verify_dlog_proof(region, config, &r, &pk, &e_bits, &s_bits, offset)?;
+
+            if let Some(q) = seed {
+                overwrite_point(region, config, seed_row.get(), &q)?;
+            }
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let sk = Fr::random(&mut rng);
+    let pk = (G1Affine::generator() * sk).to_affine();
+    // commitment
+    let nonce = Fr::random(&mut rng);
+    let r = (G1Affine::generator() * nonce).to_affine();
+    // challenge; stands in for the hash of (R, pk, msg)
+    let e = Fr::random(&mut rng);
+    // response
+    let s = nonce + e * sk;
+
+    run(r, pk, e, s, None).assert_satisfied();
+
+    // error case: the response does not match the challenge
+    assert!(run(r, pk, e, s + Fr::one(), None).verify().is_err());
+
+    // error case: the proof is for a different public key
+    assert!(run(r, (pk + G1Affine::generator()).to_affine(), e, s, None)
+        .verify()
+        .is_err());
+
+    // error case: a forged proof with a free e and s, and a seed Q that makes
+    // 2^256 * (Q - G) + s * G = R + e * pk; the seed is a constant
+    {
+        let e = Fr::random(&mut rng);
+        let s = Fr::random(&mut rng);
+        let g = G1Affine::generator();
+        let two_to_256_inv = Fr::from(2).pow_vartime([256]).invert().unwrap();
+        let seed = (g + (r + pk * e - g * s) * two_to_256_inv).to_affine();
+
+        let failures = run(r, pk, e, s, Some(seed)).verify().unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+}
+
+// the public inputs with the challenge recomputed from the transcript
+fn dlog_fixed_base_instance(g: &G1Affine, pk: &G1Affine, r: &G1Affine) -> Vec<Fq> {
+    let e = DLogProof::<G1Affine, Fq>::challenge(g, pk, r);
+    let (high, low) = field_decompose_u128(&e);
+    vec![r.x, r.y, Fq::from_u128(low), Fq::from_u128(high)]
+}
+
+#[test]
+fn test_dlog_fixed_base() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let g = G1::random(&mut rng).to_affine();
+    let sk = Fr::random(&mut rng);
+    let pk = (g * sk).to_affine();
+    let proof = prove_dlog_fixed_base(sk, &g);
+    assert!(proof.verify(&g, &pk));
+
+    // R and e are public, so that e = H(G, pk, R) is checked by the verifier
+    // seed: overwrites the seed of the double-then-add loop of s * G, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run_with_seed = |proof: &DLogProof<G1Affine, Fq>, seed: Option<G1Affine>| {
+        let instance = dlog_fixed_base_instance(&g, &pk, &proof.r);
+        mock_run_with_instance(k, instance, |ec_chip, region, config, offset| {
+            let pk = ec_chip.load_private_point(region, config, &pk, offset)?;
+
+            // R, e, s and G come before the loop
+            let rows = ec_chip.dry_run_offset(|chip, region, config, offset| {
+                chip.load_private_point(region, config, &proof.r, offset)?;
+                chip.decompose_scalar(region, config, &proof.e, offset)?;
+                chip.decompose_scalar(region, config, &proof.s, offset)?;
+                chip.load_private_point_unchecked(region, config, &g, offset)
+            })?;
+            seed_row.set(*offset + rows);
+
+            let (r, e_bits) =
+                ec_chip.verify_dlog_fixed_base(region, config, &pk, proof, &g, offset)?;
+            let e = ec_chip.compose_u128_pair(region, config, &e_bits, offset)?;
+
+            if let Some(q) = seed {
+                overwrite_point(region, config, seed_row.get(), &q)?;
+            }
+
+            Ok(vec![r.x, r.y, e.0, e.1])
+        })
+        .unwrap()
+    };
+    let run = |proof: &DLogProof<G1Affine, Fq>| run_with_seed(proof, None);
+
+    run(&proof).assert_satisfied();
+
+    // error case: a tampered response, or a tampered commitment
+    for tampered in [
+        DLogProof::new(proof.r, proof.e, proof.s + Fr::one()),
+        DLogProof::new((proof.r + g).to_affine(), proof.e, proof.s),
+    ] {
+        assert!(!tampered.verify(&g, &pk));
+        assert!(run(&tampered).verify().is_err());
+    }
+
+    // error case: a forged proof R = s * G - e * pk with a free e satisfies the
+    // equation, but not the public challenge
+    {
+        let e = Fr::random(&mut rng);
+        let s = Fr::random(&mut rng);
+        let r = (g * s - pk * e).to_affine();
+        let forged = DLogProof::new(r, e, s);
+        assert!(!forged.verify(&g, &pk));
+        assert!(run(&forged).verify().is_err());
+    }
+
+    // error case: a forged proof with the public challenge of a free R and a
+    // free s, and a seed Q that makes 2^256 * (Q - G) + s * G = R + e * pk;
+    // the seed is a constant
+    {
+        let r = G1::random(&mut rng).to_affine();
+        let e = DLogProof::<G1Affine, Fq>::challenge(&g, &pk, &r);
+        let s = Fr::random(&mut rng);
+        let forged = DLogProof::new(r, e, s);
+        assert!(!forged.verify(&g, &pk));
+
+        let gen = G1Affine::generator();
+        let two_to_256_inv = Fr::from(2).pow_vartime([256]).invert().unwrap();
+        let seed = (gen + (r + pk * e - g * s) * two_to_256_inv).to_affine();
+
+        let failures = run_with_seed(&forged, Some(seed)).verify().unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+
+    // error case: G is the identity
+    {
+        let identity = G1::identity().to_affine();
+        let res = mock_run(k, |ec_chip, region, config, offset| {
+            let pk = ec_chip.load_private_point(region, config, &pk, offset)?;
+            ec_chip.verify_dlog_fixed_base(region, config, &pk, &proof, &identity, offset)?;
+
+            Ok(())
+        });
+        assert!(matches!(res, Err(Error::Synthesis)));
+    }
+}
@@ -0,0 +1,164 @@
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Expression;
+
+use crate::ECConfig;
+
+#[cfg(test)]
+mod tests;
+
+/// A cell queried by a gate polynomial.
+/// Only compiled with the `dump_gates` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Variable {
+    /// the selector of the given index
+    Selector(usize),
+    /// the fixed column of the given index, at the rotation
+    Fixed { column: usize, rotation: i32 },
+    /// the advice column of the given index, at the rotation
+    Advice { column: usize, rotation: i32 },
+    /// the instance column of the given index, at the rotation
+    Instance { column: usize, rotation: i32 },
+    /// the challenge of the given index
+    Challenge(usize),
+}
+
+/// A monomial `coeff * vars[0] * vars[1] * ...`; the variables are sorted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Term<F> {
+    pub coeff: F,
+    pub vars: Vec<Variable>,
+}
+
+/// A gate polynomial expanded into a sum of monomials, which must vanish
+/// on every row where the gate is enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GatePoly<F> {
+    /// the name of the gate method of `ECConfig`
+    pub name: &'static str,
+    /// the index of the polynomial within the gate
+    pub index: usize,
+    /// the monomials, sorted by their variables, with no zero coefficient
+    pub terms: Vec<Term<F>>,
+}
+
+impl<C, F> ECConfig<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Dump the polynomials of the gates of the chip, without their selectors,
+    /// for external constraint analyzers. Only compiled with the `dump_gates` feature.
+    ///
+    /// The columns are indexed as in the constraint system the config was built from.
+    pub fn dump_gates(&self) -> Vec<GatePoly<F>> {
+        let mut polys = vec![];
+
+        // the gate methods query cells through `VirtualCells`, which only
+        // a gate of a constraint system can provide
+        let mut meta = ConstraintSystem::<F>::default();
+        meta.create_gate("dump gates", |meta| {
+            for (name, expressions) in self.gates(meta) {
+                for (index, expression) in expressions.iter().enumerate() {
+                    polys.push(GatePoly {
+                        name,
+                        index,
+                        terms: expand(expression),
+                    });
+                }
+            }
+
+            // a gate must have a constraint; the constraint system is discarded
+            vec![Expression::Constant(F::ZERO)]
+        });
+
+        polys
+    }
+}
+
+/// Expand an expression into a sum of monomials.
+fn expand<F: PrimeField>(expression: &Expression<F>) -> Vec<Term<F>> {
+    let var = |v: Variable| {
+        vec![Term {
+            coeff: F::ONE,
+            vars: vec![v],
+        }]
+    };
+
+    let terms = expression.evaluate(
+        &|c| {
+            vec![Term {
+                coeff: c,
+                vars: vec![],
+            }]
+        },
+        &|s| var(Variable::Selector(s.index())),
+        &|q| {
+            var(Variable::Fixed {
+                column: q.column_index(),
+                rotation: q.rotation().0,
+            })
+        },
+        &|q| {
+            var(Variable::Advice {
+                column: q.column_index(),
+                rotation: q.rotation().0,
+            })
+        },
+        &|q| {
+            var(Variable::Instance {
+                column: q.column_index(),
+                rotation: q.rotation().0,
+            })
+        },
+        &|c| var(Variable::Challenge(c.index())),
+        &|a| scale(a, -F::ONE),
+        &|a, b| [a, b].concat(),
+        &|a, b| {
+            a.iter()
+                .flat_map(|ta| {
+                    b.iter().map(|tb| Term {
+                        coeff: ta.coeff * tb.coeff,
+                        vars: [ta.vars.clone(), tb.vars.clone()].concat(),
+                    })
+                })
+                .collect()
+        },
+        &scale,
+    );
+
+    normalize(terms)
+}
+
+fn scale<F: PrimeField>(terms: Vec<Term<F>>, c: F) -> Vec<Term<F>> {
+    terms
+        .into_iter()
+        .map(|t| Term {
+            coeff: t.coeff * c,
+            vars: t.vars,
+        })
+        .collect()
+}
+
+// sort the variables of each monomial, merge equal monomials and drop the zero ones
+fn normalize<F: PrimeField>(terms: Vec<Term<F>>) -> Vec<Term<F>> {
+    let mut terms = terms
+        .into_iter()
+        .map(|mut t| {
+            t.vars.sort();
+            t
+        })
+        .collect::<Vec<_>>();
+    terms.sort_by(|a, b| a.vars.cmp(&b.vars));
+
+    let mut res: Vec<Term<F>> = vec![];
+    for t in terms {
+        match res.last_mut() {
+            Some(last) if last.vars == t.vars => last.coeff += t.coeff,
+            _ => res.push(t),
+        }
+    }
+    res.retain(|t| !bool::from(t.coeff.is_zero()));
+    res
+}
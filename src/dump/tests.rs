@@ -0,0 +1,67 @@
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::G1Affine;
+
+use super::Term;
+use super::Variable;
+use crate::chip::ECChip;
+
+#[test]
+fn test_dump_add_gate() {
+    let mut meta = ConstraintSystem::<Fq>::default();
+    let config = ECChip::<G1Affine, Fq>::configure(&mut meta);
+    let polys = config.dump_gates();
+
+    // every gate method is dumped, and expands to a nonzero polynomial
+    assert!(polys.iter().all(|p| !p.terms.is_empty()));
+
+    // a0 + b0 - a1, where a and b are the first two advice columns
+    let a0 = Variable::Advice {
+        column: 0,
+        rotation: 0,
+    };
+    let a1 = Variable::Advice {
+        column: 0,
+        rotation: 1,
+    };
+    let b0 = Variable::Advice {
+        column: 1,
+        rotation: 0,
+    };
+    let add = polys.iter().find(|p| p.name == "add_gate").unwrap();
+    assert_eq!(add.index, 0);
+    assert_eq!(
+        add.terms,
+        vec![
+            Term {
+                coeff: Fq::ONE,
+                vars: vec![a0],
+            },
+            Term {
+                coeff: -Fq::ONE,
+                vars: vec![a1],
+            },
+            Term {
+                coeff: Fq::ONE,
+                vars: vec![b0],
+            },
+        ]
+    );
+
+    // a0 * b0 - a1
+    let mul = polys.iter().find(|p| p.name == "mul_gate").unwrap();
+    assert_eq!(
+        mul.terms,
+        vec![
+            Term {
+                coeff: Fq::ONE,
+                vars: vec![a0, b0],
+            },
+            Term {
+                coeff: -Fq::ONE,
+                vars: vec![a1],
+            },
+        ]
+    );
+}
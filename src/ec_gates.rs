@@ -1,4 +1,5 @@
 use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Layouter;
 use halo2_proofs::circuit::Region;
 use halo2_proofs::circuit::Value;
 use halo2_proofs::halo2curves::ff::PrimeField;
@@ -8,11 +9,21 @@ use halo2_proofs::plonk::Error;
 
 use crate::chip::ECChip;
 use crate::config::ECConfig;
+use crate::util::decompose_u128;
 use crate::util::field_decompose_u128;
 use crate::util::leak;
 use crate::util::neg_generator_times_2_to_256;
+use crate::util::u256_div_rem_small;
+use crate::util::u256_inv_odd;
+use crate::util::u256_mul_wrapping;
+use crate::util::u256_sub_wrapping;
+use crate::util::u256_to_u64_limbs;
 use crate::ArithOps;
 use crate::AssignedECPoint;
+use crate::AssignedJacobianPoint;
+use crate::EccError;
+use crate::MsmAccumulator;
+use crate::PointMulWitness;
 
 #[cfg(test)]
 mod tests;
@@ -30,7 +41,8 @@ where
     /// Loads an ecpoint (x, y) into the circuit as a private input.
     /// Constraints (x, y) is on curve.
     ///
-    /// Will allocate the (x, y) to columns (a, b); and use column c to enforce point is on curve
+    /// Will allocate the (x, y) to columns (a, b), and enable the on curve gate
+    /// on the same row; the point and its check take a single row.
     fn load_private_point(
         &self,
         region: &mut Region<F>,
@@ -55,6 +67,20 @@ where
         offset: &mut usize,
     ) -> Result<Self::AssignedECPoint, Error>;
 
+    /// Assigns a pair (x, y) into the circuit as a private input.
+    /// Do not constraint (x, y) is on curve; caller should call `enforce_on_curve`.
+    ///
+    /// Never builds a `C` from the coordinates, so an invalid or unknown pair
+    /// does not panic during witness generation.
+    fn assign_point_from_xy(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        x: Value<F>,
+        y: Value<F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
     /// For an input pair (x, y), enforces the point is on curve.
     fn enforce_on_curve(
         &self,
@@ -91,7 +117,34 @@ where
         offset: &mut usize,
     ) -> Result<Self::AssignedECPoint, Error>;
 
+    /// Copy an assigned point into the current row.
+    /// Constraints the copied cells equal to the input cells.
+    fn copy_point(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
+    /// Return p3 = p1 + p2 for two assigned points.
+    ///
+    /// The points do not need to be adjacent; they are copied into
+    /// a fresh conditional add block with the bit fixed to 1.
+    ///
+    /// Caller must check p1 and p2 are on curve and p1 != +/- p2.
+    fn point_add(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
     /// Decompose a scalar into a vector of boolean Cells
+    ///
+    /// The layout does not depend on the value of the scalar.
     fn decompose_scalar<S>(
         &self,
         region: &mut Region<F>,
@@ -103,7 +156,31 @@ where
         S: PrimeField<Repr = [u8; 32]>,
         C: CurveAffine<ScalarExt = S>;
 
-    /// Point mul via double-then-add method
+    /// Decompose scalars into vectors of boolean cells, one vector of
+    /// 256 little endian bits per scalar.
+    ///
+    /// Each scalar takes the 192 rows of `decompose_scalar`: a partial decompose
+    /// block already packs 4 bits into both columns, so interleaving the scalars
+    /// would not save any row.
+    fn decompose_scalars<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        scalars: &[C::ScalarExt],
+        offset: &mut usize,
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
+
+    /// Point mul via double-then-add method.
+    ///
+    /// The intermediate values are computed natively by `ECChip::compute_point_mul_witness`,
+    /// then assigned and constrained by `assign_point_mul_witness`.
+    /// Each round takes 4 rows, with p and the bit read by the mul round gate
+    /// instead of being copied in.
+    /// The layout, i.e., the rows and the enabled selectors, does not depend on
+    /// the values of p and s.
     fn point_mul<S>(
         &self,
         region: &mut Region<F>,
@@ -116,84 +193,106 @@ where
         S: PrimeField<Repr = [u8; 32]>,
         C: CurveAffine<ScalarExt = S>;
 
-    /// Pad the row with empty cells.
-    fn pad(
+    /// Point mul via double-then-add method, for a point that is already assigned.
+    ///
+    /// Caller must check p is on curve.
+    /// The layout does not depend on the values of p and s.
+    fn point_mul_assigned<S>(
         &self,
         region: &mut Region<F>,
         config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        s: &C::ScalarExt,
         offset: &mut usize,
-    ) -> Result<(), Error>;
-}
-
-impl<C, F> NativeECOps<C, F> for ECChip<C, F>
-where
-    C: CurveAffine<Base = F>,
-    F: PrimeField<Repr = [u8; 32]>,
-{
-    type Config = ECConfig<C, F>;
-    type AssignedECPoint = AssignedECPoint<C, F>;
+    ) -> Result<Self::AssignedECPoint, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
 
-    /// Loads a pair (x, y) into the circuit as a private input.
-    /// Do not constraint (x, y) is on curve.
+    /// Return n * p for a constant n, i.e., `point_mul_const_scalar` with a u64 scalar.
     ///
-    /// Will allocate the (x, y) to columns (a, b)
-    fn load_private_point_unchecked(
+    /// Returns a synthesis error for n = 0, since infinity is not representable.
+    /// Caller must check p is on curve.
+    fn scalar_multiple(
         &self,
         region: &mut Region<F>,
         config: &Self::Config,
-        p: &C,
+        p: &Self::AssignedECPoint,
+        n: u64,
         offset: &mut usize,
-    ) -> Result<Self::AssignedECPoint, Error> {
-        let p = p.coordinates().unwrap();
-        let x = region.assign_advice(|| "x", config.a, *offset, || Value::known(*p.x()))?;
-        let y = region.assign_advice(|| "y", config.b, *offset, || Value::known(*p.y()))?;
-        let res = Self::AssignedECPoint::new(x, y, *offset);
-        *offset += 1;
-        Ok(res)
-    }
+    ) -> Result<Self::AssignedECPoint, Error>;
 
-    /// For an input pair (x, y), enforces the point is on curve.
-    /// The point must locate at (offset - 1) row
-    fn enforce_on_curve(
+    /// Enforces the x-coordinate of p is in the fixed table,
+    /// which is loaded via `ECChip::load_x_table`.
+    fn enforce_x_in_table(
         &self,
         region: &mut Region<F>,
         config: &Self::Config,
         p: &Self::AssignedECPoint,
         offset: &mut usize,
-    ) -> Result<(), Error> {
-        assert_eq!(
-            p.offset,
-            *offset - 1,
-            "on curve: p is not the latest assigned cells"
-        );
+    ) -> Result<(), Error>;
 
-        #[cfg(feature = "verbose")]
-        {
-            println!(
-                "[on curve check]           selector: {}, point: {}",
-                *offset - 1,
-                p.offset
-            );
-        }
+    /// Loads a point from its x-coordinate and the parity of y, i.e., a compressed public key.
+    /// The y-coordinate is witnessed via a square root.
+    ///
+    /// Constraints
+    /// - the point is on curve, and its x-coordinate equals `x`
+    /// - the parity of y equals `sign`, which is therefore a bit
+    ///
+    /// Returns a synthesis error if x^3 + b is not a square.
+    fn load_compressed_pubkey(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        x: &AssignedCell<F, F>,
+        sign: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
 
-        // | is on curve |   1  |       1      | 0  | 0  | 1  | y1^2 = x1^3 - C::b()
-        config.q_ec_enable.enable(region, *offset - 1)?;
-        config.q3.enable(region, *offset - 1)?;
-        Ok(())
-    }
+    /// Enforces p1 == p2 with a custom gate rather than a copy constraint.
+    /// The points must locate at (offset - 2) and (offset - 1) rows.
+    fn assert_points_equal_gate(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Point mul via double-then-add method, for a scalar given as 256 little endian bit cells.
+    ///
+    /// The layout does not depend on the values of p and the bits.
+    /// Caller must check p is on curve and the bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn point_mul_with_bits(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
 
     /// Input p1 and p2 that are on the curve.
     /// Input an additional bit b.
     ///
     /// Returns
-    /// - p3 = p1 + p2 if b == 1.
-    /// - p3 = p1 if b == 0.
+    /// - p3 = p1 + p2 and the slope of the line through p1 and p2 if b == 1.
+    /// - p3 = p1 and a zero slope if b == 0.
     ///
-    /// Ensures
-    /// - p3 is on curve
+    /// The points and the bit are copied into a fresh conditional add block,
+    /// with the slope stored next to the bit:
     ///
-    /// Caller must check p1 and p2 are on curve and b is a bit.
-    fn conditional_point_add(
+    ///  index  |  a   |  b
+    ///  -------|------|------
+    ///         | p1.x | p1.y
+    ///         | p2.x | p2.y
+    ///         | cond | slope
+    ///  offset | p3.x | p3.y
+    ///
+    /// Caller must check p1 and p2 are on curve, b is a bit and p1 != +/- p2.
+    fn conditional_point_add_with_slope(
         &self,
         region: &mut Region<F>,
         config: &Self::Config,
@@ -201,196 +300,2782 @@ where
         p2: &Self::AssignedECPoint,
         b: &AssignedCell<F, F>,
         offset: &mut usize,
-    ) -> Result<Self::AssignedECPoint, Error> {
-        //  index  |  a   |  b
-        //  -------|------|------
-        //         | p1.x | p1.y
-        //         | p2.x | p2.y
-        //         | cond |
-        //  offset | p3.x | p3.y
-
-        // |      ec add |   4  |       1      | 1  | 0  | 0  | (x1, y1), (x2, y2) and (x3, -y3) are on a same line
-        config.q_ec_enable.enable(region, *offset - 3)?;
-        config.q1.enable(region, *offset - 3)?;
-
-        let p1_witness = p1.witness();
-        let p2_witness = p2.witness();
-        let p3_witness = (p1_witness + p2_witness).to_affine();
-        let bit = leak(&b.value());
-
-        let p3 = if bit == F::ZERO {
-            self.load_private_point_unchecked(region, config, &p1_witness, offset)?
-        } else {
-            self.load_private_point_unchecked(region, config, &p3_witness, offset)?
-        };
-
-        #[cfg(feature = "verbose")]
-        {
-            println!(
-                "[conditional point add]    selector: {}, points: {} {} {}",
-                *offset - 3,
-                p1.offset,
-                p2.offset,
-                p3.offset
-            );
-        }
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error>;
 
-        Ok(p3)
-    }
+    /// Return p3 = p1 + p2 and the slope of the line through p1 and p2.
+    ///
+    /// The points are copied into a fresh add block, with the slope stored above the sum:
+    ///
+    ///  index  |  a    |  b
+    ///  -------|-------|------
+    ///         | p1.x  | p1.y
+    ///         | p2.x  | p2.y
+    ///         | slope |
+    ///  offset | p3.x  | p3.y
+    ///
+    /// The gate checks slope * (p2.x - p1.x) = p2.y - p1.y and derives p3 from the slope:
+    /// p3.x = slope^2 - p1.x - p2.x and p3.y = slope * (p1.x - p3.x) - p1.y.
+    ///
+    /// Caller must check p1 and p2 are on curve and p1 != +/- p2.
+    /// Returns a synthesis error if p1.x == p2.x.
+    fn point_add_with_slope(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error>;
 
-    /// Return p2 = p1 + p1
+    /// Return p2 = p1 + p1 and the slope of the tangent line at p1.
     ///
-    /// Ensures
-    /// - p2 is on curve
+    /// p1 is copied into a fresh double block, with the slope stored above it:
+    ///
+    ///  index  |  a    |  b
+    ///  -------|-------|------
+    ///         | slope |
+    ///         | p1.x  | p1.y
+    ///  offset | p2.x  | p2.y
+    ///
+    /// The gate checks 2 * p1.y * slope = 3 * p1.x^2 and derives p2 from the slope:
+    /// p2.x = slope^2 - 2 * p1.x and p2.y = slope * (p1.x - p2.x) - p1.y.
     ///
     /// Caller must check p1 is on curve.
-    fn point_double(
+    fn point_double_with_slope(
         &self,
         region: &mut Region<F>,
         config: &Self::Config,
         p1: &Self::AssignedECPoint,
         offset: &mut usize,
-    ) -> Result<Self::AssignedECPoint, Error> {
-        assert_eq!(
-            p1.offset,
-            *offset - 1,
-            "point double: p is not the latest assigned cells"
-        );
-
-        // |   ec double |   2  |       1      | 0  | 1  | 0  | (x1, y1) and (x3, -y3) are on a tangential line of the curve
-        config.q_ec_enable.enable(region, *offset - 1)?;
-        config.q2.enable(region, *offset - 1)?;
-        let p1_witness = p1.witness();
-        let p2 = (p1_witness + p1_witness).to_affine();
-        let p2 = self.load_private_point_unchecked(region, config, &p2, offset)?;
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error>;
 
-        #[cfg(feature = "verbose")]
-        {
-            println!(
-                "[point double]             selector: {}, points: {} {}",
-                *offset - 1,
-                p1.offset,
-                p2.offset,
-            );
-        }
+    /// Input the cells of the u128 limbs (hi, lo) of a 256 bit integer v,
+    /// Output the cells of the u128 limbs (hi, lo) of v mod r,
+    /// where r is the order of the scalar field.
+    ///
+    /// Asserts that v = q * r + rem with q < 16 and rem < r, via
+    /// - q * r_lo + rem_lo = lo + carry * 2^128
+    /// - q * r_hi + rem_hi + carry = hi
+    ///
+    /// with a carry < 256; none of the terms wraps around the base field.
+    /// Caller must check hi and lo are u128.
+    fn reduce_mod_r(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        hi: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
 
-        Ok(p2)
-    }
+    /// Input two points p1 and p2,
+    /// Output a bit cell that is 1 iff p1 < p2 in the lexicographic order
+    /// of (x, y), comparing the coordinates as integers.
+    ///
+    /// All coordinates are canonically decomposed into u128 limbs, and
+    /// x * 2^256 + y of both points are compared with a single borrow chain.
+    fn is_point_less_than(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
 
-    /// Decompose a scalar into a vector of boolean Cells
-    fn decompose_scalar<S>(
+    /// Map a field element u to a curve point with try-and-increment, in at most
+    /// `max_iterations` tries.
+    ///
+    /// The candidates are x_i = u + i. The prover witnesses the first index j such
+    /// that v_j = x_j^3 + b is a square, and the map returns (x_j, y) with an even y.
+    /// For soundness, every earlier candidate is proven to be a non-square: with a
+    /// fixed non-residue n, v_i * n is a non-zero square w_i^2 for all i < j.
+    /// The index j is encoded by bits e_i with a single one, and the prefix
+    /// s_i = 1 - (e_0 + ... + e_i) is 1 exactly for i < j, so that per candidate
+    ///  - w_i^2 = v_i * n * s_i
+    ///  - w_i * t_i = s_i
+    ///
+    /// Returns a synthesis error if none of the `max_iterations` candidates is on curve.
+    fn hash_to_curve_try_and_increment(
         &self,
         region: &mut Region<F>,
         config: &Self::Config,
-        s: &C::ScalarExt,
+        u: &AssignedCell<F, F>,
+        max_iterations: usize,
         offset: &mut usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>
-    where
-        S: PrimeField<Repr = [u8; 32]>,
-        C: CurveAffine<ScalarExt = S>,
-    {
-        let (high, low) = field_decompose_u128(s);
-        let (low_cells, _res) = self.decompose_u128(region, config, &low, offset)?;
-        let (high_cells, _res) = self.decompose_u128(region, config, &high, offset)?;
-        let res = [low_cells.as_slice(), high_cells.as_slice()].concat();
+    ) -> Result<Self::AssignedECPoint, Error>;
 
-        Ok(res)
-    }
+    /// Input p1 that is on the curve, and a bit b.
+    ///
+    /// Returns
+    /// - p3 = 2 * p1 if b == 1
+    /// - p3 = p1 if b == 0
+    ///
+    /// p1 and the bit are copied into a fresh conditional double block:
+    ///
+    ///  index  |  a   |  b
+    ///  -------|------|------
+    ///         | p1.x | p1.y
+    ///         | cond |
+    ///  offset | p3.x | p3.y
+    ///
+    /// The bit is constrained to be binary. As with `point_double`, the tangent
+    /// relation alone does not tell 2 * p1 from -p1 when b == 1.
+    fn conditional_double(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
 
-    /// Point mul via double-then-add method
-    // todo: assigned point -> point
-    fn point_mul<S>(
+    /// Input an x-coordinate cell,
+    /// Output a bit cell that is 1 iff there is a y such that (x, y) is on curve.
+    ///
+    /// By Euler's criterion the Legendre symbol l = (x^3 + b)^((p - 1) / 2) is
+    /// 1 for a non-zero square, -1 for a non-square and 0 for zero, which are all
+    /// squares; the exponentiation is computed by square-and-multiply over the
+    /// constant exponent, and the bit is 1 - (l^2 - l) / 2.
+    fn x_is_on_curve(
         &self,
         region: &mut Region<F>,
         config: &Self::Config,
-        p: &C,
-        s: &C::ScalarExt,
+        x: &AssignedCell<F, F>,
         offset: &mut usize,
-    ) -> Result<Self::AssignedECPoint, Error>
-    where
-        S: PrimeField<Repr = [u8; 32]>,
-        C: CurveAffine<ScalarExt = S>,
-    {
-        let gen = C::generator();
-        let bits = self.decompose_scalar(region, config, s, offset)?;
+    ) -> Result<AssignedCell<F, F>, Error>;
 
-        let p_assigned = self.load_private_point(region, config, p, offset)?;
-        let gen_assigned = self.load_private_point(region, config, &gen, offset)?;
+    /// Input two points p1 and p2,
+    /// Output a bit cell that is 1 iff p1 == p2.
+    fn is_equal_points(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
 
-        // we do not have a cell representation for infinity point
-        // therefore we first compute
-        //  res = 2^256 * generator + p *s
-        // ans then subtract 2^256 * generator from res
-        let mut res: AssignedECPoint<C, F> = gen_assigned;
+    /// Input a point p and a public set of points,
+    /// Output a bit cell that is 1 iff p is one of the points in the set.
+    ///
+    /// The set members are fixed constants; the result is
+    /// 1 - prod_i (1 - [p == set_i]), and is 0 for an empty set.
+    fn point_in_set(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        set: &[C],
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Input two points p1 and p2,
+    /// Assert p1.x == p2.x, i.e., p1 == p2 or p1 == -p2,
+    /// with the subtraction p1.x - p2.x = 0.
+    fn assert_same_x(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Input two points p1 and p2,
+    /// Assert p1.x != p2.x, i.e., p1 != +/- p2,
+    /// with a witness inv such that (p1.x - p2.x) * inv = 1.
+    fn assert_different_x(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Convert points in Jacobian coordinates to affine points, with a single
+    /// batch inversion of all Z-coordinates:
+    ///  x = X / Z^2, y = Y / Z^3
+    ///
+    /// Each affine point is copied into a row of its own.
+    /// Caller must check the Jacobian points are on curve.
+    /// Returns a synthesis error if any Z-coordinate is zero.
+    fn batch_jacobian_to_affine(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[AssignedJacobianPoint<C, F>],
+        offset: &mut usize,
+    ) -> Result<Vec<Self::AssignedECPoint>, Error>;
+
+    /// Enforces a batch of points in contiguous rows are on curve.
+    /// The points must be the latest assigned rows, in order.
+    ///
+    /// This enables the on curve selectors on each row, the same as calling
+    /// `enforce_on_curve` per point. A gate applies to a single row, so a
+    /// running product over the rows would still need a selector on every row,
+    /// plus an advice cell per point for the product; per row is optimal.
+    ///
+    /// Returns a synthesis error if the points are not the latest assigned rows.
+    fn enforce_on_curve_batch(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[Self::AssignedECPoint],
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Loads points into contiguous rows as private inputs,
+    /// and enforces they are on curve.
+    fn load_private_points(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[C],
+        offset: &mut usize,
+    ) -> Result<Vec<Self::AssignedECPoint>, Error>;
+
+    /// Input a pair of coordinates that may be off curve,
+    /// Output a bit cell that is 1 iff the pair is on curve.
+    ///
+    /// The bit is `is_zero` of the residual x^3 + b - y^2.
+    fn is_on_curve(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Enforces a pair of coordinates is NOT on curve,
+    /// i.e., the `is_on_curve` bit is 0.
+    fn enforce_not_on_curve(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Point mul via double-then-add method, for a scalar s < 2^n_bits.
+    ///
+    /// Only the n_bits least significant bits of s are decomposed and iterated,
+    /// which saves 256 - n_bits rounds of doubling and conditional add
+    /// compared to `point_mul`. The accumulator is seeded with the generator,
+    /// so 2^n_bits * generator is subtracted in the end.
+    ///
+    /// Returns a synthesis error if n_bits is 0 or larger than 256,
+    /// or if s does not fit in n_bits.
+    fn point_mul_bits<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &C,
+        s: &C::ScalarExt,
+        n_bits: usize,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
+
+    /// Input an index cell and the id of a table registered via
+    /// `ECConfig::register_point_table`,
+    /// Output the point at the index of the table.
+    ///
+    /// The (table id, index, x, y) tuple is looked up in the fixed point tables,
+    /// which must be loaded via `ECChip::load_point_tables`:
+    ///
+    /// |index  |    a   |    b    |
+    /// |-------|--------|---------|
+    /// |offset |  index | table id|
+    /// |       |    x   |    y    |
+    ///
+    /// Returns a synthesis error if the table is not registered, or the index is out of range.
+    fn lookup_constant_point(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        index: &AssignedCell<F, F>,
+        table_id: usize,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
+    /// Input a point p and a bit b,
+    /// Output -p if b = 1, and p otherwise.
+    ///
+    /// The y-coordinate is y * (1 - 2b), and the x-coordinate is copied.
+    /// Caller must check b is binary.
+    fn conditional_negate_point(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        bit: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
+    /// Input a point p,
+    /// Output
+    /// - the canonical representative of +/- p, i.e., the one with an even y
+    /// - a bit that is 1 iff p was negated, i.e., the y of p is odd
+    ///
+    /// The parity is the lowest bit of the canonical decomposition of y.
+    /// Caller must check p is on curve.
+    fn canonicalize_y(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error>;
+
+    /// Assign and constrain the intermediate values of a `point_mul`,
+    /// computed by `ECChip::compute_point_mul_witness`.
+    /// Uses the same layout as `point_mul`.
+    ///
+    /// Returns a synthesis error if the witness is not of 256 rounds.
+    fn assign_point_mul_witness(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        witness: &PointMulWitness<C>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
+    /// Input the cells of the u128 limbs (hi, lo) of a scalar s,
+    /// Output the cells of the u128 limbs (hi, lo) of t = s^-1 mod r,
+    /// where r is the order of the scalar field.
+    ///
+    /// s, t and the quotient q are split into u64 limbs, and
+    /// s * t = q * r + 1 is asserted over the integers column by column:
+    /// - sum_{i+j=k} (s_i * t_j - q_i * r_j) - [k = 0] + carry_{k-1} = carry_k * 2^64
+    ///
+    /// where each carry is shifted by 2^68 into a 72 bit range, and the last carry is 0.
+    /// No term wraps around the base field. Also asserts t < r.
+    ///
+    /// Caller must check hi and lo are u128.
+    /// Returns a synthesis error if s is 0 mod r, or not smaller than r.
+    fn scalar_inverse(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        hi: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+
+    /// Input two points p1 and p2 on curve,
+    /// Output two bit cells (same_x, same_y) that classify the pair:
+    /// - same_x and same_y: p1 == p2, i.e., double
+    /// - same_x and not same_y: p1 == -p2, i.e., the sum is the infinity point
+    /// - otherwise: a generic add
+    ///
+    /// Points on curve with the same x-coordinate have y-coordinates equal up to sign,
+    /// and the curve has no point with y = 0, so the three cases are exhaustive.
+    /// Caller must check p1 and p2 are on curve.
+    fn classify_pair(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+
+    /// Return s * p for a public constant s, via double-then-add over the
+    /// ceil(log2 s) bits of s, most significant first.
+    ///
+    /// The add/skip pattern is fixed by s, so there is no condition row and no
+    /// booleanity constraint: a round is a double, followed by an add if the bit is set.
+    /// The add is derived from its slope, with the accumulator in place:
+    ///
+    ///  index  |  a    |  b
+    ///  -------|-------|------
+    ///         | d.x   | d.y      <- double of the previous row
+    ///         | p.x   | p.y      <- copy of p
+    ///         | slope |
+    ///  offset | s.x   | s.y      <- d + p
+    ///
+    /// The rows reveal s, so this must not be used for secret scalars; s is part of
+    /// the statement. The accumulator is an even multiple of p smaller than 2^129 * p
+    /// before each add, so it never equals +/- p.
+    ///
+    /// Caller must check p is on curve.
+    /// Returns a synthesis error for s = 0, since infinity is not representable.
+    fn point_mul_const_scalar(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        s: u128,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
+    /// Input an affine point p,
+    /// Output the Jacobian point (p.x, p.y, 1).
+    ///
+    /// The coordinate cells of p are reused as X and Y, and Z is a cell fixed to
+    /// the constant 1, which takes a single row.
+    fn affine_to_jacobian(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedJacobianPoint<C, F>, Error>;
+
+    /// Enforces p != -p, i.e., p is not a 2-torsion point:
+    /// the `is_zero` bit of p.y is 0.
+    ///
+    /// `point_double` divides by 2 * p.y and is undefined at 2-torsion points.
+    fn assert_not_two_torsion(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Input a Jacobian accumulator (X1, Y1, Z1) and an affine point (x2, y2),
+    /// Output their sum in Jacobian coordinates, via the mixed addition with Z2 = 1:
+    ///  U2 = x2 * Z1^2, S2 = y2 * Z1^3, H = U2 - X1, r = S2 - Y1
+    ///  X3 = r^2 - H^3 - 2 * X1 * H^2
+    ///  Y3 = r * (X1 * H^2 - X3) - Y1 * H^3
+    ///  Z3 = Z1 * H
+    ///
+    /// Saves the Z2^2 and Z2^3 products, and the multiplications by them,
+    /// of a full Jacobian addition.
+    /// Caller must check the accumulator is not infinity, i.e., Z1 != 0, and
+    /// it is not p or -p, where H = 0 and Z3 = 0.
+    fn mixed_add(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        jac_acc: &AssignedJacobianPoint<C, F>,
+        affine_p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedJacobianPoint<C, F>, Error>;
+
+    /// Loads a pair (x, y) into the circuit as a private input, with the
+    /// on-curve residual x^3 + b - y^2, which is 0 iff the pair is on curve.
+    ///
+    /// The pair is not constrained to be on curve; callers can constrain the
+    /// residual, or use it as a penalty. As in `assign_point_from_xy`, the pair
+    /// may be off curve or unknown without a panic during witness generation.
+    fn load_private_point_with_residual(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        x: Value<F>,
+        y: Value<F>,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error>;
+
+    /// Point mul via double-then-add method, as `point_mul`, also returning the
+    /// accumulator after each round, most significant bit first.
+    ///
+    /// The circuit has no cell for the point at infinity, so the accumulator is
+    /// seeded with the generator: after the round of bit i, it is
+    /// 2^(256 - i) * generator + (s >> i) * p. The last one is the result plus
+    /// 2^256 * generator. The accumulators are the cells of the conditional adds
+    /// of the rounds, so this uses the same rows as `point_mul`.
+    ///
+    /// Returns s * p and the 256 accumulators.
+    fn point_mul_trace<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &C,
+        s: &C::ScalarExt,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, Vec<Self::AssignedECPoint>), Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
+
+    /// As `conditional_point_add`, where p2 may also be the identity, encoded
+    /// as (0, 0) like the identity of halo2curves; (0, 0) is not on curve as
+    /// b != 0, so the encoding is unambiguous.
+    ///
+    /// Returns
+    /// - p3 = p1 + p2 if b == 1 and p2 is not the identity.
+    /// - p3 = p1 if b == 0, or p2 is the identity.
+    ///
+    /// The gate of `conditional_point_add` only holds p2 when b == 1, so this
+    /// adds with the bit b * (1 - is_identity(p2)). p1 cannot be the identity:
+    /// the gate enforces the output on curve, and the identity is not.
+    ///
+    /// Caller must check p1 is on curve, p2 is on curve or (0, 0), b is a bit,
+    /// and p1 != +/- p2.
+    fn conditional_point_add_with_identity(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
+    /// Return s * (p_1 + ... + p_m) for points that share the scalar s,
+    /// given as 256 little endian bit cells.
+    ///
+    /// A joint double-then-add over (p_i, s) loads a copy of each bit for each
+    /// of the m conditional adds of a round, as the gate reads the bit inside
+    /// the add block. This sums the bases once instead, so each round has a
+    /// single conditional add and a single copy of the bit.
+    ///
+    /// Caller must check the points are on curve, no partial sum of the points
+    /// equals +/- the next point, and the bits are binary.
+    /// Returns a synthesis error if there is no point or the number of bits is not 256.
+    fn point_mul_shared_bits(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[Self::AssignedECPoint],
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>;
+
+    /// Return the chain p, 2p, 4p, ..., 2^k * p of k + 1 points.
+    ///
+    /// p is copied into the first row, and each of the k doublings takes the
+    /// row right below the previous point, which is the input of its double gate:
+    ///
+    ///  index  |  a        |  b
+    ///  -------|-----------|-----------
+    ///         | p.x       | p.y           <- copy of p
+    ///         | 2p.x      | 2p.y
+    ///         | ...       | ...
+    ///  offset | 2^k p.x   | 2^k p.y
+    ///
+    /// Caller must check p is on curve.
+    fn doubling_chain(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        k: usize,
+        offset: &mut usize,
+    ) -> Result<Vec<Self::AssignedECPoint>, Error>;
+
+    /// Pad the row with `ECChip::min_blinding_rows` rows of empty cells.
+    fn pad(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Pad the row with enough rows of empty cells for `num_blinding_rows`
+    /// blinding rows, i.e., `max(num_blinding_rows + 1, ECChip::min_blinding_rows)`
+    /// rows.
+    ///
+    /// `pad` assumes the blinding rows halo2 reserves for the chip alone.
+    /// A host circuit that queries one of its columns at more rotations makes
+    /// halo2 reserve more of them; pass `ConstraintSystem::blinding_factors` of
+    /// the host's constraint system so that the padding still covers them.
+    fn pad_for_blinding(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        num_blinding_rows: usize,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+
+    /// Pad the row with cells that are copied from the constant zero.
+    ///
+    /// Uses the same number of rows as `pad`. Each cell additionally
+    /// occupies a fixed cell and a permutation check, so this does not
+    /// make the circuit smaller; it pins the padding to zero for
+    /// callers that want the padding to be part of the statement.
+    fn pad_constant(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+}
+
+impl<C, F> NativeECOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    type Config = ECConfig<C, F>;
+    type AssignedECPoint = AssignedECPoint<C, F>;
+
+    /// Loads a pair (x, y) into the circuit as a private input.
+    /// Do not constraint (x, y) is on curve.
+    ///
+    /// Will allocate the (x, y) to columns (a, b)
+    fn load_private_point_unchecked(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &C,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let p = p.coordinates().unwrap();
+        let x = region.assign_advice(|| "x", config.a, *offset, || Value::known(*p.x()))?;
+        let y = region.assign_advice(|| "y", config.b, *offset, || Value::known(*p.y()))?;
+        let res = Self::AssignedECPoint::new(x, y, *offset);
+        *offset += 1;
+        Ok(res)
+    }
+
+    /// Assigns a pair (x, y) into the circuit as a private input.
+    /// Do not constraint (x, y) is on curve; caller should call `enforce_on_curve`.
+    ///
+    /// Will allocate the (x, y) to columns (a, b)
+    fn assign_point_from_xy(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        x: Value<F>,
+        y: Value<F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let x = region.assign_advice(|| "x", config.a, *offset, || x)?;
+        let y = region.assign_advice(|| "y", config.b, *offset, || y)?;
+        let res = Self::AssignedECPoint::new(x, y, *offset);
+        *offset += 1;
+        Ok(res)
+    }
+
+    /// For an input pair (x, y), enforces the point is on curve.
+    /// The point must locate at (offset - 1) row
+    fn enforce_on_curve(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        assert_eq!(
+            p.offset,
+            *offset - 1,
+            "on curve: p is not the latest assigned cells"
+        );
+
+        #[cfg(feature = "verbose")]
+        {
+            println!(
+                "[on curve check]           selector: {}, point: {}",
+                *offset - 1,
+                p.offset
+            );
+        }
+
+        // | is on curve |   1  |       1      | 0  | 0  | 1  | y1^2 = x1^3 + C::b()
+        config.q_ec_enable.enable(region, *offset - 1)?;
+        config.q3.enable(region, *offset - 1)?;
+        Ok(())
+    }
+
+    /// Input p1 and p2 that are on the curve.
+    /// Input an additional bit b.
+    ///
+    /// Returns
+    /// - p3 = p1 + p2 if b == 1.
+    /// - p3 = p1 if b == 0.
+    ///
+    /// Ensures
+    /// - p3 is on curve
+    ///
+    /// Caller must check p1 and p2 are on curve and b is a bit.
+    fn conditional_point_add(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let p1_witness = p1.witness();
+        let p2_witness = p2.witness();
+        let p3_witness = if leak(&b.value()) == F::ZERO {
+            p1_witness
+        } else {
+            (p1_witness + p2_witness).to_affine()
+        };
+
+        let p3 = self.conditional_point_add_with_witness(region, config, &p3_witness, offset)?;
+
+        #[cfg(feature = "verbose")]
+        {
+            println!(
+                "[conditional point add]    selector: {}, points: {} {} {}",
+                *offset - 3,
+                p1.offset,
+                p2.offset,
+                p3.offset
+            );
+        }
+
+        Ok(p3)
+    }
+
+    /// Return p2 = p1 + p1
+    ///
+    /// Ensures
+    /// - p2 is on curve
+    ///
+    /// Caller must check p1 is on curve.
+    fn point_double(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let p1_witness = p1.witness();
+        let p2_witness = (p1_witness + p1_witness).to_affine();
+        self.point_double_with_witness(region, config, p1, &p2_witness, offset)
+    }
+
+    /// Copy an assigned point into the current row.
+    /// Constraints the copied cells equal to the input cells.
+    fn copy_point(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let x = p.x.copy_advice(|| "x", region, config.a, *offset)?;
+        let y = p.y.copy_advice(|| "y", region, config.b, *offset)?;
+        let res = Self::AssignedECPoint::new(x, y, *offset);
+        *offset += 1;
+        Ok(res)
+    }
+
+    /// Return p3 = p1 + p2 for two assigned points.
+    ///
+    /// The points do not need to be adjacent; they are copied into
+    /// a fresh conditional add block with the bit fixed to 1.
+    ///
+    /// Caller must check p1 and p2 are on curve and p1 != +/- p2.
+    fn point_add(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let p1 = self.copy_point(region, config, p1, offset)?;
+        let p2 = self.copy_point(region, config, p2, offset)?;
+        let bit = self.load_two_private_fields(region, config, &F::ONE, &F::ZERO, offset)?;
+        region.constrain_constant(bit[0].cell(), F::ONE)?;
+        self.conditional_point_add(region, config, &p1, &p2, &bit[0], offset)
+    }
+
+    /// Decompose a scalar into a vector of boolean Cells
+    ///
+    /// The layout does not depend on the value of the scalar.
+    fn decompose_scalar<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        s: &C::ScalarExt,
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        let (high, low) = field_decompose_u128(s);
+        let bits = [decompose_u128(&low), decompose_u128(&high)]
+            .concat()
+            .iter()
+            .map(|&x| F::from(x))
+            .collect::<Vec<_>>();
+        let (res, _low, _high) = self.assign_u128_pair_blocks(region, config, &bits, offset)?;
+
+        Ok(res)
+    }
+
+    /// Decompose scalars into vectors of boolean cells, one vector of
+    /// 256 little endian bits per scalar.
+    ///
+    /// Each scalar takes the 192 rows of `decompose_scalar`: a partial decompose
+    /// block already packs 4 bits into both columns, so interleaving the scalars
+    /// would not save any row.
+    fn decompose_scalars<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        scalars: &[C::ScalarExt],
+        offset: &mut usize,
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        scalars
+            .iter()
+            .map(|s| self.decompose_scalar(region, config, s, offset))
+            .collect()
+    }
+
+    /// Point mul via double-then-add method.
+    ///
+    /// The intermediate values are computed natively by `ECChip::compute_point_mul_witness`,
+    /// then assigned and constrained by `assign_point_mul_witness`.
+    /// Each round takes 4 rows, with p and the bit read by the mul round gate
+    /// instead of being copied in.
+    /// The layout, i.e., the rows and the enabled selectors, does not depend on
+    /// the values of p and s.
+    fn point_mul<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &C,
+        s: &C::ScalarExt,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        let witness = Self::compute_point_mul_witness(p, s);
+        self.assign_point_mul_witness(region, config, &witness, offset)
+    }
+
+    /// Point mul via double-then-add method, for a point that is already assigned.
+    ///
+    /// Caller must check p is on curve.
+    /// The layout does not depend on the values of p and s.
+    fn point_mul_assigned<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p_assigned: &Self::AssignedECPoint,
+        s: &C::ScalarExt,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        let witness = Self::compute_point_mul_witness(&p_assigned.witness(), s);
+
+        // the only copy of p; the rounds carry it over via the mul round gate
+        let p_copied = self.copy_point(region, config, p_assigned, offset)?;
+        self.assign_point_mul_rounds(region, config, &p_copied, &witness, offset)
+    }
+
+    /// Return n * p for a constant n, i.e., `point_mul_const_scalar` with a u64 scalar.
+    ///
+    /// Returns a synthesis error for n = 0, since infinity is not representable.
+    /// Caller must check p is on curve.
+    fn scalar_multiple(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        n: u64,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        self.point_mul_const_scalar(region, config, p, n as u128, offset)
+    }
+
+    /// Enforces the x-coordinate of p is in the fixed table,
+    /// which is loaded via `ECChip::load_x_table`.
+    fn enforce_x_in_table(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let p = self.copy_point(region, config, p, offset)?;
+        config.q_x_table.enable(region, p.offset)
+    }
+
+    /// Loads a point from its x-coordinate and the parity of y, i.e., a compressed public key.
+    /// The y-coordinate is witnessed via a square root.
+    ///
+    /// Constraints
+    /// - the point is on curve, and its x-coordinate equals `x`
+    /// - the parity of y equals `sign`, which is therefore a bit
+    ///
+    /// Returns a synthesis error if x^3 + b is not a square.
+    fn load_compressed_pubkey(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        x: &AssignedCell<F, F>,
+        sign: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let x_value = leak(&x.value());
+        let sign_value = leak(&sign.value()) == F::ONE;
+        let y_value = (x_value.square() * x_value + C::b()).sqrt();
+        // the values are unknown when the floor planner measures the region;
+        // only a known x can fail to decompress
+        x.value()
+            .error_if_known_and(|_| bool::from(y_value.is_none()))?;
+        let y_value = y_value.unwrap_or(F::ZERO);
+        let y_value = if bool::from(y_value.is_odd()) == sign_value {
+            y_value
+        } else {
+            -y_value
+        };
+
+        let p = self.assign_point_from_xy(
+            region,
+            config,
+            Value::known(x_value),
+            Value::known(y_value),
+            offset,
+        )?;
+        self.enforce_on_curve(region, config, &p, offset)?;
+        region.constrain_equal(p.x.cell(), x.cell())?;
+
+        // the parity of y matches the sign
+        let y_bits = self.decompose_field(region, config, &p.y, offset)?;
+        region.constrain_equal(y_bits[0].cell(), sign.cell())?;
+
+        Ok(p)
+    }
+
+    /// Enforces p1 == p2 with a custom gate rather than a copy constraint.
+    /// The points must locate at (offset - 2) and (offset - 1) rows.
+    fn assert_points_equal_gate(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        assert_eq!(
+            p1.offset,
+            *offset - 2,
+            "point equal: p1 is not the second latest assigned cells"
+        );
+        assert_eq!(
+            p2.offset,
+            *offset - 1,
+            "point equal: p2 is not the latest assigned cells"
+        );
+
+        config.q_point_eq.enable(region, p1.offset)
+    }
+
+    /// Point mul via double-then-add method, for a scalar given as 256 little endian bit cells.
+    ///
+    /// The layout does not depend on the values of p and the bits.
+    /// Caller must check p is on curve and the bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn point_mul_with_bits(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        // the offset generator correction is for 256 doublings
+        if bits.len() != 256 {
+            return Err(Error::Synthesis);
+        }
+
+        let res = self.double_then_add(region, config, p, bits, offset)?;
+
+        // now we subtract 2^256 * generator from res
+        self.subtract_offset_generator(region, config, &res, offset)
+    }
+
+    /// Input p1 and p2 that are on the curve.
+    /// Input an additional bit b.
+    ///
+    /// Returns
+    /// - p3 = p1 + p2 and the slope of the line through p1 and p2 if b == 1.
+    /// - p3 = p1 and a zero slope if b == 0.
+    ///
+    /// The points and the bit are copied into a fresh conditional add block,
+    /// with the slope stored next to the bit:
+    ///
+    ///  index  |  a   |  b
+    ///  -------|------|------
+    ///         | p1.x | p1.y
+    ///         | p2.x | p2.y
+    ///         | cond | slope
+    ///  offset | p3.x | p3.y
+    ///
+    /// Caller must check p1 and p2 are on curve, b is a bit and p1 != +/- p2.
+    fn conditional_point_add_with_slope(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error> {
+        let p1 = self.copy_point(region, config, p1, offset)?;
+        let p2 = self.copy_point(region, config, p2, offset)?;
+
+        let bit = leak(&b.value());
+        let slope = if bit == F::ZERO {
+            F::ZERO
+        } else {
+            let x1 = leak(&p1.x.value());
+            let y1 = leak(&p1.y.value());
+            let x2 = leak(&p2.x.value());
+            let y2 = leak(&p2.y.value());
+            let inv = (x2 - x1).invert();
+            // the values are unknown when the floor planner measures the region
+            p1.x.value()
+                .error_if_known_and(|_| bool::from(inv.is_none()))?;
+            (y2 - y1) * inv.unwrap_or(F::ZERO)
+        };
+        let bit_copied = b.copy_advice(|| "cond", region, config.a, *offset)?;
+        let slope = region.assign_advice(|| "slope", config.b, *offset, || Value::known(slope))?;
+        *offset += 1;
+
+        config.q_add_slope.enable(region, *offset - 3)?;
+        let p3 = self.conditional_point_add(region, config, &p1, &p2, &bit_copied, offset)?;
+
+        Ok((p3, slope))
+    }
+
+    /// Return p3 = p1 + p2 and the slope of the line through p1 and p2.
+    ///
+    /// The points are copied into a fresh add block, with the slope stored above the sum:
+    ///
+    ///  index  |  a    |  b
+    ///  -------|-------|------
+    ///         | p1.x  | p1.y
+    ///         | p2.x  | p2.y
+    ///         | slope |
+    ///  offset | p3.x  | p3.y
+    ///
+    /// The gate checks slope * (p2.x - p1.x) = p2.y - p1.y and derives p3 from the slope:
+    /// p3.x = slope^2 - p1.x - p2.x and p3.y = slope * (p1.x - p3.x) - p1.y.
+    ///
+    /// Caller must check p1 and p2 are on curve and p1 != +/- p2.
+    /// Returns a synthesis error if p1.x == p2.x.
+    fn point_add_with_slope(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error> {
+        let x1 = leak(&p1.x.value());
+        let y1 = leak(&p1.y.value());
+        let x2 = leak(&p2.x.value());
+        let y2 = leak(&p2.y.value());
+        let inv = (x2 - x1).invert();
+        // the values are unknown when the floor planner measures the region
+        p1.x.value()
+            .error_if_known_and(|_| bool::from(inv.is_none()))?;
+        let slope = (y2 - y1) * inv.unwrap_or(F::ZERO);
+        let p3 = (p1.witness() + p2.witness()).to_affine();
+
+        config.q_add_with_slope.enable(region, *offset)?;
+        self.copy_point(region, config, p1, offset)?;
+        self.copy_point(region, config, p2, offset)?;
+        let slope = self.load_private_field(region, config, &slope, offset)?;
+        let p3 = self.load_private_point_unchecked(region, config, &p3, offset)?;
+
+        Ok((p3, slope))
+    }
+
+    /// Return p2 = p1 + p1 and the slope of the tangent line at p1.
+    ///
+    /// p1 is copied into a fresh double block, with the slope stored above it:
+    ///
+    ///  index  |  a    |  b
+    ///  -------|-------|------
+    ///         | slope |
+    ///         | p1.x  | p1.y
+    ///  offset | p2.x  | p2.y
+    ///
+    /// The gate checks 2 * p1.y * slope = 3 * p1.x^2 and derives p2 from the slope:
+    /// p2.x = slope^2 - 2 * p1.x and p2.y = slope * (p1.x - p2.x) - p1.y.
+    ///
+    /// Caller must check p1 is on curve.
+    fn point_double_with_slope(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error> {
+        let x1 = leak(&p1.x.value());
+        let y1 = leak(&p1.y.value());
+        let inv = (y1 + y1).invert();
+        // the values are unknown when the floor planner measures the region
+        p1.y.value()
+            .error_if_known_and(|_| bool::from(inv.is_none()))?;
+        let slope = x1 * x1 * F::from(3) * inv.unwrap_or(F::ZERO);
+
+        let p2 = (p1.witness() + p1.witness()).to_affine();
+
+        config.q_double_slope.enable(region, *offset)?;
+        let slope = self.load_private_field(region, config, &slope, offset)?;
+        self.copy_point(region, config, p1, offset)?;
+        let p2 = self.load_private_point_unchecked(region, config, &p2, offset)?;
+
+        Ok((p2, slope))
+    }
+
+    /// Input the cells of the u128 limbs (hi, lo) of a 256 bit integer v,
+    /// Output the cells of the u128 limbs (hi, lo) of v mod r,
+    /// where r is the order of the scalar field.
+    ///
+    /// Asserts that v = q * r + rem with q < 16 and rem < r, via
+    /// - q * r_lo + rem_lo = lo + carry * 2^128
+    /// - q * r_hi + rem_hi + carry = hi
+    ///
+    /// with a carry < 256; none of the terms wraps around the base field.
+    /// Caller must check hi and lo are u128.
+    fn reduce_mod_r(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        hi: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let two_to_128 = F::from_u128(1 << 127) * F::from(2);
+
+        // r is odd, so r_lo = m_lo + 1 does not overflow
+        let (m_hi, m_lo) = field_decompose_u128(&-C::ScalarExt::ONE);
+        let (r_hi, r_lo) = (m_hi, m_lo + 1);
+
+        let (_, v_hi) = field_decompose_u128(&leak(&hi.value()));
+        let (_, v_lo) = field_decompose_u128(&leak(&lo.value()));
+        let (q, (rem_hi, rem_lo)) = u256_div_rem_small((v_hi, v_lo), (r_hi, r_lo));
+        let carry = v_hi
+            .wrapping_sub(r_hi.wrapping_mul(q as u128))
+            .wrapping_sub(rem_hi) as u64;
+
+        // q < 16 and carry < 256
+        let q_bits = (0..4).map(|i| F::from((q >> i) & 1)).collect::<Vec<_>>();
+        let (_, q_cell) = self.assign_bit_blocks(region, config, &q_bits, offset)?;
+        let carry_bits = (0..8)
+            .map(|i| F::from((carry >> i) & 1))
+            .collect::<Vec<_>>();
+        let (_, carry_cell) = self.assign_bit_blocks(region, config, &carry_bits, offset)?;
+
+        // rem_hi and rem_lo are u128
+        let (_, rem_lo_cell) = self.decompose_u128(region, config, &rem_lo, offset)?;
+        let (_, rem_hi_cell) = self.decompose_u128(region, config, &rem_hi, offset)?;
+
+        // q * r_lo + rem_lo = lo + carry * 2^128
+        let lhs = self.mul_const(region, config, &q_cell, &F::from_u128(r_lo), offset)?;
+        let lhs = self.add_cells(region, config, &lhs, &rem_lo_cell, offset)?;
+        let rhs = self.mul_const(region, config, &carry_cell, &two_to_128, offset)?;
+        let rhs = self.add_cells(region, config, lo, &rhs, offset)?;
+        region.constrain_equal(lhs.cell(), rhs.cell())?;
+
+        // q * r_hi + rem_hi + carry = hi
+        let lhs = self.mul_const(region, config, &q_cell, &F::from_u128(r_hi), offset)?;
+        let lhs = self.add_cells(region, config, &lhs, &rem_hi_cell, offset)?;
+        let lhs = self.add_cells(region, config, &lhs, &carry_cell, offset)?;
+        region.constrain_equal(lhs.cell(), hi.cell())?;
+
+        // rem <= r - 1
+        self.enforce_u256_le_const(
+            region,
+            config,
+            &rem_hi_cell,
+            &rem_lo_cell,
+            &(m_hi, m_lo),
+            offset,
+        )?;
+
+        Ok((rem_hi_cell, rem_lo_cell))
+    }
+
+    /// Input two points p1 and p2,
+    /// Output a bit cell that is 1 iff p1 < p2 in the lexicographic order
+    /// of (x, y), comparing the coordinates as integers.
+    ///
+    /// All coordinates are canonically decomposed into u128 limbs, and
+    /// x * 2^256 + y of both points are compared with a single borrow chain.
+    fn is_point_less_than(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (_, x1_high, x1_low) = self.decompose_field_limbs(region, config, &p1.x, offset)?;
+        let (_, y1_high, y1_low) = self.decompose_field_limbs(region, config, &p1.y, offset)?;
+        let (_, x2_high, x2_low) = self.decompose_field_limbs(region, config, &p2.x, offset)?;
+        let (_, y2_high, y2_low) = self.decompose_field_limbs(region, config, &p2.y, offset)?;
+        self.limbs_less_than(
+            region,
+            config,
+            &[y1_low, y1_high, x1_low, x1_high],
+            &[y2_low, y2_high, x2_low, x2_high],
+            offset,
+        )
+    }
+
+    /// Map a field element u to a curve point with try-and-increment, in at most
+    /// `max_iterations` tries.
+    ///
+    /// The candidates are x_i = u + i. The prover witnesses the first index j such
+    /// that v_j = x_j^3 + b is a square, and the map returns (x_j, y) with an even y.
+    /// For soundness, every earlier candidate is proven to be a non-square: with a
+    /// fixed non-residue n, v_i * n is a non-zero square w_i^2 for all i < j.
+    /// The index j is encoded by bits e_i with a single one, and the prefix
+    /// s_i = 1 - (e_0 + ... + e_i) is 1 exactly for i < j, so that per candidate
+    ///  - w_i^2 = v_i * n * s_i
+    ///  - w_i * t_i = s_i
+    ///
+    /// Returns a synthesis error if none of the `max_iterations` candidates is on curve.
+    fn hash_to_curve_try_and_increment(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        u: &AssignedCell<F, F>,
+        max_iterations: usize,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        // a generator of the multiplicative group is a non-residue
+        let non_residue = F::MULTIPLICATIVE_GENERATOR;
+
+        // the first candidate that is on curve
+        let u_value = leak(&u.value());
+        let j = (0..max_iterations).find(|&i| {
+            let x = u_value + F::from(i as u64);
+            bool::from((x.square() * x + C::b()).sqrt().is_some())
+        });
+        // the values are unknown when the floor planner measures the region
+        u.value().error_if_known_and(|_| j.is_none())?;
+        let j = j.unwrap_or(0);
+
+        let mut prefix: Option<AssignedCell<F, F>> = None;
+        let mut index: Option<AssignedCell<F, F>> = None;
+        for i in 0..max_iterations {
+            // v_i = (u + i)^3 + b
+            let x = self.add_const(region, config, u, &F::from(i as u64), offset)?;
+            let x_square = self.mul_cells(region, config, &x, &x, offset)?;
+            let x_cube = self.mul_cells(region, config, &x_square, &x, offset)?;
+            let v = self.add_const(region, config, &x_cube, &C::b(), offset)?;
+
+            // e_i is a bit, and s_i = 1 - (e_0 + ... + e_i)
+            let e = self.load_private_field(region, config, &F::from((i == j) as u64), offset)?;
+            self.enforce_bit(region, config, &e, offset)?;
+            let e_sum = match &prefix {
+                Some(prev) => self.add_cells(region, config, prev, &e, offset)?,
+                None => e.clone(),
+            };
+            let s = self.mul_const(region, config, &e_sum, &-F::ONE, offset)?;
+            let s = self.add_const(region, config, &s, &F::ONE, offset)?;
+
+            // j = sum i * e_i
+            let i_e = self.mul_const(region, config, &e, &F::from(i as u64), offset)?;
+            index = Some(match &index {
+                Some(prev) => self.add_cells(region, config, prev, &i_e, offset)?,
+                None => i_e,
+            });
+            prefix = Some(e_sum);
+
+            // w_i^2 = v_i * n * s_i
+            let (w_value, t_value) = if i < j {
+                // v_i is a non-residue, unless the values are unknown
+                let w = (leak(&v.value()) * non_residue).sqrt().unwrap_or(F::ZERO);
+                (w, w.invert().unwrap_or(F::ZERO))
+            } else {
+                (F::ZERO, F::ZERO)
+            };
+            let v_n = self.mul_const(region, config, &v, &non_residue, offset)?;
+            let rhs = self.mul_cells(region, config, &v_n, &s, offset)?;
+            let [w, t] =
+                self.load_two_private_fields(region, config, &w_value, &t_value, offset)?;
+            let w_square = self.mul_cells(region, config, &w, &w, offset)?;
+            region.constrain_equal(w_square.cell(), rhs.cell())?;
+
+            // w_i * t_i = s_i, i.e., w_i is non-zero for i < j
+            let w_t = self.mul_cells(region, config, &w, &t, offset)?;
+            region.constrain_equal(w_t.cell(), s.cell())?;
+        }
+
+        // exactly one e_i is set; there is at least one candidate since j was found
+        let (e_sum, index) = (prefix.unwrap(), index.unwrap());
+        region.constrain_constant(e_sum.cell(), F::ONE)?;
+
+        // the point (u + j, y) with an even y
+        let x = self.add_cells(region, config, u, &index, offset)?;
+        let sign = self.load_constant(region, config, &F::ZERO, offset)?;
+        self.load_compressed_pubkey(region, config, &x, &sign, offset)
+    }
+
+    /// Input p1 that is on the curve, and a bit b.
+    ///
+    /// Returns
+    /// - p3 = 2 * p1 if b == 1
+    /// - p3 = p1 if b == 0
+    ///
+    /// p1 and the bit are copied into a fresh conditional double block:
+    ///
+    ///  index  |  a   |  b
+    ///  -------|------|------
+    ///         | p1.x | p1.y
+    ///         | cond |
+    ///  offset | p3.x | p3.y
+    ///
+    /// The bit is constrained to be binary. As with `point_double`, the tangent
+    /// relation alone does not tell 2 * p1 from -p1 when b == 1.
+    fn conditional_double(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let p1 = self.copy_point(region, config, p1, offset)?;
+        config.q_cond_double.enable(region, p1.offset)?;
+
+        b.copy_advice(|| "cond", region, config.a, *offset)?;
+        region.assign_advice(|| "pad", config.b, *offset, || Value::known(F::ZERO))?;
+        *offset += 1;
+
+        let p1_witness = p1.witness();
+        let p3 = if leak(&b.value()) == F::ONE {
+            (p1_witness + p1_witness).to_affine()
+        } else {
+            p1_witness
+        };
+        self.load_private_point_unchecked(region, config, &p3, offset)
+    }
+
+    /// Input an x-coordinate cell,
+    /// Output a bit cell that is 1 iff there is a y such that (x, y) is on curve.
+    ///
+    /// By Euler's criterion the Legendre symbol l = (x^3 + b)^((p - 1) / 2) is
+    /// 1 for a non-zero square, -1 for a non-square and 0 for zero, which are all
+    /// squares; the exponentiation is computed by square-and-multiply over the
+    /// constant exponent, and the bit is 1 - (l^2 - l) / 2.
+    fn x_is_on_curve(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        x: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // v = x^3 + b
+        let x_square = self.mul_cells(region, config, x, x, offset)?;
+        let x_cube = self.mul_cells(region, config, &x_square, x, offset)?;
+        let v = self.add_const(region, config, &x_cube, &C::b(), offset)?;
+
+        // (p - 1) / 2 in big endian bits
+        let (high, low) = field_decompose_u128(&-F::ONE);
+        let exponent_high = high >> 1;
+        let exponent_low = (low >> 1) | (high << 127);
+        let bits = (0..128)
+            .rev()
+            .map(|i| (exponent_high >> i) & 1 == 1)
+            .chain((0..128).rev().map(|i| (exponent_low >> i) & 1 == 1))
+            .skip_while(|b| !b)
+            .collect::<Vec<_>>();
+
+        // l = v^((p - 1) / 2); the leading bit is 1
+        let mut l = v.clone();
+        for &bit in bits.iter().skip(1) {
+            l = self.mul_cells(region, config, &l, &l, offset)?;
+            if bit {
+                l = self.mul_cells(region, config, &l, &v, offset)?;
+            }
+        }
+
+        // 1 - (l^2 - l) / 2
+        let l_square = self.mul_cells(region, config, &l, &l, offset)?;
+        let neg_l = self.mul_const(region, config, &l, &-F::ONE, offset)?;
+        let t = self.add_cells(region, config, &l_square, &neg_l, offset)?;
+        let t = self.mul_const(region, config, &t, &-F::from(2).invert().unwrap(), offset)?;
+        self.add_const(region, config, &t, &F::ONE, offset)
+    }
+
+    /// Input two points p1 and p2,
+    /// Output a bit cell that is 1 iff p1 == p2.
+    fn is_equal_points(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let neg_x2 = self.mul_const(region, config, &p2.x, &-F::ONE, offset)?;
+        let neg_y2 = self.mul_const(region, config, &p2.y, &-F::ONE, offset)?;
+        let x_diff = self.add_cells(region, config, &p1.x, &neg_x2, offset)?;
+        let y_diff = self.add_cells(region, config, &p1.y, &neg_y2, offset)?;
+
+        let x_eq = self.is_zero(region, config, &x_diff, offset)?;
+        let y_eq = self.is_zero(region, config, &y_diff, offset)?;
+        self.mul_cells(region, config, &x_eq, &y_eq, offset)
+    }
+
+    /// Input a point p and a public set of points,
+    /// Output a bit cell that is 1 iff p is one of the points in the set.
+    ///
+    /// The set members are fixed constants; the result is
+    /// 1 - prod_i (1 - [p == set_i]), and is 0 for an empty set.
+    fn point_in_set(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        set: &[C],
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // prod_i (1 - [p == set_i])
+        let mut not_in_set = self.load_constant(region, config, &F::ONE, offset)?;
+        for member in set.iter() {
+            let member = member.coordinates().unwrap();
+            let x_diff = self.add_const(region, config, &p.x, &-*member.x(), offset)?;
+            let y_diff = self.add_const(region, config, &p.y, &-*member.y(), offset)?;
+            let x_eq = self.is_zero(region, config, &x_diff, offset)?;
+            let y_eq = self.is_zero(region, config, &y_diff, offset)?;
+            let eq = self.mul_cells(region, config, &x_eq, &y_eq, offset)?;
+
+            let neq = self.mul_const(region, config, &eq, &-F::ONE, offset)?;
+            let neq = self.add_const(region, config, &neq, &F::ONE, offset)?;
+            not_in_set = self.mul_cells(region, config, &not_in_set, &neq, offset)?;
+        }
+
+        let in_set = self.mul_const(region, config, &not_in_set, &-F::ONE, offset)?;
+        self.add_const(region, config, &in_set, &F::ONE, offset)
+    }
+
+    /// Input two points p1 and p2,
+    /// Assert p1.x == p2.x, i.e., p1 == p2 or p1 == -p2,
+    /// with the subtraction p1.x - p2.x = 0.
+    fn assert_same_x(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let neg_x2 = self.mul_const(region, config, &p2.x, &-F::ONE, offset)?;
+        let x_diff = self.add_cells(region, config, &p1.x, &neg_x2, offset)?;
+        region.constrain_constant(x_diff.cell(), F::ZERO)
+    }
+
+    /// Input two points p1 and p2,
+    /// Assert p1.x != p2.x, i.e., p1 != +/- p2,
+    /// with a witness inv such that (p1.x - p2.x) * inv = 1.
+    fn assert_different_x(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let neg_x2 = self.mul_const(region, config, &p2.x, &-F::ONE, offset)?;
+        let x_diff = self.add_cells(region, config, &p1.x, &neg_x2, offset)?;
+
+        // the inverse does not exist for the same x; assign 0 and let the check fail
+        let inv = leak(&x_diff.value()).invert().unwrap_or(F::ZERO);
+        let inv = self.load_private_field(region, config, &inv, offset)?;
+        let prod = self.mul_cells(region, config, &x_diff, &inv, offset)?;
+        region.constrain_constant(prod.cell(), F::ONE)
+    }
+
+    /// Convert points in Jacobian coordinates to affine points, with a single
+    /// batch inversion of all Z-coordinates:
+    ///  x = X / Z^2, y = Y / Z^3
+    ///
+    /// Each affine point is copied into a row of its own.
+    /// Caller must check the Jacobian points are on curve.
+    /// Returns a synthesis error if any Z-coordinate is zero.
+    fn batch_jacobian_to_affine(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[AssignedJacobianPoint<C, F>],
+        offset: &mut usize,
+    ) -> Result<Vec<Self::AssignedECPoint>, Error> {
+        let z = points.iter().map(|p| p.z.clone()).collect::<Vec<_>>();
+        let z_inv = self.batch_invert(region, config, &z, offset)?;
+
+        points
+            .iter()
+            .zip(z_inv.iter())
+            .map(|(p, z_inv)| {
+                let z_inv_square = self.mul_cells(region, config, z_inv, z_inv, offset)?;
+                let z_inv_cube = self.mul_cells(region, config, &z_inv_square, z_inv, offset)?;
+                let x = self.mul_cells(region, config, &p.x, &z_inv_square, offset)?;
+                let y = self.mul_cells(region, config, &p.y, &z_inv_cube, offset)?;
+
+                let res = self.assign_point_from_xy(
+                    region,
+                    config,
+                    x.value().copied(),
+                    y.value().copied(),
+                    offset,
+                )?;
+                region.constrain_equal(res.x.cell(), x.cell())?;
+                region.constrain_equal(res.y.cell(), y.cell())?;
+                Ok(res)
+            })
+            .collect()
+    }
+
+    /// Enforces a batch of points in contiguous rows are on curve.
+    /// The points must be the latest assigned rows, in order.
+    ///
+    /// This enables the on curve selectors on each row, the same as calling
+    /// `enforce_on_curve` per point. A gate applies to a single row, so a
+    /// running product over the rows would still need a selector on every row,
+    /// plus an advice cell per point for the product; per row is optimal.
+    ///
+    /// Returns a synthesis error if the points are not the latest assigned rows.
+    fn enforce_on_curve_batch(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[Self::AssignedECPoint],
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        if points
+            .iter()
+            .enumerate()
+            .any(|(i, p)| p.offset + points.len() != *offset + i)
+        {
+            return Err(Error::Synthesis);
+        }
+
+        for p in points.iter() {
+            // | is on curve |   1  |       1      | 0  | 0  | 1  | y1^2 = x1^3 + C::b()
+            config.q_ec_enable.enable(region, p.offset)?;
+            config.q3.enable(region, p.offset)?;
+        }
+        Ok(())
+    }
+
+    /// Loads points into contiguous rows as private inputs,
+    /// and enforces they are on curve.
+    fn load_private_points(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[C],
+        offset: &mut usize,
+    ) -> Result<Vec<Self::AssignedECPoint>, Error> {
+        let res = points
+            .iter()
+            .map(|p| self.load_private_point_unchecked(region, config, p, offset))
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.enforce_on_curve_batch(region, config, &res, offset)?;
+        Ok(res)
+    }
+
+    /// Input a pair of coordinates that may be off curve,
+    /// Output a bit cell that is 1 iff the pair is on curve.
+    ///
+    /// The bit is `is_zero` of the residual x^3 + b - y^2.
+    fn is_on_curve(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let r = self.on_curve_residual(region, config, p, offset)?;
+        self.is_zero(region, config, &r, offset)
+    }
+
+    /// Enforces a pair of coordinates is NOT on curve,
+    /// i.e., the `is_on_curve` bit is 0.
+    fn enforce_not_on_curve(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let is_on_curve = self.is_on_curve(region, config, p, offset)?;
+        region.constrain_constant(is_on_curve.cell(), F::ZERO)?;
+        Ok(())
+    }
+
+    /// Point mul via double-then-add method, for a scalar s < 2^n_bits.
+    ///
+    /// Only the n_bits least significant bits of s are decomposed and iterated,
+    /// which saves 256 - n_bits rounds of doubling and conditional add
+    /// compared to `point_mul`. The accumulator is seeded with the generator,
+    /// so 2^n_bits * generator is subtracted in the end.
+    ///
+    /// Returns a synthesis error if n_bits is 0 or larger than 256,
+    /// or if s does not fit in n_bits.
+    fn point_mul_bits<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &C,
+        s: &C::ScalarExt,
+        n_bits: usize,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        if n_bits == 0 || n_bits > 256 {
+            return Err(Error::Synthesis);
+        }
+
+        let (high, low) = field_decompose_u128(s);
+        let bits = [decompose_u128(&low), decompose_u128(&high)].concat();
+        if bits[n_bits..].iter().any(|&b| b != 0) {
+            return Err(Error::Synthesis);
+        }
+
+        let p_assigned = self.load_private_point(region, config, p, offset)?;
+
+        // the bit blocks absorb 4 bits at a time; the padding bits are fixed to 0
+        let num_blocks_bits = (n_bits + 3) / 4 * 4;
+        let bits = bits[..num_blocks_bits]
+            .iter()
+            .map(|&x| F::from(x))
+            .collect::<Vec<_>>();
+        let (bits, _acc) = self.assign_bit_blocks(region, config, &bits, offset)?;
+        for bit in bits[n_bits..].iter() {
+            region.constrain_constant(bit.cell(), F::ZERO)?;
+        }
+
+        // res = 2^n_bits * generator + p * s
+        let res = self.double_then_add(region, config, &p_assigned, &bits[..n_bits], offset)?;
+
+        // now we subtract 2^n_bits * generator from res
+        let offset_generator_assigned =
+            self.load_offset_generator_correction_bits(region, config, n_bits, offset)?;
+        let bit = self.load_two_private_fields(region, config, &F::ONE, &F::ZERO, offset)?;
+        region.constrain_constant(bit[0].cell(), F::ONE)?;
+        let res = self.conditional_point_add(
+            region,
+            config,
+            &res,
+            &offset_generator_assigned,
+            &bit[0],
+            offset,
+        )?;
+
+        // as in `point_mul_assigned`, res is the latest assigned row
+        self.enforce_on_curve(region, config, &res, offset)?;
+
+        Ok(res)
+    }
+
+    /// Input an index cell and the id of a table registered via
+    /// `ECConfig::register_point_table`,
+    /// Output the point at the index of the table.
+    ///
+    /// The (table id, index, x, y) tuple is looked up in the fixed point tables,
+    /// which must be loaded via `ECChip::load_point_tables`:
+    ///
+    /// |index  |    a   |    b    |
+    /// |-------|--------|---------|
+    /// |offset |  index | table id|
+    /// |       |    x   |    y    |
+    ///
+    /// Returns a synthesis error if the table is not registered, or the index is out of range.
+    fn lookup_constant_point(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        index: &AssignedCell<F, F>,
+        table_id: usize,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        if table_id == 0 || table_id > config.point_tables.len() {
+            return Err(Error::Synthesis);
+        }
+        let table = &config.point_tables[table_id - 1];
+
+        let index_value = leak(&index.value());
+        let position = (0..table.len()).find(|&i| F::from(i as u64) == index_value);
+        index.value().error_if_known_and(|_| position.is_none())?;
+        let p = table
+            .get(position.unwrap_or(0))
+            .copied()
+            .unwrap_or(C::generator());
+
+        config.q_point_table.enable(region, *offset)?;
+        index.copy_advice(|| "index", region, config.a, *offset)?;
+        region.assign_advice_from_constant(
+            || "table id",
+            config.b,
+            *offset,
+            F::from(table_id as u64),
+        )?;
+        *offset += 1;
+
+        self.load_private_point_unchecked(region, config, &p, offset)
+    }
+
+    /// Input a point p and a bit b,
+    /// Output -p if b = 1, and p otherwise.
+    ///
+    /// The y-coordinate is y * (1 - 2b), and the x-coordinate is copied.
+    /// Caller must check b is binary.
+    fn conditional_negate_point(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        bit: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        // y - 2 * b * y
+        let by = self.mul_cells(region, config, bit, &p.y, offset)?;
+        let by = self.mul_const(region, config, &by, &-F::from(2), offset)?;
+        let y = self.add_cells(region, config, &p.y, &by, offset)?;
+
+        let res = self.assign_point_from_xy(
+            region,
+            config,
+            p.x.value().copied(),
+            y.value().copied(),
+            offset,
+        )?;
+        region.constrain_equal(res.x.cell(), p.x.cell())?;
+        region.constrain_equal(res.y.cell(), y.cell())?;
+
+        Ok(res)
+    }
+
+    /// Input a point p,
+    /// Output
+    /// - the canonical representative of +/- p, i.e., the one with an even y
+    /// - a bit that is 1 iff p was negated, i.e., the y of p is odd
+    ///
+    /// The parity is the lowest bit of the canonical decomposition of y.
+    /// Caller must check p is on curve.
+    fn canonicalize_y(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error> {
+        let y_bits = self.decompose_field(region, config, &p.y, offset)?;
+        let is_odd = y_bits[0].clone();
+        let res = self.conditional_negate_point(region, config, p, &is_odd, offset)?;
+
+        Ok((res, is_odd))
+    }
+
+    /// Assign and constrain the intermediate values of a `point_mul`,
+    /// computed by `ECChip::compute_point_mul_witness`.
+    /// Uses the same layout as `point_mul`.
+    ///
+    /// Returns a synthesis error if the witness is not of 256 rounds.
+    fn assign_point_mul_witness(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        witness: &PointMulWitness<C>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let p_assigned = self.load_private_point(region, config, &witness.p, offset)?;
+        self.assign_point_mul_rounds(region, config, &p_assigned, witness, offset)
+    }
+
+    /// Input the cells of the u128 limbs (hi, lo) of a scalar s,
+    /// Output the cells of the u128 limbs (hi, lo) of t = s^-1 mod r,
+    /// where r is the order of the scalar field.
+    ///
+    /// s, t and the quotient q are split into u64 limbs, and
+    /// s * t = q * r + 1 is asserted over the integers column by column:
+    /// - sum_{i+j=k} (s_i * t_j - q_i * r_j) - [k = 0] + carry_{k-1} = carry_k * 2^64
+    ///
+    /// where each carry is shifted by 2^68 into a 72 bit range, and the last carry is 0.
+    /// No term wraps around the base field. Also asserts t < r.
+    ///
+    /// Caller must check hi and lo are u128.
+    /// Returns a synthesis error if s is 0 mod r, or not smaller than r.
+    fn scalar_inverse(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        hi: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let two_to_64 = F::from_u128(1 << 64);
+        let two_to_64_inv = two_to_64.invert().unwrap();
+        let carry_shift = F::from_u128(1 << 68);
+
+        // r is odd, so r_lo = m_lo + 1 does not overflow
+        let (m_hi, m_lo) = field_decompose_u128(&-C::ScalarExt::ONE);
+        let r = u256_to_u64_limbs((m_hi, m_lo + 1));
+
+        // the witnesses t = s^-1 and q = (s * t - 1) / r
+        let (_, s_hi) = field_decompose_u128(&leak(&hi.value()));
+        let (_, s_lo) = field_decompose_u128(&leak(&lo.value()));
+        let mut repr = <C::ScalarExt as PrimeField>::Repr::default();
+        repr.as_mut()[..16].copy_from_slice(&s_lo.to_le_bytes());
+        repr.as_mut()[16..].copy_from_slice(&s_hi.to_le_bytes());
+        let t = Option::<C::ScalarExt>::from(C::ScalarExt::from_repr(repr))
+            .and_then(|s| Option::from(s.invert()));
+        // the values are unknown when the floor planner measures the region
+        hi.value().error_if_known_and(|_| t.is_none())?;
+        let t = u256_to_u64_limbs(field_decompose_u128(&t.unwrap_or(C::ScalarExt::ONE)));
+        let s = u256_to_u64_limbs((s_hi, s_lo));
+        // q < 2^256, so it equals (s * t - 1) * r^-1 mod 2^256
+        let q = u256_mul_wrapping(
+            &u256_sub_wrapping(&u256_mul_wrapping(&s, &t), &[1, 0, 0, 0]),
+            &u256_inv_odd(&r),
+        );
+
+        // all limbs are u64
+        let mut limb_cells = vec![];
+        for limb in s.iter().chain(t.iter()).chain(q.iter()) {
+            let bits = (0..64)
+                .map(|i| F::from((limb >> i) & 1))
+                .collect::<Vec<_>>();
+            let (_, cell) = self.assign_bit_blocks(region, config, &bits, offset)?;
+            limb_cells.push(cell);
+        }
+        let (s_cells, rest) = limb_cells.split_at(4);
+        let (t_cells, q_cells) = rest.split_at(4);
+
+        // the limbs of s recompose its u128 limbs
+        for (cell, limbs) in [(lo, &s_cells[..2]), (hi, &s_cells[2..])] {
+            let rhs = self.mul_const(region, config, &limbs[1], &two_to_64, offset)?;
+            let rhs = self.add_cells(region, config, &limbs[0], &rhs, offset)?;
+            region.constrain_equal(cell.cell(), rhs.cell())?;
+        }
+
+        // s * t = q * r + 1, column by column
+        let mut carry: Option<(AssignedCell<F, F>, F)> = None;
+        for k in 0..7 {
+            let mut col: Option<AssignedCell<F, F>> = None;
+            let mut col_value = F::ZERO;
+            for i in (0..4).filter(|&i| i <= k && k - i < 4) {
+                let j = k - i;
+                let st = self.mul_cells(region, config, &s_cells[i], &t_cells[j], offset)?;
+                let qr = self.mul_const(region, config, &q_cells[i], &-F::from(r[j]), offset)?;
+                let term = self.add_cells(region, config, &st, &qr, offset)?;
+                col = Some(match col {
+                    Some(col) => self.add_cells(region, config, &col, &term, offset)?,
+                    None => term,
+                });
+                col_value += F::from(s[i]) * F::from(t[j]) - F::from(q[i]) * F::from(r[j]);
+            }
+            let mut col = col.unwrap();
+            if k == 0 {
+                col = self.add_const(region, config, &col, &-F::ONE, offset)?;
+                col_value -= F::ONE;
+            }
+            if let Some((shifted_carry, carry_value)) = &carry {
+                col = self.add_cells(region, config, &col, shifted_carry, offset)?;
+                col = self.add_const(region, config, &col, &-carry_shift, offset)?;
+                col_value += carry_value;
+            }
+
+            if k == 6 {
+                // the last carry is 0
+                region.constrain_constant(col.cell(), F::ZERO)?;
+            } else {
+                // col = carry * 2^64, with carry + 2^68 < 2^72
+                let carry_value = col_value * two_to_64_inv;
+                let (_, shifted) = field_decompose_u128(&(carry_value + carry_shift));
+                let bits = (0..72)
+                    .map(|i| F::from(((shifted >> i) & 1) as u64))
+                    .collect::<Vec<_>>();
+                let (_, shifted_carry) = self.assign_bit_blocks(region, config, &bits, offset)?;
+                let rhs = self.mul_const(region, config, &shifted_carry, &two_to_64, offset)?;
+                let rhs =
+                    self.add_const(region, config, &rhs, &-(carry_shift * two_to_64), offset)?;
+                region.constrain_equal(col.cell(), rhs.cell())?;
+                carry = Some((shifted_carry, carry_value));
+            }
+        }
+
+        // the u128 limbs of t, and t <= r - 1
+        let t_lo = self.mul_const(region, config, &t_cells[1], &two_to_64, offset)?;
+        let t_lo = self.add_cells(region, config, &t_cells[0], &t_lo, offset)?;
+        let t_hi = self.mul_const(region, config, &t_cells[3], &two_to_64, offset)?;
+        let t_hi = self.add_cells(region, config, &t_cells[2], &t_hi, offset)?;
+        self.enforce_u256_le_const(region, config, &t_hi, &t_lo, &(m_hi, m_lo), offset)?;
+
+        Ok((t_hi, t_lo))
+    }
+
+    /// Input two points p1 and p2 on curve,
+    /// Output two bit cells (same_x, same_y) that classify the pair:
+    /// - same_x and same_y: p1 == p2, i.e., double
+    /// - same_x and not same_y: p1 == -p2, i.e., the sum is the infinity point
+    /// - otherwise: a generic add
+    ///
+    /// Points on curve with the same x-coordinate have y-coordinates equal up to sign,
+    /// and the curve has no point with y = 0, so the three cases are exhaustive.
+    /// Caller must check p1 and p2 are on curve.
+    fn classify_pair(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let neg_x2 = self.mul_const(region, config, &p2.x, &-F::ONE, offset)?;
+        let neg_y2 = self.mul_const(region, config, &p2.y, &-F::ONE, offset)?;
+        let x_diff = self.add_cells(region, config, &p1.x, &neg_x2, offset)?;
+        let y_diff = self.add_cells(region, config, &p1.y, &neg_y2, offset)?;
+
+        let same_x = self.is_zero(region, config, &x_diff, offset)?;
+        let same_y = self.is_zero(region, config, &y_diff, offset)?;
+        Ok((same_x, same_y))
+    }
+
+    /// Return s * p for a public constant s, via double-then-add over the
+    /// ceil(log2 s) bits of s, most significant first.
+    ///
+    /// The add/skip pattern is fixed by s, so there is no condition row and no
+    /// booleanity constraint: a round is a double, followed by an add if the bit is set.
+    /// The add is derived from its slope, with the accumulator in place:
+    ///
+    ///  index  |  a    |  b
+    ///  -------|-------|------
+    ///         | d.x   | d.y      <- double of the previous row
+    ///         | p.x   | p.y      <- copy of p
+    ///         | slope |
+    ///  offset | s.x   | s.y      <- d + p
+    ///
+    /// The rows reveal s, so this must not be used for secret scalars; s is part of
+    /// the statement. The accumulator is an even multiple of p smaller than 2^129 * p
+    /// before each add, so it never equals +/- p.
+    ///
+    /// Caller must check p is on curve.
+    /// Returns a synthesis error for s = 0, since infinity is not representable.
+    fn point_mul_const_scalar(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        s: u128,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        if s == 0 {
+            return Err(Error::Synthesis);
+        }
+        let p_witness = p.witness();
+
+        // the leading bit is always 1 and initializes the accumulator with p
+        let mut res = self.copy_point(region, config, p, offset)?;
+        for i in (0..127 - s.leading_zeros()).rev() {
+            res = self.point_double(region, config, &res, offset)?;
+
+            if (s >> i) & 1 == 1 {
+                let x1 = leak(&res.x.value());
+                let y1 = leak(&res.y.value());
+                let x2 = leak(&p.x.value());
+                let y2 = leak(&p.y.value());
+                let slope = (y2 - y1) * (x2 - x1).invert().unwrap_or(F::ZERO);
+                let sum = (res.witness() + p_witness).to_affine();
+
+                config.q_add_with_slope.enable(region, res.offset)?;
+                self.copy_point(region, config, p, offset)?;
+                self.load_private_field(region, config, &slope, offset)?;
+                res = self.load_private_point_unchecked(region, config, &sum, offset)?;
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Input an affine point p,
+    /// Output the Jacobian point (p.x, p.y, 1).
+    ///
+    /// The coordinate cells of p are reused as X and Y, and Z is a cell fixed to
+    /// the constant 1, which takes a single row.
+    fn affine_to_jacobian(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedJacobianPoint<C, F>, Error> {
+        let z = self.load_private_field(region, config, &F::ONE, offset)?;
+        region.constrain_constant(z.cell(), F::ONE)?;
+
+        Ok(AssignedJacobianPoint::new(p.x.clone(), p.y.clone(), z))
+    }
+
+    /// Enforces p != -p, i.e., p is not a 2-torsion point:
+    /// the `is_zero` bit of p.y is 0.
+    ///
+    /// `point_double` divides by 2 * p.y and is undefined at 2-torsion points.
+    fn assert_not_two_torsion(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let y_is_zero = self.is_zero(region, config, &p.y, offset)?;
+        region.constrain_constant(y_is_zero.cell(), F::ZERO)?;
+        Ok(())
+    }
+
+    /// Input a Jacobian accumulator (X1, Y1, Z1) and an affine point (x2, y2),
+    /// Output their sum in Jacobian coordinates, via the mixed addition with Z2 = 1:
+    ///  U2 = x2 * Z1^2, S2 = y2 * Z1^3, H = U2 - X1, r = S2 - Y1
+    ///  X3 = r^2 - H^3 - 2 * X1 * H^2
+    ///  Y3 = r * (X1 * H^2 - X3) - Y1 * H^3
+    ///  Z3 = Z1 * H
+    ///
+    /// Saves the Z2^2 and Z2^3 products, and the multiplications by them,
+    /// of a full Jacobian addition.
+    /// Caller must check the accumulator is not infinity, i.e., Z1 != 0, and
+    /// it is not p or -p, where H = 0 and Z3 = 0.
+    fn mixed_add(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        jac_acc: &AssignedJacobianPoint<C, F>,
+        affine_p: &Self::AssignedECPoint,
+        offset: &mut usize,
+    ) -> Result<AssignedJacobianPoint<C, F>, Error> {
+        let (x1, y1, z1) = (&jac_acc.x, &jac_acc.y, &jac_acc.z);
+
+        let z1_square = self.mul_cells(region, config, z1, z1, offset)?;
+        let z1_cube = self.mul_cells(region, config, &z1_square, z1, offset)?;
+        let u2 = self.mul_cells(region, config, &affine_p.x, &z1_square, offset)?;
+        let s2 = self.mul_cells(region, config, &affine_p.y, &z1_cube, offset)?;
+
+        let neg_x1 = self.mul_const(region, config, x1, &-F::ONE, offset)?;
+        let neg_y1 = self.mul_const(region, config, y1, &-F::ONE, offset)?;
+        let h = self.add_cells(region, config, &u2, &neg_x1, offset)?;
+        let r = self.add_cells(region, config, &s2, &neg_y1, offset)?;
+
+        let h_square = self.mul_cells(region, config, &h, &h, offset)?;
+        let h_cube = self.mul_cells(region, config, &h_square, &h, offset)?;
+        let v = self.mul_cells(region, config, x1, &h_square, offset)?;
+
+        // X3 = r^2 - H^3 - 2V
+        let r_square = self.mul_cells(region, config, &r, &r, offset)?;
+        let neg_h_cube = self.mul_const(region, config, &h_cube, &-F::ONE, offset)?;
+        let neg_two_v = self.mul_const(region, config, &v, &-F::from(2), offset)?;
+        let t = self.add_cells(region, config, &r_square, &neg_h_cube, offset)?;
+        let x3 = self.add_cells(region, config, &t, &neg_two_v, offset)?;
+
+        // Y3 = r * (V - X3) - Y1 * H^3
+        let neg_x3 = self.mul_const(region, config, &x3, &-F::ONE, offset)?;
+        let v_minus_x3 = self.add_cells(region, config, &v, &neg_x3, offset)?;
+        let t = self.mul_cells(region, config, &r, &v_minus_x3, offset)?;
+        let neg_y1_h_cube = self.mul_cells(region, config, &neg_y1, &h_cube, offset)?;
+        let y3 = self.add_cells(region, config, &t, &neg_y1_h_cube, offset)?;
+
+        let z3 = self.mul_cells(region, config, z1, &h, offset)?;
+
+        Ok(AssignedJacobianPoint::new(x3, y3, z3))
+    }
+
+    /// Loads a pair (x, y) into the circuit as a private input, with the
+    /// on-curve residual x^3 + b - y^2, which is 0 iff the pair is on curve.
+    ///
+    /// The pair is not constrained to be on curve; callers can constrain the
+    /// residual, or use it as a penalty. As in `assign_point_from_xy`, the pair
+    /// may be off curve or unknown without a panic during witness generation.
+    fn load_private_point_with_residual(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        x: Value<F>,
+        y: Value<F>,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, AssignedCell<F, F>), Error> {
+        let p = self.assign_point_from_xy(region, config, x, y, offset)?;
+        let residual = self.on_curve_residual(region, config, &p, offset)?;
+        Ok((p, residual))
+    }
+
+    /// Point mul via double-then-add method, as `point_mul`, also returning the
+    /// accumulator after each round, most significant bit first.
+    ///
+    /// The circuit has no cell for the point at infinity, so the accumulator is
+    /// seeded with the generator: after the round of bit i, it is
+    /// 2^(256 - i) * generator + (s >> i) * p. The last one is the result plus
+    /// 2^256 * generator. The accumulators are the cells of the conditional adds
+    /// of the rounds, so this uses the same rows as `point_mul`.
+    ///
+    /// Returns s * p and the 256 accumulators.
+    fn point_mul_trace<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &C,
+        s: &C::ScalarExt,
+        offset: &mut usize,
+    ) -> Result<(Self::AssignedECPoint, Vec<Self::AssignedECPoint>), Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        let witness = Self::compute_point_mul_witness(p, s);
+        let p_assigned = self.load_private_point(region, config, &witness.p, offset)?;
+        self.assign_point_mul_trace(region, config, &p_assigned, &witness, offset)
+    }
+
+    /// As `conditional_point_add`, where p2 may also be the identity, encoded
+    /// as (0, 0) like the identity of halo2curves; (0, 0) is not on curve as
+    /// b != 0, so the encoding is unambiguous.
+    ///
+    /// Returns
+    /// - p3 = p1 + p2 if b == 1 and p2 is not the identity.
+    /// - p3 = p1 if b == 0, or p2 is the identity.
+    ///
+    /// The gate of `conditional_point_add` only holds p2 when b == 1, so this
+    /// adds with the bit b * (1 - is_identity(p2)). p1 cannot be the identity:
+    /// the gate enforces the output on curve, and the identity is not.
+    ///
+    /// Caller must check p1 is on curve, p2 is on curve or (0, 0), b is a bit,
+    /// and p1 != +/- p2.
+    fn conditional_point_add_with_identity(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p1: &Self::AssignedECPoint,
+        p2: &Self::AssignedECPoint,
+        b: &AssignedCell<F, F>,
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        // p2 is the identity iff both coordinates are 0
+        let x_is_zero = self.is_zero(region, config, &p2.x, offset)?;
+        let y_is_zero = self.is_zero(region, config, &p2.y, offset)?;
+        let is_identity = self.mul_cells(region, config, &x_is_zero, &y_is_zero, offset)?;
+
+        // b * (1 - is_identity) = b - b * is_identity
+        let b_and_identity = self.mul_cells(region, config, b, &is_identity, offset)?;
+        let neg_b_and_identity =
+            self.mul_const(region, config, &b_and_identity, &-F::ONE, offset)?;
+        let bit = self.add_cells(region, config, b, &neg_b_and_identity, offset)?;
+
+        // copy the points and the bit into a fresh conditional add block
+        let p1 = self.copy_point(region, config, p1, offset)?;
+        let p2 = self.copy_point(region, config, p2, offset)?;
+        let bit_copied =
+            self.load_two_private_fields(region, config, &leak(&bit.value()), &F::ZERO, offset)?;
+        region.constrain_equal(bit_copied[0].cell(), bit.cell())?;
+        self.conditional_point_add(region, config, &p1, &p2, &bit_copied[0], offset)
+    }
+
+    /// Return s * (p_1 + ... + p_m) for points that share the scalar s,
+    /// given as 256 little endian bit cells.
+    ///
+    /// A joint double-then-add over (p_i, s) loads a copy of each bit for each
+    /// of the m conditional adds of a round, as the gate reads the bit inside
+    /// the add block. This sums the bases once instead, so each round has a
+    /// single conditional add and a single copy of the bit.
+    ///
+    /// Caller must check the points are on curve, no partial sum of the points
+    /// equals +/- the next point, and the bits are binary.
+    /// Returns a synthesis error if there is no point or the number of bits is not 256.
+    fn point_mul_shared_bits(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        points: &[Self::AssignedECPoint],
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<Self::AssignedECPoint, Error> {
+        let (first, rest) = points.split_first().ok_or(Error::Synthesis)?;
+
+        // sum the bases, so that the loop adds once per round
+        let mut sum = first.clone();
+        for p in rest {
+            sum = self.point_add(region, config, &sum, p, offset)?;
+        }
+
+        self.point_mul_with_bits(region, config, &sum, bits, offset)
+    }
+
+    /// Return the chain p, 2p, 4p, ..., 2^k * p of k + 1 points.
+    ///
+    /// p is copied into the first row, and each of the k doublings takes the
+    /// row right below the previous point, which is the input of its double gate:
+    ///
+    ///  index  |  a        |  b
+    ///  -------|-----------|-----------
+    ///         | p.x       | p.y           <- copy of p
+    ///         | 2p.x      | 2p.y
+    ///         | ...       | ...
+    ///  offset | 2^k p.x   | 2^k p.y
+    ///
+    /// Caller must check p is on curve.
+    fn doubling_chain(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        p: &Self::AssignedECPoint,
+        k: usize,
+        offset: &mut usize,
+    ) -> Result<Vec<Self::AssignedECPoint>, Error> {
+        let mut witnesses = vec![];
+        let mut acc = p.witness();
+        for _ in 0..k {
+            acc = (acc + acc).to_affine();
+            witnesses.push(acc);
+        }
+        self.doubling_chain_with_witnesses(region, config, p, &witnesses, offset)
+    }
+
+    /// Pad the row with `ECChip::min_blinding_rows` rows of empty cells.
+    fn pad(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        // the rows of the chip's own blinding factors are the minimum
+        self.pad_for_blinding(region, config, 0, offset)
+    }
+
+    /// Pad the row with enough rows of empty cells for `num_blinding_rows`
+    /// blinding rows, i.e., `max(num_blinding_rows + 1, ECChip::min_blinding_rows)`
+    /// rows.
+    fn pad_for_blinding(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        num_blinding_rows: usize,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let rows = (num_blinding_rows + 1).max(Self::min_blinding_rows());
+        for i in 0..rows {
+            region.assign_advice(|| "pad", config.a, *offset + i, || Value::known(F::ZERO))?;
+            region.assign_advice(|| "pad", config.b, *offset + i, || Value::known(F::ZERO))?;
+        }
+        *offset += rows;
+        Ok(())
+    }
+
+    /// Pad the row with cells that are copied from the constant zero.
+    fn pad_constant(
+        &self,
+        region: &mut Region<F>,
+        config: &Self::Config,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let rows = Self::min_blinding_rows();
+        for i in 0..rows {
+            region.assign_advice_from_constant(|| "pad", config.a, *offset + i, F::ZERO)?;
+            region.assign_advice_from_constant(|| "pad", config.b, *offset + i, F::ZERO)?;
+        }
+        *offset += rows;
+        Ok(())
+    }
+}
+
+impl<C, F> ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// The double-then-add loop over little endian bit cells.
+    /// Returns 2^n * generator + p * s, where n is the number of bits.
+    ///
+    /// Caller must check p is on curve and the bits are binary.
+    pub(crate) fn double_then_add(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        p_assigned: &AssignedECPoint<C, F>,
+        bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        self.joint_double_then_add(region, config, &[(p_assigned, bits)], offset)
+    }
+
+    /// The double-then-add loop over pairs of points and little endian bit cells,
+    /// sharing the doublings: in each round the accumulator is doubled once, and
+    /// each point is conditionally added with its bit.
+    /// Returns 2^n * generator + sum_i p_i * s_i, where n is the number of bits.
+    ///
+    /// Caller must check the points are on curve, the bits are binary, and all
+    /// pairs have the same number of bits.
+    pub(crate) fn joint_double_then_add(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        pairs: &[(&AssignedECPoint<C, F>, &[AssignedCell<F, F>])],
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        let num_bits = pairs.first().map(|(_, bits)| bits.len()).unwrap_or(0);
+        if pairs.iter().any(|(_, bits)| bits.len() != num_bits) {
+            return Err(Error::Synthesis);
+        }
+
+        // the seed is fixed to the generator; with a free seed Q the result would
+        // be shifted by 2^n * (Q - generator), which the correction cannot catch
+        let gen_assigned = self.load_generator(region, config, offset)?;
+
+        // we do not have a cell representation for infinity point
+        // therefore we first compute
+        //  res = 2^256 * generator + p *s
+        // ans then subtract 2^256 * generator from res
+        let mut res: AssignedECPoint<C, F> = gen_assigned;
 
         // begin the `double-then-add` loop
-        for b in bits.iter().rev() {
+        for i in (0..num_bits).rev() {
             // double
-            let res_double = self.point_double(region, config, &res, offset)?;
-
-            // conditional add depending on the bit b
-            res = {
-                let p_copied = if leak(&b.value()) == F::ONE {
-                    // copy the base point cells
-                    let p_copied: AssignedECPoint<C, F> =
-                        self.load_private_point_unchecked(region, config, p, offset)?;
-                    region.constrain_equal(p_copied.x.cell(), p_assigned.x.cell())?;
-                    region.constrain_equal(p_copied.y.cell(), p_assigned.y.cell())?;
-                    p_copied
-                } else {
-                    // the point here doesn't matter but we do need to fill in the cells
-                    self.load_private_point_unchecked(region, config, &gen, offset)?
-                };
+            res = self.point_double(region, config, &res, offset)?;
 
-                // copy the bit cell; already constraint `bit` is either 0 or 1
-                let bit = self.load_two_private_fields(
-                    region,
-                    config,
-                    &leak(&b.value()),
-                    &F::ZERO,
-                    offset,
-                )?;
-                region.constrain_equal(bit[0].cell(), b.cell())?;
+            for (p_assigned, bits) in pairs.iter() {
+                let b = &bits[i];
 
-                // conditional add
-                self.conditional_point_add(region, config, &res_double, &p_copied, &bit[0], offset)?
-            };
+                // conditional add depending on the bit b
+                res = {
+                    // copy the base point cells whatever the bit: the copy constraints
+                    // must not depend on the witness, as the keys are generated
+                    // without it; the gate ignores p when the bit is 0
+                    let p_copied = self.copy_point(region, config, p_assigned, offset)?;
+
+                    // copy the bit cell; already constraint `bit` is either 0 or 1
+                    let bit = self.load_two_private_fields(
+                        region,
+                        config,
+                        &leak(&b.value()),
+                        &F::ZERO,
+                        offset,
+                    )?;
+                    region.constrain_equal(bit[0].cell(), b.cell())?;
+
+                    // conditional add
+                    self.conditional_point_add(region, config, &res, &p_copied, &bit[0], offset)?
+                };
+            }
         }
 
-        // now we subtract 2^256 * generator from res
-        let (offset_generator, x, y) = neg_generator_times_2_to_256::<C, C::Base>();
+        Ok(res)
+    }
+
+    /// Subtract 2^256 * generator from the result of a 256 round
+    /// double-then-add loop, with the correction bit fixed to 1.
+    pub(crate) fn subtract_offset_generator(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        res: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
         let offset_generator_assigned =
-            self.load_private_point_unchecked(region, config, &offset_generator, offset)?;
+            self.load_offset_generator_correction(region, config, offset)?;
         let bit = self.load_two_private_fields(region, config, &F::ONE, &F::ZERO, offset)?;
-        res = self.conditional_point_add(
+        region.constrain_constant(bit[0].cell(), F::ONE)?;
+        self.conditional_point_add(
             region,
             config,
-            &res,
+            res,
             &offset_generator_assigned,
             &bit[0],
             offset,
-        )?;
+        )
+    }
+
+    /// Loads the constant -2^256 * generator.
+    pub(crate) fn load_offset_generator_correction(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        let (offset_generator, x, y) = neg_generator_times_2_to_256::<C, F>();
+        let offset_generator_assigned =
+            self.load_private_point_unchecked(region, config, &offset_generator, offset)?;
         // ensure the `subtract 2^256 * generator` cells are fixed constants
         region.constrain_constant(offset_generator_assigned.x.cell(), x)?;
         region.constrain_constant(offset_generator_assigned.y.cell(), y)?;
+        Ok(offset_generator_assigned)
+    }
+
+    /// Loads the constant -2^n_bits * generator, i.e., the correction of a
+    /// double-then-add loop over n_bits bits.
+    pub(crate) fn load_offset_generator_correction_bits(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        n_bits: usize,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        let two_to_n = C::ScalarExt::from(2).pow_vartime([n_bits as u64]);
+        let offset_generator = (-(C::generator() * two_to_n)).to_affine();
+        let coordinates = offset_generator.coordinates().unwrap();
+        let offset_generator_assigned =
+            self.load_private_point_unchecked(region, config, &offset_generator, offset)?;
+        // ensure the `subtract 2^n_bits * generator` cells are fixed constants
+        region.constrain_constant(offset_generator_assigned.x.cell(), *coordinates.x())?;
+        region.constrain_constant(offset_generator_assigned.y.cell(), *coordinates.y())?;
+        Ok(offset_generator_assigned)
+    }
+
+    /// Load a public point from two consecutive instance rows (x, y), starting at `row`.
+    ///
+    /// The point is copied into a new region and enforced to be on curve.
+    /// Returns `EccError::IdentityNotRepresentable` if the instance holds (0, 0),
+    /// the encoding of the identity in halo2curves.
+    pub fn load_point_from_instance(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        config: &ECConfig<C, F>,
+        row: usize,
+    ) -> Result<AssignedECPoint<C, F>, EccError> {
+        // the region can only return a plonk error; this tells the identity apart
+        let mut is_identity = false;
+        let res = layouter.assign_region(
+            || "load point from instance",
+            |mut region| {
+                let mut offset = 0;
+                let x = region.assign_advice_from_instance(
+                    || "x",
+                    config.instance,
+                    row,
+                    config.a,
+                    offset,
+                )?;
+                let y = region.assign_advice_from_instance(
+                    || "y",
+                    config.instance,
+                    row + 1,
+                    config.b,
+                    offset,
+                )?;
+                x.value().zip(y.value()).map(|(x, y)| {
+                    is_identity = bool::from(x.is_zero() & y.is_zero());
+                });
+                if is_identity {
+                    return Err(Error::Synthesis);
+                }
+
+                let p = AssignedECPoint::new(x, y, offset);
+                offset += 1;
+                self.enforce_on_curve(&mut region, config, &p, &mut offset)?;
+
+                // pad the rows queried by the gates
+                self.pad(&mut region, config, &mut offset)?;
+
+                Ok(p)
+            },
+        );
+
+        match res {
+            Err(_) if is_identity => Err(EccError::IdentityNotRepresentable),
+            res => Ok(res?),
+        }
+    }
+
+    /// Return sum_i s_i * p_i in a new region, via `MsmAccumulator`.
+    ///
+    /// The points are loaded and enforced to be on curve.
+    /// Returns
+    /// - `EccError::LengthMismatch` if the numbers of points and scalars differ;
+    /// - `EccError::IdentityNotRepresentable` if there is no point, a point is
+    ///   the identity, or the sum is the identity.
+    ///
+    /// Caller must ensure no partial sum equals +/- the next product.
+    pub fn msm<S>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        config: &ECConfig<C, F>,
+        points: &[C],
+        scalars: &[C::ScalarExt],
+    ) -> Result<AssignedECPoint<C, F>, EccError>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        if points.len() != scalars.len() {
+            return Err(EccError::LengthMismatch {
+                expected: points.len(),
+                got: scalars.len(),
+            });
+        }
+        let sum = points
+            .iter()
+            .zip(scalars.iter())
+            .map(|(p, s)| *p * *s)
+            .reduce(|acc, x| acc + x)
+            .map(|sum| sum.to_affine());
+        if points
+            .iter()
+            .chain(sum.iter())
+            .any(|p| bool::from(p.is_identity()))
+            || sum.is_none()
+        {
+            return Err(EccError::IdentityNotRepresentable);
+        }
+
+        let res = layouter.assign_region(
+            || "msm",
+            |mut region| {
+                let mut offset = 0;
+                let mut msm = MsmAccumulator::new();
+                for (p, s) in points.iter().zip(scalars.iter()) {
+                    let p = self.load_private_point(&mut region, config, p, &mut offset)?;
+                    let bits = self.decompose_scalar(&mut region, config, s, &mut offset)?;
+                    msm.push(&p, &bits)?;
+                }
+                let res = msm.finalize(self, &mut region, config, &mut offset)?;
 
+                // pad the rows queried by the gates
+                self.pad(&mut region, config, &mut offset)?;
+
+                Ok(res)
+            },
+        )?;
         Ok(res)
     }
 
-    /// Pad the row with empty cells.
-    fn pad(
+    /// Compute the intermediate values of `point_mul` natively, without a region.
+    pub fn compute_point_mul_witness<S>(p: &C, s: &C::ScalarExt) -> PointMulWitness<C>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        let (high, low) = field_decompose_u128(s);
+        let bits = [decompose_u128(&low), decompose_u128(&high)]
+            .concat()
+            .iter()
+            .map(|&b| b == 1)
+            .collect::<Vec<_>>();
+
+        let mut doubles = vec![];
+        let mut adds = vec![];
+        let mut acc = C::generator();
+        for &bit in bits.iter().rev() {
+            acc = (acc + acc).to_affine();
+            doubles.push(acc);
+            if bit {
+                acc = (acc + *p).to_affine();
+            }
+            adds.push(acc);
+        }
+
+        let (offset_generator, _, _) = neg_generator_times_2_to_256::<C, F>();
+        let res = (acc + offset_generator).to_affine();
+
+        PointMulWitness {
+            p: *p,
+            bits,
+            doubles,
+            adds,
+            res,
+        }
+    }
+
+    /// The constraints of `conditional_point_add`, with the witness p3 computed by the caller:
+    /// the ec add gate of p1, p2, the condition bit and p3.
+    ///
+    /// Caller must check p1, p2 and the bit are the latest assigned rows.
+    pub(crate) fn conditional_point_add_with_witness(
         &self,
         region: &mut Region<F>,
-        config: &Self::Config,
+        config: &ECConfig<C, F>,
+        p3_witness: &C,
         offset: &mut usize,
-    ) -> Result<(), Error> {
-        region.assign_advice(|| "pad", config.a, *offset, || Value::known(F::ZERO))?;
-        region.assign_advice(|| "pad", config.b, *offset, || Value::known(F::ZERO))?;
-        region.assign_advice(|| "pad", config.a, *offset + 1, || Value::known(F::ZERO))?;
-        region.assign_advice(|| "pad", config.b, *offset + 1, || Value::known(F::ZERO))?;
-        region.assign_advice(|| "pad", config.a, *offset + 2, || Value::known(F::ZERO))?;
-        region.assign_advice(|| "pad", config.b, *offset + 2, || Value::known(F::ZERO))?;
-        *offset += 3;
-        Ok(())
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        //  index  |  a   |  b
+        //  -------|------|------
+        //         | p1.x | p1.y
+        //         | p2.x | p2.y
+        //         | cond |
+        //  offset | p3.x | p3.y
+
+        // |      ec add |   4  |       1      | 1  | 0  | 0  | (x1, y1), (x2, y2) and (x3, -y3) are on a same line
+        config.q_ec_enable.enable(region, *offset - 3)?;
+        config.q1.enable(region, *offset - 3)?;
+
+        self.load_private_point_unchecked(region, config, p3_witness, offset)
+    }
+
+    /// The constraints of `doubling_chain`, with the doublings computed by the caller:
+    /// a copy of p followed by one double gate per witness.
+    pub(crate) fn doubling_chain_with_witnesses(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        p: &AssignedECPoint<C, F>,
+        witnesses: &[C],
+        offset: &mut usize,
+    ) -> Result<Vec<AssignedECPoint<C, F>>, Error> {
+        let mut chain = vec![self.copy_point(region, config, p, offset)?];
+        for w in witnesses {
+            let next =
+                self.point_double_with_witness(region, config, chain.last().unwrap(), w, offset)?;
+            chain.push(next);
+        }
+        Ok(chain)
+    }
+
+    /// The constraints of `point_double`, with the witness p2 computed by the caller:
+    /// the ec double gate of p1 and p2.
+    pub(crate) fn point_double_with_witness(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        p1: &AssignedECPoint<C, F>,
+        p2_witness: &C,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        assert_eq!(
+            p1.offset,
+            *offset - 1,
+            "point double: p is not the latest assigned cells"
+        );
+
+        // |   ec double |   2  |       1      | 0  | 1  | 0  | (x1, y1) and (x3, -y3) are on a tangential line of the curve
+        config.q_ec_enable.enable(region, *offset - 1)?;
+        config.q2.enable(region, *offset - 1)?;
+        let p2 = self.load_private_point_unchecked(region, config, p2_witness, offset)?;
+
+        #[cfg(feature = "verbose")]
+        {
+            println!(
+                "[point double]             selector: {}, points: {} {}",
+                *offset - 1,
+                p1.offset,
+                p2.offset,
+            );
+        }
+
+        Ok(p2)
+    }
+
+    /// The double-then-add loop of `point_mul` over the witness, followed by the
+    /// `subtract 2^256 * generator` correction. p must be the latest assigned row.
+    ///
+    /// Each round takes 4 rows, with p and the bit read by the mul round gate:
+    ///
+    ///  index  |  a   |  b
+    ///  -------|------|------
+    ///         | p.x  | p.y      <- seed: p
+    ///         | 0    | 0        <- seed: acc = 0
+    ///         | g.x  | g.y      <- seed: generator
+    ///  round  | d.x  | d.y      <- double of the previous row
+    ///         | p.x  | p.y      <- equals p of the previous round
+    ///         | bit  | acc      <- acc = 2 * acc_prev + bit
+    ///         | s.x  | s.y      <- s = d + p if bit is 1, and d otherwise
+    ///
+    /// p is copied neither into the rounds nor into the bit, so a round costs no copy
+    /// constraint; the bits are decomposed by the running sum acc, most significant first.
+    pub(crate) fn assign_point_mul_rounds(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        p_assigned: &AssignedECPoint<C, F>,
+        witness: &PointMulWitness<C>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        let (res, _trace) =
+            self.assign_point_mul_trace(region, config, p_assigned, witness, offset)?;
+        Ok(res)
+    }
+
+    /// As `assign_point_mul_rounds`, also returning the accumulator after
+    /// each round, i.e., the cells of the conditional adds.
+    pub(crate) fn assign_point_mul_trace(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        p_assigned: &AssignedECPoint<C, F>,
+        witness: &PointMulWitness<C>,
+        offset: &mut usize,
+    ) -> Result<(AssignedECPoint<C, F>, Vec<AssignedECPoint<C, F>>), Error> {
+        if witness.bits.len() != 256 || witness.doubles.len() != 256 || witness.adds.len() != 256 {
+            return Err(Error::Synthesis);
+        }
+        assert_eq!(
+            p_assigned.offset,
+            *offset - 1,
+            "point mul: p is not the latest assigned cells"
+        );
+
+        // the running sum starts from 0
+        let acc_seed = self.load_two_private_fields(region, config, &F::ZERO, &F::ZERO, offset)?;
+        region.constrain_constant(acc_seed[1].cell(), F::ZERO)?;
+
+        // res = 2^256 * generator + p * s, from a seed fixed to the generator
+        let mut res = self.load_generator(region, config, offset)?;
+        let mut acc = F::ZERO;
+        let mut trace = vec![];
+        for ((&bit, double), add) in witness
+            .bits
+            .iter()
+            .rev()
+            .zip(witness.doubles.iter())
+            .zip(witness.adds.iter())
+        {
+            res = self.point_double_with_witness(region, config, &res, double, offset)?;
+
+            config.q_mul_round.enable(region, *offset)?;
+            self.load_private_point_unchecked(region, config, &witness.p, offset)?;
+
+            let bit = F::from(bit as u64);
+            acc = acc.double() + bit;
+            self.load_two_private_fields(region, config, &bit, &acc, offset)?;
+
+            res = self.conditional_point_add_with_witness(region, config, add, offset)?;
+            trace.push(res.clone());
+        }
+
+        // now we subtract 2^256 * generator from res
+        self.load_offset_generator_correction(region, config, offset)?;
+        let bit = self.load_two_private_fields(region, config, &F::ONE, &F::ZERO, offset)?;
+        region.constrain_constant(bit[0].cell(), F::ONE)?;
+        let res = self.conditional_point_add_with_witness(region, config, &witness.res, offset)?;
+
+        // the conditional add gate sums the line and the on curve equations into a
+        // single constraint; enforce the result is on curve on its own as well.
+        // res is the latest assigned row, so this only enables the selectors on it
+        // and does not use any new row.
+        self.enforce_on_curve(region, config, &res, offset)?;
+
+        Ok((res, trace))
+    }
+
+    /// Return the on-curve residual x^3 + b - y^2 of a pair of coordinates.
+    pub(crate) fn on_curve_residual(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        p: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let y_square = self.mul_cells(region, config, &p.y, &p.y, offset)?;
+        let x_square = self.mul_cells(region, config, &p.x, &p.x, offset)?;
+        let x_cube = self.mul_cells(region, config, &x_square, &p.x, offset)?;
+        let v = self.add_const(region, config, &x_cube, &C::b(), offset)?;
+        let neg_y_square = self.mul_const(region, config, &y_square, &-F::ONE, offset)?;
+        self.add_cells(region, config, &v, &neg_y_square, offset)
+    }
+
+    /// Loads the constant generator.
+    pub(crate) fn load_generator(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        let gen = C::generator();
+        let gen_coordinates = gen.coordinates().unwrap();
+        let gen_assigned = self.load_private_point_unchecked(region, config, &gen, offset)?;
+        region.constrain_constant(gen_assigned.x.cell(), *gen_coordinates.x())?;
+        region.constrain_constant(gen_assigned.y.cell(), *gen_coordinates.y())?;
+        Ok(gen_assigned)
     }
 }
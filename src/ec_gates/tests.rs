@@ -2,14 +2,26 @@ use std::ops::Mul;
 
 use ark_std::test_rng;
 use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::AssignedCell;
 use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::Region;
 use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::circuit::Value;
+use halo2_proofs::dev::CellValue;
+use halo2_proofs::dev::FailureLocation;
 use halo2_proofs::dev::MockProver;
+use halo2_proofs::dev::VerifyFailure;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::ff::WithSmallOrderMulGroup;
 use halo2_proofs::halo2curves::group::Curve;
 use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Advice;
 use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::Column;
 use halo2_proofs::plonk::ConstraintSystem;
 use halo2_proofs::plonk::Error;
+use halo2_proofs::poly::Rotation;
 use halo2curves::grumpkin::Fq;
 use halo2curves::grumpkin::Fr;
 use halo2curves::grumpkin::G1Affine;
@@ -18,7 +30,28 @@ use halo2curves::grumpkin::G1;
 use crate::chip::ECChip;
 use crate::config::ECConfig;
 use crate::ec_gates::NativeECOps;
+use crate::native_add;
+use crate::native_double;
+use crate::native_mul;
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::mock_run;
+use crate::test_circuit::overwrite_point;
+use crate::test_circuit::real_prover_verifies;
+use crate::test_circuit::try_and_increment;
+use crate::test_vectors::bytes;
+use crate::test_vectors::fr;
+use crate::test_vectors::point;
+use crate::test_vectors::COMPRESSION_VECTORS;
+use crate::test_vectors::EC_OPS_VECTORS;
+use crate::util::field_decompose_u128;
+use crate::util::neg_generator_times_2_to_256;
 use crate::ArithOps;
+use crate::AssignedECPoint;
+use crate::AssignedJacobianPoint;
+use crate::BatchVerifier;
+use crate::EccError;
+use crate::MsmAccumulator;
+use crate::PointBatch;
 
 #[derive(Default, Debug, Clone, Copy)]
 struct ECTestCircuit {
@@ -27,7 +60,7 @@ struct ECTestCircuit {
     p2: G1Affine,
     p3: G1Affine, // p1 + p2
     p4: G1Affine, // 2p1
-    p5: G1Affine, // p1 * s
+                  // p1 * s is public
 }
 
 impl Circuit<Fq> for ECTestCircuit {
@@ -49,6 +82,9 @@ impl Circuit<Fq> for ECTestCircuit {
     ) -> Result<(), Error> {
         let ec_chip = ECChip::construct(config.clone());
 
+        // unit test: load point from instance
+        let p5 = ec_chip.load_point_from_instance(&mut layouter, &config, 0)?;
+
         layouter.assign_region(
             || "test ec circuit",
             |mut region| {
@@ -69,7 +105,6 @@ impl Circuit<Fq> for ECTestCircuit {
                     ec_chip.load_private_point(&mut region, &config, &self.p2, &mut offset)?;
                 let p3 = ec_chip.load_private_point(&mut region, &config, &self.p3, &mut offset)?;
                 let p4 = ec_chip.load_private_point(&mut region, &config, &self.p4, &mut offset)?;
-                let p5 = ec_chip.load_private_point(&mut region, &config, &self.p5, &mut offset)?;
 
                 // unit test: point addition with 1
                 {
@@ -191,50 +226,3089 @@ fn test_ec_ops() {
     let p3 = (p1 + p2).to_affine();
     let p4 = (p1 + p1).to_affine();
     let p5 = p1.mul(s).to_affine();
+    let p5_coordinates = p5.coordinates().unwrap();
+    let instances = vec![*p5_coordinates.x(), *p5_coordinates.y()];
 
     {
-        let circuit = ECTestCircuit {
-            s,
-            p1,
-            p2,
-            p3,
-            p4,
-            p5,
-        };
+        let circuit = ECTestCircuit { s, p1, p2, p3, p4 };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![instances.clone()]).unwrap();
         prover.assert_satisfied();
     }
 
     // error case: add not equal
     {
         let p3 = (p1 + p1).to_affine();
-        let circuit = ECTestCircuit {
-            s,
-            p1,
-            p2,
-            p3,
-            p4,
-            p5,
-        };
+        let circuit = ECTestCircuit { s, p1, p2, p3, p4 };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![instances.clone()]).unwrap();
         assert!(prover.verify().is_err());
     }
 
     // error case: double not equal
     {
         let p4 = (p1 + p2).to_affine();
+        let circuit = ECTestCircuit { s, p1, p2, p3, p4 };
+
+        let prover = MockProver::run(k, &circuit, vec![instances.clone()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: the public point is not p1 * s
+    {
+        let circuit = ECTestCircuit { s, p1, p2, p3, p4 };
+        let p1_coordinates = p1.coordinates().unwrap();
+        let instances = vec![*p1_coordinates.x(), *p1_coordinates.y()];
+
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: the public point is not on curve
+    {
+        let circuit = ECTestCircuit { s, p1, p2, p3, p4 };
+        let instances = vec![*p5_coordinates.x(), *p5_coordinates.y() + Fq::one()];
+
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // the fixed vectors
+    for v in EC_OPS_VECTORS.iter() {
         let circuit = ECTestCircuit {
-            s,
-            p1,
-            p2,
-            p3,
-            p4,
-            p5,
+            s: fr(v.s),
+            p1: point(&v.p1),
+            p2: point(&v.p2),
+            p3: point(&v.add),
+            p4: point(&v.double),
+        };
+        let p5 = point(&v.mul);
+        let instances = vec![p5.x, p5.y];
+
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        if let Err(failures) = prover.verify() {
+            panic!("vector {}: {:?}", v.name, failures);
+        }
+    }
+}
+
+#[test]
+fn test_point_batch() {
+    let k = 14;
+
+    let mut rng = test_rng();
+    let points = [
+        G1::random(&mut rng).to_affine(),
+        G1::random(&mut rng).to_affine(),
+        G1::random(&mut rng).to_affine(),
+    ];
+    let scalars = [
+        Fr::random(&mut rng),
+        Fr::random(&mut rng),
+        Fr::random(&mut rng),
+    ];
+    let sum = (points[0] + points[1] + points[2]).to_affine();
+    let products = [
+        points[0].mul(scalars[0]).to_affine(),
+        points[1].mul(scalars[1]).to_affine(),
+        points[2].mul(scalars[2]).to_affine(),
+    ];
+
+    // sum: sum of points; products: points[i] * scalars[i]
+    let run = |sum: G1Affine, products: [G1Affine; 3]| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let batch = PointBatch::load(ec_chip, region, config, &points, offset)?;
+
+            // unit test: batch sum
+            {
+                let sum = ec_chip.load_private_point(region, config, &sum, offset)?;
+                let sum_rec = batch.sum(ec_chip, region, config, offset)?;
+                region.constrain_equal(sum.x.cell(), sum_rec.x.cell())?;
+                region.constrain_equal(sum.y.cell(), sum_rec.y.cell())?;
+            }
+
+            // unit test: batch mul
+            {
+                let products = PointBatch::load(ec_chip, region, config, &products, offset)?;
+                let products_rec = batch.map_mul(ec_chip, region, config, &scalars, offset)?;
+                for (p, p_rec) in products.points().iter().zip(products_rec.points().iter()) {
+                    region.constrain_equal(p.x.cell(), p_rec.x.cell())?;
+                    region.constrain_equal(p.y.cell(), p_rec.y.cell())?;
+                }
+            }
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    run(sum, products).assert_satisfied();
+
+    // error case: sum not equal
+    {
+        let sum = (points[0] + points[1]).to_affine();
+        assert!(run(sum, products).verify().is_err());
+    }
+
+    // error case: product not equal
+    {
+        let mut products = products;
+        products[1] = points[1].mul(scalars[0]).to_affine();
+        assert!(run(sum, products).verify().is_err());
+    }
+
+    // error case: the sum of an empty batch
+    {
+        let res = mock_run(k, |ec_chip, region, config, offset| {
+            let batch = PointBatch::load(ec_chip, region, config, &[], offset)?;
+            batch.sum(ec_chip, region, config, offset)?;
+            Ok(())
+        });
+        assert!(matches!(res, Err(Error::Synthesis)));
+    }
+
+    // error case: a scalar is missing
+    {
+        let res = mock_run(k, |ec_chip, region, config, offset| {
+            let batch = PointBatch::load(ec_chip, region, config, &points, offset)?;
+            batch.map_mul(ec_chip, region, config, &scalars[..2], offset)?;
+            Ok(())
+        });
+        assert!(matches!(res, Err(Error::Synthesis)));
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct PadConstantTestCircuit {
+    p1: G1Affine,
+}
+
+impl Circuit<Fq> for PadConstantTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test pad constant circuit",
+            |mut region| {
+                let mut offset = 0;
+                let p1 = ec_chip.load_private_point(&mut region, &config, &self.p1, &mut offset)?;
+                let _p2 = ec_chip.point_double(&mut region, &config, &p1, &mut offset)?;
+
+                // the double gate on the last row reads the padded cells
+                let start = offset;
+                ec_chip.pad_constant(&mut region, &config, &mut offset)?;
+                assert_eq!(offset - start, ECChip::<G1Affine, Fq>::min_blinding_rows());
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_pad_constant() {
+    let k = 6;
+
+    let mut rng = test_rng();
+    let p1 = G1::random(&mut rng).to_affine();
+    let circuit = PadConstantTestCircuit { p1 };
+
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    prover.assert_satisfied();
+}
+
+// the number of rotations at which the host column below is queried
+const HOST_NUM_QUERIES: usize = 12;
+
+#[derive(Default, Debug, Clone, Copy)]
+struct PadForBlindingTestCircuit {
+    // number of rows to fill before padding
+    rows: usize,
+    // the blinding rows to pad for
+    num_blinding_rows: usize,
+}
+
+impl Circuit<Fq> for PadForBlindingTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        *self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        // a host column queried at more rotations than the chip's columns,
+        // so that halo2 reserves more blinding rows than `pad` covers
+        let host = meta.advice_column();
+        let q_host = meta.selector();
+        meta.create_gate("host gate", |meta| {
+            let q = meta.query_selector(q_host);
+            let sum = (0..HOST_NUM_QUERIES as i32)
+                .map(|i| meta.query_advice(host, Rotation(i)))
+                .reduce(|acc, x| acc + x)
+                .unwrap();
+            vec![q * sum]
+        });
+
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test pad for blinding circuit",
+            |mut region| {
+                let mut offset = 0;
+                for i in 0..self.rows {
+                    ec_chip.load_private_field(
+                        &mut region,
+                        &config,
+                        &Fq::from(i as u64),
+                        &mut offset,
+                    )?;
+                }
+                ec_chip.pad_for_blinding(
+                    &mut region,
+                    &config,
+                    self.num_blinding_rows,
+                    &mut offset,
+                )?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_pad_for_blinding() {
+    let k = 6;
+
+    let mut meta = ConstraintSystem::<Fq>::default();
+    let _ = PadForBlindingTestCircuit::configure(&mut meta);
+    let num_blinding_rows = meta.blinding_factors();
+    assert!(num_blinding_rows + 1 > ECChip::<G1Affine, Fq>::min_blinding_rows());
+
+    // the rows halo2 leaves for assignments, i.e., without the blinding rows
+    // and the last row
+    let usable_rows = (1 << k) - (num_blinding_rows + 1);
+
+    // the rows and the padding for the host's blinding rows fit exactly
+    {
+        let circuit = PadForBlindingTestCircuit {
+            rows: (1 << k) - 2 * (num_blinding_rows + 1),
+            num_blinding_rows,
+        };
+        assert_eq!(circuit.rows + num_blinding_rows + 1, usable_rows);
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: one row too many
+    {
+        let circuit = PadForBlindingTestCircuit {
+            rows: (1 << k) - 2 * (num_blinding_rows + 1) + 1,
+            num_blinding_rows,
+        };
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+
+    // fewer blinding rows than the chip's own fall back to `pad`
+    {
+        let circuit = PadForBlindingTestCircuit {
+            rows: 1,
+            num_blinding_rows: 0,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
+
+#[test]
+fn test_assign_point_from_xy() {
+    let k = 6;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let coordinates = p.coordinates().unwrap();
+    let x = *coordinates.x();
+    let y = *coordinates.y();
+
+    let run = |x: Value<Fq>, y: Value<Fq>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.assign_point_from_xy(region, config, x, y, offset)?;
+            ec_chip.enforce_on_curve(region, config, &p, offset)
+        })
+    };
+
+    // valid pair
+    run(Value::known(x), Value::known(y))
+        .unwrap()
+        .assert_satisfied();
+
+    // error case: invalid pair is assigned without panic, and rejected by the on curve gate
+    {
+        let prover = run(Value::known(x), Value::known(y + Fq::one())).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // unknown pair: the assignment does not panic; the mock prover reports a synthesis error
+    assert!(run(Value::unknown(), Value::unknown()).is_err());
+}
+
+fn compress(p: &G1Affine) -> [u8; 32] {
+    let coordinates = p.coordinates().unwrap();
+    let mut bytes = coordinates.x().to_repr();
+    if bool::from(coordinates.y().is_odd()) {
+        bytes[31] |= 0x80;
+    }
+    bytes
+}
+
+#[test]
+fn test_point_compression() {
+    let k = 12;
+
+    // bytes: compressed p
+    let run = |p: G1Affine, bytes: [u8; 32]| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+
+            // unit test: compression
+            let bytes_rec = p.to_compressed_bytes(ec_chip, region, config, offset)?;
+            for (byte, byte_rec) in bytes.iter().zip(bytes_rec.iter()) {
+                let byte =
+                    ec_chip.load_private_field(region, config, &Fq::from(*byte as u64), offset)?;
+                region.constrain_equal(byte.cell(), byte_rec.cell())?;
+            }
+
+            // unit test: decompression
+            let p_rec = AssignedECPoint::from_compressed_bytes(
+                ec_chip, region, config, &bytes_rec, offset,
+            )?;
+            region.constrain_equal(p.x.cell(), p_rec.x.cell())?;
+            region.constrain_equal(p.y.cell(), p_rec.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    for _ in 0..2 {
+        let p = G1::random(&mut rng).to_affine();
+        let bytes = compress(&p);
+
+        run(p, bytes).assert_satisfied();
+
+        // error case: sign bit flipped
+        {
+            let mut bytes = bytes;
+            bytes[31] ^= 0x80;
+            assert!(run(p, bytes).verify().is_err());
+        }
+
+        // error case: x not equal
+        {
+            let mut bytes = bytes;
+            bytes[0] ^= 0x01;
+            assert!(run(p, bytes).verify().is_err());
+        }
+    }
+
+    // the fixed vectors
+    for v in COMPRESSION_VECTORS.iter() {
+        if let Err(failures) = run(point(&v.p), bytes(v.bytes)).verify() {
+            panic!("vector {}: {:?}", v.name, failures);
+        }
+    }
+}
+
+#[test]
+fn test_scalar_multiple() {
+    let k = 8;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let mut multiples = [p; 8];
+    for i in 1..8 {
+        multiples[i] = (multiples[i - 1] + p).to_affine();
+    }
+
+    // multiples: p * (i + 1)
+    let run = |multiples: [G1Affine; 8]| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+
+            for (i, multiple) in multiples.iter().enumerate() {
+                let multiple = ec_chip.load_private_point(region, config, multiple, offset)?;
+                let multiple_rec =
+                    ec_chip.scalar_multiple(region, config, &p, i as u64 + 1, offset)?;
+                region.constrain_equal(multiple.x.cell(), multiple_rec.x.cell())?;
+                region.constrain_equal(multiple.y.cell(), multiple_rec.y.cell())?;
+            }
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    run(multiples).assert_satisfied();
+
+    // error case: 5 * p not equal
+    {
+        let mut multiples = multiples;
+        multiples[4] = multiples[5];
+        assert!(run(multiples).verify().is_err());
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct XTableTestCircuit {
+    table: [Fq; 16],
+    p: G1Affine,
+    // enforce the pair (0, 0) instead of p
+    zero: bool,
+}
+
+impl Circuit<Fq> for XTableTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+        ec_chip.load_x_table(&mut layouter, &self.table)?;
+
+        layouter.assign_region(
+            || "test x table circuit",
+            |mut region| {
+                let mut offset = 0;
+                let p = if self.zero {
+                    ec_chip.assign_point_from_xy(
+                        &mut region,
+                        &config,
+                        Value::known(Fq::zero()),
+                        Value::known(Fq::zero()),
+                        &mut offset,
+                    )?
+                } else {
+                    ec_chip.load_private_point(&mut region, &config, &self.p, &mut offset)?
+                };
+                ec_chip.enforce_x_in_table(&mut region, &config, &p, &mut offset)?;
+
+                // pad the last two rows
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_x_in_table() {
+    let k = 6;
+
+    let mut rng = test_rng();
+    let points = (0..16)
+        .map(|_| G1::random(&mut rng).to_affine())
+        .collect::<Vec<_>>();
+    let mut table = [Fq::zero(); 16];
+    for (x, p) in table.iter_mut().zip(points.iter()) {
+        *x = *p.coordinates().unwrap().x();
+    }
+
+    // member
+    {
+        let circuit = XTableTestCircuit {
+            table,
+            p: points[7],
+            zero: false,
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: not a member
+    {
+        let circuit = XTableTestCircuit {
+            table,
+            p: G1::random(&mut rng).to_affine(),
+            zero: false,
         };
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         assert!(prover.verify().is_err());
     }
+
+    // error case: the default row does not allow zero
+    {
+        let circuit = XTableTestCircuit {
+            table,
+            p: points[0],
+            zero: true,
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(feature = "debug_verify")]
+#[test]
+fn test_debug_verify() {
+    let mut rng = test_rng();
+    let p1 = G1::random(&mut rng).to_affine();
+    let circuit = PadConstantTestCircuit { p1 };
+
+    crate::debug_verify(6, &circuit, vec![vec![]]).unwrap();
+}
+
+// doubles p and constrains the result to the instance, which is `expected`;
+// the mock prover runs at the end of `synthesize` if the circuit is witnessed
+#[cfg(feature = "debug_verify")]
+#[derive(Default, Debug, Clone, Copy)]
+struct DebugVerifySynthesisTestCircuit {
+    p: G1Affine,
+    expected: G1Affine,
+    witnessed: bool,
+}
+
+#[cfg(feature = "debug_verify")]
+impl Circuit<Fq> for DebugVerifySynthesisTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            witnessed: false,
+            ..*self
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    #[crate::debug_verify_synthesis(
+        k = 6,
+        witnessed = self.witnessed,
+        instances = vec![vec![self.expected.x, self.expected.y]]
+    )]
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        let p2 = layouter.assign_region(
+            || "test debug verify synthesis circuit",
+            |mut region| {
+                let mut offset = 0;
+                let p = ec_chip.load_private_point(&mut region, &config, &self.p, &mut offset)?;
+                let p2 = ec_chip.point_double(&mut region, &config, &p, &mut offset)?;
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(p2)
+            },
+        )?;
+
+        layouter.constrain_instance(p2.x.cell(), config.instance, 0)?;
+        layouter.constrain_instance(p2.y.cell(), config.instance, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "debug_verify")]
+#[test]
+fn test_debug_verify_synthesis() {
+    use halo2_proofs::plonk::keygen_vk;
+    use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+    use halo2curves::bn256::Bn256;
+
+    let k = 6;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let circuit = DebugVerifySynthesisTestCircuit {
+        p,
+        expected: (p + p).to_affine(),
+        witnessed: true,
+    };
+
+    // the annotated synthesize runs the mock prover itself
+    let prover = MockProver::run(
+        k,
+        &circuit,
+        vec![vec![circuit.expected.x, circuit.expected.y]],
+    )
+    .unwrap();
+    prover.assert_satisfied();
+
+    // error case: the result is not the instance; synthesize returns the error
+    let unsatisfied = DebugVerifySynthesisTestCircuit {
+        expected: p,
+        ..circuit
+    };
+    assert!(matches!(
+        MockProver::run(k, &unsatisfied, vec![vec![p.x, p.y]]),
+        Err(Error::Synthesis)
+    ));
+
+    // the keys are generated without the witness, and the check is skipped,
+    // whatever the values the circuit carries
+    let params = ParamsKZG::<Bn256>::setup(k, &mut rng);
+    assert!(keygen_vk(&params, &circuit.without_witnesses()).is_ok());
+    assert!(keygen_vk(&params, &unsatisfied.without_witnesses()).is_ok());
+}
+
+#[test]
+fn test_load_compressed_pubkey() {
+    let k = 10;
+
+    // p: decompressed (x, sign)
+    let run = |x: Fq, sign: Fq, p: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let [x, sign] = ec_chip.load_two_private_fields(region, config, &x, &sign, offset)?;
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+
+            let p_rec = ec_chip.load_compressed_pubkey(region, config, &x, &sign, offset)?;
+            region.constrain_equal(p.x.cell(), p_rec.x.cell())?;
+            region.constrain_equal(p.y.cell(), p_rec.y.cell())?;
+
+            Ok(())
+        })
+    };
+
+    let mut rng = test_rng();
+
+    // both parities
+    let mut tested = [false; 2];
+    while !(tested[0] && tested[1]) {
+        let p = G1::random(&mut rng).to_affine();
+        let coordinates = p.coordinates().unwrap();
+        let parity = bool::from(coordinates.y().is_odd());
+        if tested[parity as usize] {
+            continue;
+        }
+        tested[parity as usize] = true;
+
+        let x = *coordinates.x();
+        let sign = Fq::from(parity as u64);
+        run(x, sign, p).unwrap().assert_satisfied();
+
+        // error case: tampered sign bit
+        assert!(run(x, Fq::one() - sign, p).unwrap().verify().is_err());
+
+        // error case: the sign is not a bit
+        assert!(run(x, Fq::from(2), p).unwrap().verify().is_err());
+    }
+
+    // error case: x^3 + b is not a square
+    {
+        let mut x = Fq::random(&mut rng);
+        while bool::from((x.square() * x + G1Affine::b()).sqrt().is_some()) {
+            x = Fq::random(&mut rng);
+        }
+        assert!(run(x, Fq::zero(), G1::random(&mut rng).to_affine()).is_err());
+    }
+}
+
+#[test]
+fn test_assert_points_equal_gate() {
+    let k = 6;
+
+    let run = |p1: G1Affine, p2: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p1 = ec_chip.load_private_point(region, config, &p1, offset)?;
+            let p2 = ec_chip.load_private_point(region, config, &p2, offset)?;
+            ec_chip.assert_points_equal_gate(region, config, &p1, &p2, offset)
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p1 = G1::random(&mut rng).to_affine();
+
+    run(p1, p1).assert_satisfied();
+
+    // error case: p2 = -p1 has the same x-coordinate
+    assert!(run(p1, -p1).verify().is_err());
+
+    // error case: p2 != p1
+    assert!(run(p1, G1::random(&mut rng).to_affine()).verify().is_err());
+}
+
+// p * s where s is given in little endian bits, computed natively
+fn mul_by_bits(p: &G1Affine, bits: &[bool]) -> G1Affine {
+    let mut res = G1::identity();
+    for &b in bits.iter().rev() {
+        res = res.double();
+        if b {
+            res += p;
+        }
+    }
+    res.to_affine()
+}
+
+#[test]
+fn test_clamp_scalar_x25519() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let s = Fr::random(&mut rng);
+    let p = G1::random(&mut rng).to_affine();
+
+    // q: p * clamp(s)
+    let run = |q: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+            let q = ec_chip.load_private_point(region, config, &q, offset)?;
+
+            let mut bits = ec_chip.decompose_scalar(region, config, &s, offset)?;
+            ec_chip.clamp_scalar_x25519(region, config, &mut bits, offset)?;
+
+            // the clamped bits follow the X25519 spec
+            let s_bits = crate::util::to_le_bits(&s);
+            for (i, bit) in bits.iter().enumerate() {
+                let expected = match i {
+                    0 | 1 | 2 | 255 => false,
+                    254 => true,
+                    _ => s_bits[i],
+                };
+                bit.value()
+                    .assert_if_known(|&&v| v == Fq::from(expected as u64));
+            }
+
+            let q_rec = ec_chip.point_mul_with_bits(region, config, &p, &bits, offset)?;
+            region.constrain_equal(q.x.cell(), q_rec.x.cell())?;
+            region.constrain_equal(q.y.cell(), q_rec.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut clamped_bits = crate::util::to_le_bits(&s);
+    clamped_bits[0] = false;
+    clamped_bits[1] = false;
+    clamped_bits[2] = false;
+    clamped_bits[255] = false;
+    clamped_bits[254] = true;
+    run(mul_by_bits(&p, &clamped_bits)).assert_satisfied();
+
+    // error case: the unclamped product
+    assert!(run(p.mul(s).to_affine()).verify().is_err());
+}
+
+#[test]
+fn test_point_ops_with_slope() {
+    let k = 6;
+
+    let mut rng = test_rng();
+    let p1 = G1::random(&mut rng).to_affine();
+    let p2 = G1::random(&mut rng).to_affine();
+
+    // add_slope: expected slope of p1 + p2; double_slope: expected slope of 2 * p1
+    let run = |bit: bool, add_slope: Fq, double_slope: Fq| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p1_assigned = ec_chip.load_private_point(region, config, &p1, offset)?;
+            let p2_assigned = ec_chip.load_private_point(region, config, &p2, offset)?;
+            let bit_assigned =
+                ec_chip.load_private_field(region, config, &Fq::from(bit as u64), offset)?;
+
+            let (p3, add_slope_rec) = ec_chip.conditional_point_add_with_slope(
+                region,
+                config,
+                &p1_assigned,
+                &p2_assigned,
+                &bit_assigned,
+                offset,
+            )?;
+            let (p4, double_slope_rec) =
+                ec_chip.point_double_with_slope(region, config, &p1_assigned, offset)?;
+
+            // the slopes match the expected ones
+            let add_slope = ec_chip.load_private_field(region, config, &add_slope, offset)?;
+            let double_slope = ec_chip.load_private_field(region, config, &double_slope, offset)?;
+            region.constrain_equal(add_slope_rec.cell(), add_slope.cell())?;
+            region.constrain_equal(double_slope_rec.cell(), double_slope.cell())?;
+
+            // the points match the plain add and double
+            let p3_expected = if bit { (p1 + p2).to_affine() } else { p1 };
+            let p3_expected = ec_chip.load_private_point(region, config, &p3_expected, offset)?;
+            let p4_expected =
+                ec_chip.load_private_point(region, config, &(p1 + p1).to_affine(), offset)?;
+            region.constrain_equal(p3.x.cell(), p3_expected.x.cell())?;
+            region.constrain_equal(p3.y.cell(), p3_expected.y.cell())?;
+            region.constrain_equal(p4.x.cell(), p4_expected.x.cell())?;
+            region.constrain_equal(p4.y.cell(), p4_expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let x1 = *p1.coordinates().unwrap().x();
+    let y1 = *p1.coordinates().unwrap().y();
+    let x2 = *p2.coordinates().unwrap().x();
+    let y2 = *p2.coordinates().unwrap().y();
+    let add_slope = (y2 - y1) * (x2 - x1).invert().unwrap();
+    let double_slope = x1 * x1 * Fq::from(3) * (y1 + y1).invert().unwrap();
+
+    run(true, add_slope, double_slope).assert_satisfied();
+
+    // the add slope is zero when the bit is not set
+    run(false, Fq::zero(), double_slope).assert_satisfied();
+
+    // error case: wrong add slope
+    assert!(run(true, add_slope + Fq::one(), double_slope)
+        .verify()
+        .is_err());
+
+    // error case: wrong double slope
+    assert!(run(true, add_slope, double_slope.double())
+        .verify()
+        .is_err());
+}
+
+#[test]
+fn test_load_private_point_off_curve() {
+    let k = 5;
+
+    // (x, y): coordinates that may be off curve
+    let run = |x: Fq, y: Fq| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            // as in `load_private_point`, which takes a `C` that cannot
+            // hold off curve coordinates
+            let p = ec_chip.assign_point_from_xy(
+                region,
+                config,
+                Value::known(x),
+                Value::known(y),
+                offset,
+            )?;
+            ec_chip.enforce_on_curve(region, config, &p, offset)?;
+            // the on curve check is on the row of the point, and adds no row
+            assert_eq!(*offset, 1);
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    run(p.x, p.y).assert_satisfied();
+
+    // error case: (x, y + 1) is not on the curve, and is rejected by the on curve check
+    // of the native ec chip gate at the row of the point
+    {
+        let p = G1Affine {
+            x: p.x,
+            y: p.y + Fq::one(),
+        };
+        assert!(!bool::from(p.is_on_curve()));
+
+        let failures = run(p.x, p.y).verify().unwrap_err();
+        assert_eq!(failures.len(), 1);
+        match &failures[0] {
+            VerifyFailure::ConstraintNotSatisfied {
+                constraint,
+                location,
+                ..
+            } => {
+                assert!(constraint.to_string().contains("native ec chip"));
+                assert!(matches!(
+                    location,
+                    FailureLocation::InRegion { offset: 0, .. }
+                ));
+            }
+            failure => panic!("unexpected failure: {failure}"),
+        }
+    }
+}
+
+// (a_hi, a_lo) + (b_hi, b_lo) for 256 bit integers without overflow
+fn u256_add(a: (u128, u128), b: (u128, u128)) -> (u128, u128) {
+    let (lo, carry) = a.1.overflowing_add(b.1);
+    (a.0 + b.0 + carry as u128, lo)
+}
+
+#[test]
+fn test_reduce_mod_r() {
+    let k = 10;
+
+    // rem: expected v mod r
+    let run = |hi: u128, lo: u128, rem: Fr| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let (_, hi) = ec_chip.decompose_u128(region, config, &hi, offset)?;
+            let (_, lo) = ec_chip.decompose_u128(region, config, &lo, offset)?;
+            let (rem_hi, rem_lo) = ec_chip.reduce_mod_r(region, config, &hi, &lo, offset)?;
+
+            let (expected_hi, expected_lo) = field_decompose_u128(&rem);
+            let expected = ec_chip.load_two_private_fields(
+                region,
+                config,
+                &Fq::from_u128(expected_hi),
+                &Fq::from_u128(expected_lo),
+                offset,
+            )?;
+            region.constrain_equal(rem_hi.cell(), expected[0].cell())?;
+            region.constrain_equal(rem_lo.cell(), expected[1].cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let two_to_128 = Fr::from_u128(1 << 127).double();
+    let reduce = |(hi, lo): (u128, u128)| Fr::from_u128(hi) * two_to_128 + Fr::from_u128(lo);
+
+    // r = (r - 1) + 1
+    let r_minus_one = field_decompose_u128(&-Fr::one());
+    let r = u256_add(r_minus_one, (0, 1));
+
+    let mut inputs = vec![(0, 0), (u128::MAX, u128::MAX)];
+    let mut multiple = (0, 0);
+    // 5r < 2^256 < 6r
+    for _ in 0..5 {
+        inputs.push(u256_add(multiple, r_minus_one)); // k * r - 1
+        multiple = u256_add(multiple, r);
+        inputs.push(multiple); // k * r
+        inputs.push(u256_add(multiple, (0, 1))); // k * r + 1
+    }
+
+    for (hi, lo) in inputs {
+        run(hi, lo, reduce((hi, lo))).assert_satisfied();
+    }
+
+    // error case: wrong remainder
+    {
+        let rem = reduce((u128::MAX, u128::MAX)) + Fr::one();
+        assert!(run(u128::MAX, u128::MAX, rem).verify().is_err());
+    }
+}
+
+#[test]
+fn test_point_mul_result_on_curve() {
+    let k = 14;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+
+    // corrupted: overwrites the cells of the result
+    let run = |corrupted: Option<(Fq, Fq)>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+            let res = ec_chip.point_mul_assigned(region, config, &p, &s, offset)?;
+
+            if let Some((x, y)) = corrupted {
+                region.assign_advice(|| "x", config.a, res.offset(), || Value::known(x))?;
+                region.assign_advice(|| "y", config.b, res.offset(), || Value::known(y))?;
+            }
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    run(None).assert_satisfied();
+
+    // error case: the result is replaced by an off curve point that satisfies the
+    // conditional add gate of the last step, i.e., the sum of the line equation
+    //  (x3 - x1)(y2 - y1) + (x2 - x1)(y3 + y1)
+    // and the on curve equation
+    //  x3^3 - y3^2 - 17
+    // is zero; it is rejected by the explicit on curve check
+    {
+        let (correction, _, _) = neg_generator_times_2_to_256::<G1Affine, Fq>();
+        let res = (p * s).to_affine();
+        let p1 = (G1::from(res) - correction).to_affine();
+        let p2 = correction;
+
+        let a = p2.x - p1.x;
+        let mut x3 = res.x;
+        let corrupted = loop {
+            x3 += Fq::one();
+            // y3^2 - a * y3 - c = 0
+            let c = (x3 - p1.x) * (p2.y - p1.y) + a * p1.y + x3.square() * x3 - Fq::from(17);
+            if let Some(d) = Option::<Fq>::from((a.square() + c.double().double()).sqrt()) {
+                break G1Affine {
+                    x: x3,
+                    y: (a + d) * Fq::from(2).invert().unwrap(),
+                };
+            }
+        };
+        assert!(!bool::from(corrupted.is_on_curve()));
+
+        assert!(run(Some((corrupted.x, corrupted.y))).verify().is_err());
+    }
+}
+
+// a < b as integers
+fn field_less_than(a: &Fq, b: &Fq) -> bool {
+    field_decompose_u128(a) < field_decompose_u128(b)
+}
+
+#[test]
+fn test_is_point_less_than() {
+    let k = 12;
+
+    // less_than: expected p1 < p2
+    let run = |p1: G1Affine, p2: G1Affine, less_than: bool| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p1 = ec_chip.load_private_point(region, config, &p1, offset)?;
+            let p2 = ec_chip.load_private_point(region, config, &p2, offset)?;
+            let less_than_rec = ec_chip.is_point_less_than(region, config, &p1, &p2, offset)?;
+            let expected =
+                ec_chip.load_private_field(region, config, &Fq::from(less_than as u64), offset)?;
+            region.constrain_equal(less_than_rec.cell(), expected.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    // differ only in y
+    let neg_p = -p;
+    // differ only in x: (zeta * x)^3 = x^3 for a cube root of unity zeta
+    let zeta_p = G1Affine {
+        x: p.x * Fq::ZETA,
+        y: p.y,
+    };
+    assert!(bool::from(zeta_p.is_on_curve()));
+
+    for (p1, p2, less_than) in [
+        (p, neg_p, field_less_than(&p.y, &neg_p.y)),
+        (neg_p, p, field_less_than(&neg_p.y, &p.y)),
+        (p, zeta_p, field_less_than(&p.x, &zeta_p.x)),
+        (zeta_p, p, field_less_than(&zeta_p.x, &p.x)),
+        // equal points
+        (p, p, false),
+    ] {
+        run(p1, p2, less_than).assert_satisfied();
+    }
+
+    // error case: wrong order
+    {
+        let less_than = !field_less_than(&p.y, &neg_p.y);
+        assert!(run(p, neg_p, less_than).verify().is_err());
+    }
+}
+
+#[test]
+fn test_hash_to_curve_try_and_increment() {
+    let k = 11;
+    let max_iterations = 8;
+
+    let run = |u: Fq, max_iterations: usize, expected: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let u = ec_chip.load_private_field(region, config, &u, offset)?;
+            let p = ec_chip.hash_to_curve_try_and_increment(
+                region,
+                config,
+                &u,
+                max_iterations,
+                offset,
+            )?;
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(p.x.cell(), expected.x.cell())?;
+            region.constrain_equal(p.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+    };
+
+    let mut rng = test_rng();
+    let mut inputs = (0..3).map(|_| Fq::random(&mut rng)).collect::<Vec<_>>();
+    // an input that skips at least two non-squares
+    let u = (0..)
+        .map(Fq::from)
+        .find(|&u| try_and_increment(u).0 >= 2)
+        .unwrap();
+    inputs.push(u);
+
+    for u in inputs.iter() {
+        let (_, expected) = try_and_increment(*u);
+        run(*u, max_iterations, expected)
+            .unwrap()
+            .assert_satisfied();
+    }
+
+    // error case: the point is the one with an odd y
+    {
+        let (_, expected) = try_and_increment(u);
+        let prover = run(u, max_iterations, -expected).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: max_iterations is too small to reach the first square
+    {
+        let (j, expected) = try_and_increment(u);
+        assert!(run(u, j, expected).is_err());
+    }
+}
+
+#[test]
+fn test_conditional_double() {
+    let k = 6;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let p_double = (p + p).to_affine();
+
+    // malicious: overwrites the cells of the result
+    let run = |bit: Fq, expected: G1Affine, malicious: Option<G1Affine>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+            let bit = ec_chip.load_private_field(region, config, &bit, offset)?;
+            let p3 = ec_chip.conditional_double(region, config, &p, &bit, offset)?;
+
+            if let Some(malicious) = malicious {
+                region.assign_advice(
+                    || "x",
+                    config.a,
+                    p3.offset(),
+                    || Value::known(malicious.x),
+                )?;
+                region.assign_advice(
+                    || "y",
+                    config.b,
+                    p3.offset(),
+                    || Value::known(malicious.y),
+                )?;
+            }
+
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(p3.x.cell(), expected.x.cell())?;
+            region.constrain_equal(p3.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    for (bit, expected) in [(Fq::one(), p_double), (Fq::zero(), p)] {
+        run(bit, expected, None).assert_satisfied();
+    }
+
+    // error case: malicious results that skip or apply the doubling against the bit
+    for (bit, malicious) in [(Fq::one(), p), (Fq::zero(), p_double)] {
+        assert!(run(bit, malicious, Some(malicious)).verify().is_err());
+    }
+
+    // error case: the bit is not binary
+    assert!(run(Fq::from(2), p, None).verify().is_err());
+}
+
+#[test]
+fn test_x_is_on_curve() {
+    let k = 11;
+
+    // on_curve: expected result
+    let run = |x: Fq, on_curve: bool| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let x = ec_chip.load_private_field(region, config, &x, offset)?;
+            let on_curve_rec = ec_chip.x_is_on_curve(region, config, &x, offset)?;
+            let expected =
+                ec_chip.load_private_field(region, config, &Fq::from(on_curve as u64), offset)?;
+            region.constrain_equal(on_curve_rec.cell(), expected.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    // an x-coordinate with no y
+    let x_off_curve = (0..)
+        .map(|i| p.x + Fq::from(i))
+        .find(|x| bool::from((x.square() * x - Fq::from(17)).sqrt().is_none()))
+        .unwrap();
+
+    for (x, on_curve) in [(p.x, true), (x_off_curve, false)] {
+        run(x, on_curve).assert_satisfied();
+    }
+
+    // error case: wrong result
+    for (x, on_curve) in [(p.x, false), (x_off_curve, true)] {
+        assert!(run(x, on_curve).verify().is_err());
+    }
+}
+
+#[test]
+fn test_point_in_set() {
+    let k = 8;
+
+    // in_set: expected result
+    let run = |p: G1Affine, set: &[G1Affine], in_set: bool| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+            let in_set_rec = ec_chip.point_in_set(region, config, &p, set, offset)?;
+            let expected =
+                ec_chip.load_private_field(region, config, &Fq::from(in_set as u64), offset)?;
+            region.constrain_equal(in_set_rec.cell(), expected.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let set = (0..3)
+        .map(|_| G1::random(&mut rng).to_affine())
+        .collect::<Vec<_>>();
+    let p = G1::random(&mut rng).to_affine();
+
+    for (p, set, in_set) in [
+        (set[1], &set[..], true),
+        (p, &set[..], false),
+        // -p shares the x-coordinate with p
+        (-set[1], &set[..], false),
+        (p, &[][..], false),
+    ] {
+        run(p, set, in_set).assert_satisfied();
+    }
+
+    // error case: wrong result
+    for (p, in_set) in [(set[1], false), (p, true)] {
+        assert!(run(p, &set, in_set).verify().is_err());
+    }
+}
+
+#[test]
+fn test_assert_same_x() {
+    let k = 6;
+
+    // asserts the same x if same_x is true, and different x otherwise
+    let run = |p1: G1Affine, p2: G1Affine, same_x: bool| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p1 = ec_chip.load_private_point(region, config, &p1, offset)?;
+            let p2 = ec_chip.load_private_point(region, config, &p2, offset)?;
+            if same_x {
+                ec_chip.assert_same_x(region, config, &p1, &p2, offset)
+            } else {
+                ec_chip.assert_different_x(region, config, &p1, &p2, offset)
+            }
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let q = G1::random(&mut rng).to_affine();
+
+    for (p1, p2, same_x) in [(p, p, true), (p, -p, true), (p, q, false)] {
+        run(p1, p2, same_x).assert_satisfied();
+    }
+
+    // error case: the other assertion
+    for (p1, p2, same_x) in [(p, p, false), (p, -p, false), (p, q, true)] {
+        assert!(run(p1, p2, same_x).verify().is_err());
+    }
+}
+
+#[test]
+fn test_msm_accumulator() {
+    let k = 14;
+
+    let mut rng = test_rng();
+    let points = (0..10)
+        .map(|_| G1::random(&mut rng).to_affine())
+        .collect::<Vec<_>>();
+    let scalars = (0..10).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+    // the one-shot msm
+    let msm = |n: usize| {
+        points
+            .iter()
+            .zip(scalars.iter())
+            .take(n)
+            .fold(G1::identity(), |acc, (p, s)| acc + p * s)
+            .to_affine()
+    };
+
+    // flush_after: flush the buffered pairs after pushing this many pairs
+    let run = |n: usize, flush_after: Option<usize>, expected: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let mut acc = MsmAccumulator::new();
+            for (i, (p, s)) in points.iter().zip(scalars.iter()).take(n).enumerate() {
+                if flush_after == Some(i) {
+                    acc.flush(ec_chip, region, config, offset)?;
+                }
+                let p = ec_chip.load_private_point(region, config, p, offset)?;
+                let bits = ec_chip.decompose_scalar(region, config, s, offset)?;
+                acc.push(&p, &bits)?;
+            }
+            let res = acc.finalize(ec_chip, region, config, offset)?;
+
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    for (n, flush_after) in [(1, None), (3, None), (10, None), (3, Some(1))] {
+        run(n, flush_after, msm(n)).assert_satisfied();
+    }
+
+    // error case: wrong result
+    assert!(run(3, None, msm(2)).verify().is_err());
+}
+
+#[test]
+fn test_batch_jacobian_to_affine() {
+    let k = 8;
+
+    let run = |points: &[G1Affine], z: &[Fq]| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            // (X, Y, Z) = (x * z^2, y * z^3, z)
+            let mut jacobian_points = vec![];
+            for (p, z) in points.iter().zip(z.iter()) {
+                let [x, y] = ec_chip.load_two_private_fields(
+                    region,
+                    config,
+                    &(p.x * z.square()),
+                    &(p.y * z.square() * z),
+                    offset,
+                )?;
+                let z = ec_chip.load_private_field(region, config, z, offset)?;
+                jacobian_points.push(AssignedJacobianPoint::new(x, y, z));
+            }
+
+            let res = ec_chip.batch_jacobian_to_affine(region, config, &jacobian_points, offset)?;
+
+            for (p, expected) in res.iter().zip(points.iter()) {
+                let expected = ec_chip.load_private_point(region, config, expected, offset)?;
+                region.constrain_equal(p.x.cell(), expected.x.cell())?;
+                region.constrain_equal(p.y.cell(), expected.y.cell())?;
+            }
+
+            Ok(())
+        })
+    };
+
+    let mut rng = test_rng();
+    let points = (0..4)
+        .map(|_| G1::random(&mut rng).to_affine())
+        .collect::<Vec<_>>();
+    let z = (0..4).map(|_| Fq::random(&mut rng)).collect::<Vec<_>>();
+
+    for n in [1, 4] {
+        run(&points[..n], &z[..n]).unwrap().assert_satisfied();
+    }
+
+    // error case: a zero Z-coordinate has no inverse
+    {
+        let mut z = z.clone();
+        z[2] = Fq::zero();
+        assert!(run(&points, &z).is_err());
+    }
+}
+
+#[test]
+fn test_conditional_point_add_with_identity() {
+    let k = 7;
+
+    let mut rng = test_rng();
+    let p1 = G1::random(&mut rng).to_affine();
+    let p2 = G1::random(&mut rng).to_affine();
+    let identity = (Fq::zero(), Fq::zero());
+    assert_eq!(
+        G1::identity().to_affine(),
+        G1Affine::from_xy(identity.0, identity.1).unwrap()
+    );
+
+    // p2: coordinates of p2, where (0, 0) is the identity
+    // plain: add via `conditional_point_add` rather than `conditional_point_add_with_identity`
+    let run = |p2: (Fq, Fq), bit: bool, expected: G1Affine, plain: bool| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let bit = Fq::from(bit as u64);
+            let res = if plain {
+                let p1 = ec_chip.load_private_point_unchecked(region, config, &p1, offset)?;
+                let p2 = ec_chip.assign_point_from_xy(
+                    region,
+                    config,
+                    Value::known(p2.0),
+                    Value::known(p2.1),
+                    offset,
+                )?;
+                let bit = ec_chip.load_private_field(region, config, &bit, offset)?;
+                ec_chip.conditional_point_add(region, config, &p1, &p2, &bit, offset)?
+            } else {
+                let p1 = ec_chip.load_private_point(region, config, &p1, offset)?;
+                let p2 = ec_chip.assign_point_from_xy(
+                    region,
+                    config,
+                    Value::known(p2.0),
+                    Value::known(p2.1),
+                    offset,
+                )?;
+                let bit = ec_chip.load_private_field(region, config, &bit, offset)?;
+                ec_chip
+                    .conditional_point_add_with_identity(region, config, &p1, &p2, &bit, offset)?
+            };
+
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+        .verify()
+    };
+
+    // p2 is the identity: p1 for both bits
+    for bit in [false, true] {
+        assert!(run(identity, bit, p1, false).is_ok());
+    }
+    // p2 is a point: p1 + p2 iff the bit is 1
+    assert!(run((p2.x, p2.y), false, p1, false).is_ok());
+    assert!(run((p2.x, p2.y), true, (p1 + p2).to_affine(), false).is_ok());
+
+    // error case: the sum when the identity is added
+    for bit in [false, true] {
+        assert!(run(identity, bit, (p1 + p2).to_affine(), false).is_err());
+    }
+    // error case: p1 when p2 is a point and the bit is 1
+    assert!(run((p2.x, p2.y), true, p1, false).is_err());
+
+    // the plain conditional add holds the identity with bit 0 only:
+    // with bit 1 the line through p1 and (0, 0) does not meet -p1
+    assert!(run(identity, false, p1, true).is_ok());
+    assert!(run(identity, true, p1, true).is_err());
+}
+
+#[test]
+fn test_enforce_on_curve_batch() {
+    let k = 6;
+
+    let run = |points: &[G1Affine]| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            ec_chip.load_private_points(region, config, points, offset)?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let mut points = (0..16)
+        .map(|_| G1::random(&mut rng).to_affine())
+        .collect::<Vec<_>>();
+
+    run(&points).assert_satisfied();
+
+    // error case: the 10-th point is off curve, and is caught at its row
+    {
+        points[10].y += Fq::one();
+        let failures = run(&points).verify().unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(
+            &failures[0],
+            VerifyFailure::ConstraintNotSatisfied {
+                location: FailureLocation::InRegion { offset: 10, .. },
+                ..
+            }
+        ));
+    }
+
+    // error case: the points are not the latest assigned rows
+    {
+        let res = mock_run(k, |ec_chip, region, config, offset| {
+            let points = ec_chip.load_private_points(region, config, &points[..2], offset)?;
+            ec_chip.load_private_field(region, config, &Fq::one(), offset)?;
+            ec_chip.enforce_on_curve_batch(region, config, &points, offset)
+        });
+        assert!(matches!(res, Err(Error::Synthesis)));
+    }
+}
+
+#[test]
+fn test_native_reference() {
+    let k = 14;
+
+    let mut rng = test_rng();
+    let s = Fr::random(&mut rng);
+    let p1 = G1::random(&mut rng).to_affine();
+    let p2 = G1::random(&mut rng).to_affine();
+
+    // the circuit agrees with the native reference
+    {
+        let circuit = ECTestCircuit {
+            s,
+            p1,
+            p2,
+            p3: native_add(&p1, &p2).unwrap(),
+            p4: native_double(&p1).unwrap(),
+        };
+        let p5 = native_mul(&p1, &s).unwrap().coordinates().unwrap();
+        let instances = vec![*p5.x(), *p5.y()];
+
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // edge cases the circuit cannot compute
+    let identity = G1::identity().to_affine();
+    assert!(native_add(&p1, &p1).is_none());
+    assert!(native_add(&p1, &-p1).is_none());
+    assert!(native_add(&p1, &identity).is_none());
+    assert!(native_add(&identity, &p2).is_none());
+    assert!(native_double(&identity).is_none());
+    assert!(native_mul(&p1, &Fr::zero()).is_none());
+    assert!(native_mul(&identity, &s).is_none());
+}
+
+#[test]
+fn test_enforce_not_on_curve() {
+    let k = 6;
+
+    // p: coordinates that may be off curve
+    let run = |p: (Fq, Fq)| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.assign_point_from_xy(
+                region,
+                config,
+                Value::known(p.0),
+                Value::known(p.1),
+                offset,
+            )?;
+            ec_chip.enforce_not_on_curve(region, config, &p, offset)?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    // (x, y + 1) is off curve
+    run((p.x, p.y + Fq::one())).assert_satisfied();
+
+    // error case: p is on curve
+    assert!(run((p.x, p.y)).verify().is_err());
+}
+
+#[test]
+fn test_load_private_point_with_residual() {
+    let k = 6;
+
+    // p: coordinates that may be off curve
+    // residual: expected x^3 + b - y^2
+    let run = |p: (Fq, Fq), residual: Fq| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let (_p, residual_rec) = ec_chip.load_private_point_with_residual(
+                region,
+                config,
+                Value::known(p.0),
+                Value::known(p.1),
+                offset,
+            )?;
+
+            // the residual equals the expected value
+            let expected = ec_chip.load_private_field(region, config, &residual, offset)?;
+            region.constrain_equal(residual_rec.cell(), expected.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let b = G1Affine::b();
+
+    // on curve: the residual is 0
+    run((p.x, p.y), Fq::zero()).assert_satisfied();
+
+    // (x, y + 1) is off curve: the residual is x^3 + b - (y + 1)^2 = -(2y + 1)
+    {
+        let y = p.y + Fq::one();
+        let residual = p.x.square() * p.x + b - y.square();
+        assert_eq!(residual, -(p.y.double() + Fq::one()));
+        run((p.x, y), residual).assert_satisfied();
+
+        // error case: the off curve pair with the residual 0
+        assert!(run((p.x, y), Fq::zero()).verify().is_err());
+    }
+
+    // the identity (0, 0) is off curve, with the residual b
+    run((Fq::zero(), Fq::zero()), b).assert_satisfied();
+}
+
+#[test]
+fn test_point_ops_derived_from_slope() {
+    let k = 6;
+
+    let mut rng = test_rng();
+    let p1 = G1::random(&mut rng).to_affine();
+    let p2 = G1::random(&mut rng).to_affine();
+
+    let x1 = *p1.coordinates().unwrap().x();
+    let y1 = *p1.coordinates().unwrap().y();
+    let x2 = *p2.coordinates().unwrap().x();
+    let y2 = *p2.coordinates().unwrap().y();
+    let add_slope = (y2 - y1) * (x2 - x1).invert().unwrap();
+    let double_slope = x1 * x1 * Fq::from(3) * (y1 + y1).invert().unwrap();
+    let sum = (p1 + p2).to_affine();
+    let double = (p1 + p1).to_affine();
+
+    // sum: expected p1 + p2, with the expected slope add_slope
+    // corrupted: overwrites the sum cells
+    let run = |sum_exp: G1Affine, add_slope: Fq, double_slope: Fq, corrupted: Option<(Fq, Fq)>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p1 = ec_chip.load_private_point(region, config, &p1, offset)?;
+            let p2 = ec_chip.load_private_point(region, config, &p2, offset)?;
+
+            let (sum, add_slope_rec) =
+                ec_chip.point_add_with_slope(region, config, &p1, &p2, offset)?;
+            if let Some((x, y)) = corrupted {
+                region.assign_advice(|| "x", config.a, sum.offset(), || Value::known(x))?;
+                region.assign_advice(|| "y", config.b, sum.offset(), || Value::known(y))?;
+            }
+            let (double_rec, double_slope_rec) =
+                ec_chip.point_double_with_slope(region, config, &p1, offset)?;
+
+            let sum_expected = ec_chip.load_private_point(region, config, &sum_exp, offset)?;
+            let double_expected = ec_chip.load_private_point(region, config, &double, offset)?;
+            let add_slope_expected =
+                ec_chip.load_private_field(region, config, &add_slope, offset)?;
+            let double_slope_expected =
+                ec_chip.load_private_field(region, config, &double_slope, offset)?;
+
+            region.constrain_equal(sum.x.cell(), sum_expected.x.cell())?;
+            region.constrain_equal(sum.y.cell(), sum_expected.y.cell())?;
+            region.constrain_equal(double_rec.x.cell(), double_expected.x.cell())?;
+            region.constrain_equal(double_rec.y.cell(), double_expected.y.cell())?;
+            region.constrain_equal(add_slope_rec.cell(), add_slope_expected.cell())?;
+            region.constrain_equal(double_slope_rec.cell(), double_slope_expected.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    run(sum, add_slope, double_slope, None).assert_satisfied();
+
+    // error case: wrong add slope
+    assert!(run(sum, add_slope + Fq::one(), double_slope, None)
+        .verify()
+        .is_err());
+
+    // error case: wrong double slope
+    assert!(run(sum, add_slope, double_slope.double(), None)
+        .verify()
+        .is_err());
+
+    // error case: the sum is replaced by -(p1 + p2), which is on the same line
+    {
+        let neg_sum = -sum;
+        assert!(run(
+            neg_sum,
+            add_slope,
+            double_slope,
+            Some((neg_sum.x, neg_sum.y))
+        )
+        .verify()
+        .is_err());
+    }
+}
+
+#[test]
+fn test_dry_run_offset() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+
+    mock_run(k, |ec_chip, region, config, offset| {
+        let expected = ec_chip.dry_run_offset(|chip, region, config, offset| {
+            chip.point_mul(region, config, &p, &s, offset)
+        })?;
+
+        // start the op at a non-zero offset
+        ec_chip.load_private_point(region, config, &p, offset)?;
+        let start = *offset;
+        ec_chip.point_mul(region, config, &p, &s, offset)?;
+        assert_eq!(*offset - start, expected);
+
+        Ok(())
+    })
+    .unwrap()
+    .assert_satisfied();
+}
+
+#[test]
+fn test_point_mul_bits() {
+    let k = 12;
+
+    // p_times_s: expected s * p
+    let run = |p: G1Affine, s: Fr, n_bits: usize, p_times_s: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p_times_s_rec = ec_chip.point_mul_bits(region, config, &p, &s, n_bits, offset)?;
+            let expected = ec_chip.load_private_point(region, config, &p_times_s, offset)?;
+            region.constrain_equal(p_times_s_rec.x.cell(), expected.x.cell())?;
+            region.constrain_equal(p_times_s_rec.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    for n_bits in [1, 63, 64, 128, 192, 256] {
+        // a random scalar with exactly n_bits bits
+        let (_, r) = field_decompose_u128(&Fr::random(&mut rng));
+        let mut s = Fr::one();
+        for i in 1..n_bits {
+            s = s.double() + Fr::from(((r >> (i % 128)) & 1) as u64);
+        }
+        let p_times_s = (p * s).to_affine();
+
+        run(p, s, n_bits, p_times_s).unwrap().assert_satisfied();
+
+        // error case: wrong result
+        assert!(run(p, s, n_bits, (p_times_s + p).to_affine())
+            .unwrap()
+            .verify()
+            .is_err());
+    }
+
+    // error case: the scalar does not fit in n_bits
+    {
+        let s = Fr::from(1 << 20);
+        assert!(run(p, s, 16, (p * s).to_affine()).is_err());
+    }
+}
+
+#[test]
+fn test_point_mul_bits_rows() {
+    let mut meta = ConstraintSystem::<Fq>::default();
+    let config = ECChip::<G1Affine, Fq>::configure(&mut meta);
+    let ec_chip = ECChip::construct(config);
+
+    let p = G1Affine::generator();
+    let rows = [64, 128, 192, 256]
+        .iter()
+        .map(|&n_bits| {
+            ec_chip
+                .dry_run_offset(|chip, region, config, offset| {
+                    chip.point_mul_bits(region, config, &p, &Fr::one(), n_bits, offset)
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // every 64 bits cost the same number of rows
+    let step = rows[1] - rows[0];
+    assert!(step > 0);
+    assert_eq!(rows[2] - rows[1], step);
+    assert_eq!(rows[3] - rows[2], step);
+
+    // the full 256 bits cost the rows of `point_mul` plus the 192 rows of the bit
+    // blocks, less the seed row of the running sum that `point_mul` decomposes in place
+    let point_mul_rows = ec_chip
+        .dry_run_offset(|chip, region, config, offset| {
+            chip.point_mul(region, config, &p, &Fr::one(), offset)
+        })
+        .unwrap();
+    assert_eq!(rows[3], point_mul_rows + 192 - 1);
+}
+
+const POINT_TABLE_SIZE: usize = 16;
+
+// two tables of constant points: i * G and -i * G for i in 1..=16
+fn point_tables() -> [Vec<G1Affine>; 2] {
+    let gen = G1Affine::generator();
+    let table = (1..=POINT_TABLE_SIZE as u64)
+        .map(|i| (gen * Fr::from(i)).to_affine())
+        .collect::<Vec<_>>();
+    let neg_table = table.iter().map(|p| -*p).collect::<Vec<_>>();
+    [table, neg_table]
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct PointTableTestCircuit {
+    table_id: usize,
+    indices: [u64; 3],
+    expected: [G1Affine; 3],
+    // overwrites the indices after they are looked up
+    corrupted_index: Option<u64>,
+}
+
+impl Circuit<Fq> for PointTableTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            table_id: self.table_id,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        let mut config = ECChip::configure(meta);
+        for table in point_tables().iter() {
+            config.register_point_table(table);
+        }
+        config
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+        ec_chip.load_point_tables(&mut layouter)?;
+
+        layouter.assign_region(
+            || "test point table circuit",
+            |mut region| {
+                let mut offset = 0;
+                for (index, expected) in self.indices.iter().zip(self.expected.iter()) {
+                    let index = ec_chip.load_private_field(
+                        &mut region,
+                        &config,
+                        &Fq::from(*index),
+                        &mut offset,
+                    )?;
+                    let p = ec_chip.lookup_constant_point(
+                        &mut region,
+                        &config,
+                        &index,
+                        self.table_id,
+                        &mut offset,
+                    )?;
+                    let expected =
+                        ec_chip.load_private_point(&mut region, &config, expected, &mut offset)?;
+                    region.constrain_equal(p.x.cell(), expected.x.cell())?;
+                    region.constrain_equal(p.y.cell(), expected.y.cell())?;
+
+                    if let Some(corrupted) = self.corrupted_index {
+                        // keep the copy constraint of the index, so only the lookup fails
+                        let corrupted = Value::known(Fq::from(corrupted));
+                        let row = index.cell().row_offset;
+                        region.assign_advice(|| "index", config.a, row, || corrupted)?;
+                        region.assign_advice(|| "index", config.a, p.offset - 1, || corrupted)?;
+                    }
+                }
+
+                // pad the last two rows
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_lookup_constant_point() {
+    let k = 6;
+    let tables = point_tables();
+    let last = POINT_TABLE_SIZE as u64 - 1;
+
+    // select several indices of both tables, including 0 and N - 1
+    for (i, table) in tables.iter().enumerate() {
+        let circuit = PointTableTestCircuit {
+            table_id: i + 1,
+            indices: [0, 5, last],
+            expected: [table[0], table[5], table[last as usize]],
+            corrupted_index: None,
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: the point is from the other table
+    {
+        let circuit = PointTableTestCircuit {
+            table_id: 1,
+            indices: [0, 5, last],
+            expected: [tables[0][0], tables[1][5], tables[0][last as usize]],
+            corrupted_index: None,
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // error case: the index is out of range
+    {
+        let circuit = PointTableTestCircuit {
+            table_id: 1,
+            indices: [0, 5, last + 1],
+            expected: [tables[0][0], tables[0][5], tables[0][last as usize]],
+            corrupted_index: None,
+        };
+
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+
+    // error case: an out of range index is rejected by the lookup
+    {
+        let circuit = PointTableTestCircuit {
+            table_id: 1,
+            indices: [0, 5, last],
+            expected: [tables[0][0], tables[0][5], tables[0][last as usize]],
+            corrupted_index: Some(last + 1),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Lookup { .. })));
+    }
+
+    // error case: the table is not registered
+    {
+        let circuit = PointTableTestCircuit {
+            table_id: 3,
+            indices: [0, 5, last],
+            expected: [tables[0][0], tables[0][5], tables[0][last as usize]],
+            corrupted_index: None,
+        };
+
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+}
+
+#[test]
+fn test_canonicalize_y() {
+    let k = 10;
+
+    // canonical: expected +/- p with an even y
+    // negated: expected negation bit
+    let run = |p: G1Affine, canonical: G1Affine, negated: bool| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+            let (canonical_rec, negated_rec) =
+                ec_chip.canonicalize_y(region, config, &p, offset)?;
+
+            let expected = ec_chip.load_private_point(region, config, &canonical, offset)?;
+            region.constrain_equal(canonical_rec.x.cell(), expected.x.cell())?;
+            region.constrain_equal(canonical_rec.y.cell(), expected.y.cell())?;
+            region.constrain_constant(negated_rec.cell(), Fq::from(negated as u64))?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let (even, odd) = if bool::from(p.coordinates().unwrap().y().is_odd()) {
+        (-p, p)
+    } else {
+        (p, -p)
+    };
+
+    // an even y is kept, and an odd y is negated
+    for (p, negated) in [(even, false), (odd, true)] {
+        run(p, even, negated).assert_satisfied();
+
+        // error case: wrong negation bit
+        assert!(run(p, even, !negated).verify().is_err());
+    }
+
+    // error case: an odd y is not canonical
+    assert!(run(odd, odd, false).verify().is_err());
+}
+
+#[test]
+fn test_point_mul_witness() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+
+    // the native witness
+    let witness = ECChip::<G1Affine, Fq>::compute_point_mul_witness(&p, &s);
+    assert_eq!(witness.bits.len(), 256);
+    assert_eq!(witness.doubles.len(), 256);
+    assert_eq!(witness.adds.len(), 256);
+    assert_eq!(witness.res, (p * s).to_affine());
+    for (i, (double, add)) in witness.doubles.iter().zip(witness.adds.iter()).enumerate() {
+        if witness.bits[255 - i] {
+            assert_eq!(*add, (*double + p).to_affine());
+        } else {
+            assert_eq!(add, double);
+        }
+    }
+
+    // the assigned witness uses the same rows as `point_mul_assigned` on a loaded point
+    {
+        let mut meta = ConstraintSystem::<Fq>::default();
+        let config = ECChip::<G1Affine, Fq>::configure(&mut meta);
+        let ec_chip = ECChip::construct(config);
+        let rows = ec_chip
+            .dry_run_offset(|chip, region, config, offset| {
+                chip.assign_point_mul_witness(region, config, &witness, offset)
+            })
+            .unwrap();
+        let point_mul_rows = ec_chip
+            .dry_run_offset(|chip, region, config, offset| {
+                let p = chip.load_private_point(region, config, &p, offset)?;
+                chip.point_mul_assigned(region, config, &p, &s, offset)
+            })
+            .unwrap();
+        assert_eq!(rows, point_mul_rows);
+    }
+
+    // tampered_round: replaces the accumulator after the add of this round with the
+    // one after the double
+    let run = |tampered_round: Option<usize>| {
+        let mut witness = witness.clone();
+        if let Some(i) = tampered_round {
+            witness.adds[i] = witness.doubles[i];
+        }
+
+        mock_run(k, move |ec_chip, region, config, offset| {
+            let res = ec_chip.assign_point_mul_witness(region, config, &witness, offset)?;
+            let expected =
+                ec_chip.load_private_point(region, config, &(p * s).to_affine(), offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    run(None).assert_satisfied();
+
+    // error case: a tampered accumulator of a round with a set bit
+    {
+        let round = (0..256).find(|&i| witness.bits[255 - i]).unwrap();
+        assert!(run(Some(round)).verify().is_err());
+    }
+}
+
+#[test]
+fn test_scalar_inverse() {
+    let k = 12;
+
+    // s_inv: expected s^-1
+    let run = |s: Fr, s_inv: Fr| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let (s_hi, s_lo) = field_decompose_u128(&s);
+            let [hi, lo] = ec_chip.load_two_private_fields(
+                region,
+                config,
+                &Fq::from_u128(s_hi),
+                &Fq::from_u128(s_lo),
+                offset,
+            )?;
+            let (inv_hi, inv_lo) = ec_chip.scalar_inverse(region, config, &hi, &lo, offset)?;
+
+            let (expected_hi, expected_lo) = field_decompose_u128(&s_inv);
+            let [expected_hi, expected_lo] = ec_chip.load_two_private_fields(
+                region,
+                config,
+                &Fq::from_u128(expected_hi),
+                &Fq::from_u128(expected_lo),
+                offset,
+            )?;
+            region.constrain_equal(inv_hi.cell(), expected_hi.cell())?;
+            region.constrain_equal(inv_lo.cell(), expected_lo.cell())?;
+
+            Ok(())
+        })
+    };
+
+    let mut rng = test_rng();
+    for s in [Fr::one(), -Fr::one(), Fr::random(&mut rng)] {
+        let s_inv = s.invert().unwrap();
+
+        run(s, s_inv).unwrap().assert_satisfied();
+
+        // error case: wrong inverse
+        assert!(run(s, s_inv + Fr::one()).unwrap().verify().is_err());
+    }
+
+    // error case: 0 has no inverse
+    assert!(run(Fr::zero(), Fr::zero()).is_err());
+}
+
+#[test]
+fn test_classify_pair() {
+    let k = 10;
+
+    // same_x, same_y: expected result
+    let run = |p1: G1Affine, p2: G1Affine, same_x: bool, same_y: bool| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p1 = ec_chip.load_private_point(region, config, &p1, offset)?;
+            let p2 = ec_chip.load_private_point(region, config, &p2, offset)?;
+            let (same_x_rec, same_y_rec) =
+                ec_chip.classify_pair(region, config, &p1, &p2, offset)?;
+            let expected = ec_chip.load_two_private_fields(
+                region,
+                config,
+                &Fq::from(same_x as u64),
+                &Fq::from(same_y as u64),
+                offset,
+            )?;
+            region.constrain_equal(same_x_rec.cell(), expected[0].cell())?;
+            region.constrain_equal(same_y_rec.cell(), expected[1].cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p1 = G1::random(&mut rng).to_affine();
+    let p2 = G1::random(&mut rng).to_affine();
+    let neg_p1 = -p1;
+
+    // double, infinity and add
+    for (p2, same_x, same_y) in [(p1, true, true), (neg_p1, true, false), (p2, false, false)] {
+        run(p1, p2, same_x, same_y).assert_satisfied();
+    }
+
+    // error case: wrong classification
+    for (p2, same_x, same_y) in [(p1, true, false), (neg_p1, true, true), (p2, true, false)] {
+        assert!(run(p1, p2, same_x, same_y).verify().is_err());
+    }
+}
+
+#[test]
+fn test_point_mul_rounds() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+
+    // tampered_round: replaces p in the rows of this round
+    let run = |tampered_round: Option<usize>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let res = ec_chip.point_mul(region, config, &p, &s, offset)?;
+
+            // the seed takes 3 rows and every round 4 rows, plus the correction
+            assert_eq!(*offset, 3 + 4 * 256 + 3);
+
+            if let Some(i) = tampered_round {
+                // overwrite p of the round, i.e., the row after the double
+                let q = (p + G1Affine::generator()).to_affine();
+                let mut p_offset = 3 + 4 * i + 1;
+                ec_chip.load_private_point_unchecked(region, config, &q, &mut p_offset)?;
+            }
+
+            let expected =
+                ec_chip.load_private_point(region, config, &(p * s).to_affine(), offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    run(None).assert_satisfied();
+
+    // error case: p is not carried over in a round whose bit is 0, so that
+    // the conditional add ignores it and only the mul round gate catches it
+    {
+        let witness = ECChip::<G1Affine, Fq>::compute_point_mul_witness(&p, &s);
+        let round = (1..255).find(|&i| !witness.bits[255 - i]).unwrap();
+        let failures = run(Some(round)).verify().unwrap_err();
+        assert!(!failures.is_empty());
+        for failure in failures {
+            match failure {
+                VerifyFailure::ConstraintNotSatisfied { constraint, .. } => {
+                    assert!(constraint.to_string().contains("ec mul round"));
+                }
+                failure => panic!("unexpected failure: {failure}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_point_mul_trace() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+
+    mock_run(k, |ec_chip, region, config, offset| {
+        let start = *offset;
+        let (res, trace) = ec_chip.point_mul_trace(region, config, &p, &s, offset)?;
+        let trace_rows = *offset - start;
+
+        // one accumulator per bit
+        assert_eq!(trace.len(), 256);
+
+        // the same result and rows as point_mul
+        let start = *offset;
+        let res_rec = ec_chip.point_mul(region, config, &p, &s, offset)?;
+        assert_eq!(*offset - start, trace_rows);
+        region.constrain_equal(res.x.cell(), res_rec.x.cell())?;
+        region.constrain_equal(res.y.cell(), res_rec.y.cell())?;
+
+        // the accumulators, from a native double-then-add seeded with the generator
+        let mut acc = G1::generator();
+        let bits = s.to_repr();
+        for (i, acc_assigned) in (0..256).rev().zip(trace.iter()) {
+            acc = acc.double();
+            if (bits[i / 8] >> (i % 8)) & 1 == 1 {
+                acc += p;
+            }
+            let expected =
+                ec_chip.load_private_point_unchecked(region, config, &acc.to_affine(), offset)?;
+            region.constrain_equal(acc_assigned.x.cell(), expected.x.cell())?;
+            region.constrain_equal(acc_assigned.y.cell(), expected.y.cell())?;
+        }
+
+        // the last accumulator is the result plus 2^256 * generator
+        let two_to_256 = Fr::from(2).pow_vartime([256]);
+        assert_eq!(
+            acc.to_affine(),
+            (p * s + G1::generator() * two_to_256).to_affine()
+        );
+
+        Ok(())
+    })
+    .unwrap()
+    .assert_satisfied();
+}
+
+#[test]
+fn test_point_mul_seed_is_fixed() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+    // another point on curve in place of the generator seed
+    let q = G1::random(&mut rng).to_affine();
+
+    // the double-then-add loop of the witness, seeded with q: every round is
+    // consistent, and the result is shifted by 2^256 * (q - generator)
+    let mut witness = ECChip::<G1Affine, Fq>::compute_point_mul_witness(&p, &s);
+    let mut acc = q;
+    for (i, &bit) in witness.bits.clone().iter().rev().enumerate() {
+        acc = (acc + acc).to_affine();
+        witness.doubles[i] = acc;
+        if bit {
+            acc = (acc + p).to_affine();
+        }
+        witness.adds[i] = acc;
+    }
+    let (correction, _, _) = neg_generator_times_2_to_256::<G1Affine, Fq>();
+    witness.res = (acc + correction).to_affine();
+    assert_ne!(witness.res, (p * s).to_affine());
+
+    // the rows of the witness are p, the running sum and then the seed
+    let seed_row = 2;
+    let failures = mock_run(k, |ec_chip, region, config, offset| {
+        ec_chip.assign_point_mul_witness(region, config, &witness, offset)?;
+        overwrite_point(region, config, seed_row, &q)
+    })
+    .unwrap()
+    .verify()
+    .unwrap_err();
+    // the failures are all copy constraints, and one is at the seed row
+    assert!(failures
+        .iter()
+        .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+    assert!(copy_fails_at(&failures, seed_row));
+
+    // the seed of `point_mul_with_bits` is the first row of the op
+    let seed_row = std::cell::Cell::new(0);
+    let failures = mock_run(k, |ec_chip, region, config, offset| {
+        let p = ec_chip.load_private_point(region, config, &p, offset)?;
+        let bits = ec_chip.decompose_scalar(region, config, &s, offset)?;
+        seed_row.set(*offset);
+        ec_chip.point_mul_with_bits(region, config, &p, &bits, offset)?;
+        overwrite_point(region, config, seed_row.get(), &q)
+    })
+    .unwrap()
+    .verify()
+    .unwrap_err();
+    assert!(copy_fails_at(&failures, seed_row.get()));
+}
+
+#[test]
+fn test_point_mul_const_scalar() {
+    let k = 10;
+
+    // p_times_s: expected s * p
+    let run = |p: G1Affine, s: u128, p_times_s: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+
+            let start = *offset;
+            let res = ec_chip.point_mul_const_scalar(region, config, &p, s, offset)?;
+
+            // the leading bit copies p, every other bit is a double,
+            // and every other set bit is an add of 3 rows
+            let num_bits = 128 - s.leading_zeros() as usize;
+            let num_ones = s.count_ones() as usize;
+            assert_eq!(*offset - start, 1 + (num_bits - 1) + 3 * (num_ones - 1));
+
+            let expected = ec_chip.load_private_point(region, config, &p_times_s, offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    for s in [2, 3, 17, 0xdead_beef_0123_4567_89ab_cdef_f00d_cafe] {
+        let p_times_s = (p * Fr::from_u128(s)).to_affine();
+
+        run(p, s, p_times_s).unwrap().assert_satisfied();
+
+        // error case: the result is for a different scalar
+        assert!(run(p, s + 1, p_times_s).unwrap().verify().is_err());
+    }
+
+    // error case: s = 0
+    assert!(run(p, 0, p).is_err());
+}
+
+#[test]
+fn test_decompose_scalars() {
+    let k = 11;
+
+    let mut rng = test_rng();
+    let scalars = (0..4).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+
+    mock_run(k, |ec_chip, region, config, offset| {
+        let bits = ec_chip.decompose_scalars(region, config, &scalars, offset)?;
+        assert_eq!(bits.len(), scalars.len());
+        assert_eq!(*offset, 192 * scalars.len());
+
+        // each scalar recomposes from its bits
+        for (s, bits) in scalars.iter().zip(bits.iter()) {
+            let (low, high) = ec_chip.compose_u128_pair(region, config, bits, offset)?;
+            let (expected_high, expected_low) = field_decompose_u128(s);
+            let expected = ec_chip.load_two_private_fields(
+                region,
+                config,
+                &Fq::from_u128(expected_low),
+                &Fq::from_u128(expected_high),
+                offset,
+            )?;
+            region.constrain_equal(low.cell(), expected[0].cell())?;
+            region.constrain_equal(high.cell(), expected[1].cell())?;
+        }
+
+        Ok(())
+    })
+    .unwrap()
+    .assert_satisfied();
+}
+
+#[test]
+fn test_coordinates_value() {
+    let k = 5;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    mock_run(k, |ec_chip, region, config, offset| {
+        // the values are unknown when the region is measured
+        ec_chip.dry_run_offset(|chip, region, config, offset| {
+            let p = chip.load_private_point(region, config, &p, offset)?;
+            p.coordinates_value().error_if_known_and(|_| true)
+        })?;
+
+        let p_assigned = ec_chip.load_private_point(region, config, &p, offset)?;
+        p_assigned
+            .coordinates_value()
+            .assert_if_known(|&(x, y)| (x, y) == (p.x, p.y));
+
+        Ok(())
+    })
+    .unwrap()
+    .assert_satisfied();
+}
+
+#[test]
+fn test_affine_to_jacobian() {
+    let k = 8;
+
+    let mut rng = test_rng();
+    let points = (0..4)
+        .map(|_| G1::random(&mut rng).to_affine())
+        .collect::<Vec<_>>();
+
+    mock_run(k, |ec_chip, region, config, offset| {
+        let points = ec_chip.load_private_points(region, config, &points, offset)?;
+
+        let jacobian_points = points
+            .iter()
+            .map(|p| ec_chip.affine_to_jacobian(region, config, p, offset))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // round trip
+        let res = ec_chip.batch_jacobian_to_affine(region, config, &jacobian_points, offset)?;
+        for (p, expected) in res.iter().zip(points.iter()) {
+            region.constrain_equal(p.x.cell(), expected.x.cell())?;
+            region.constrain_equal(p.y.cell(), expected.y.cell())?;
+        }
+
+        Ok(())
+    })
+    .unwrap()
+    .assert_satisfied();
+}
+
+#[test]
+fn test_assert_not_two_torsion() {
+    let k = 5;
+
+    // p: coordinates that may be off curve
+    let run = |p: (Fq, Fq)| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p = ec_chip.assign_point_from_xy(
+                region,
+                config,
+                Value::known(p.0),
+                Value::known(p.1),
+                offset,
+            )?;
+            ec_chip.assert_not_two_torsion(region, config, &p, offset)
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    run((p.x, p.y)).assert_satisfied();
+
+    // error case: y = 0; Grumpkin has a prime order, so no such point is on
+    // the curve, and the coordinates are assigned without the on curve check
+    assert!(run((p.x, Fq::zero())).verify().is_err());
+}
+
+#[test]
+fn test_mixed_add() {
+    let k = 8;
+
+    let mut rng = test_rng();
+    let acc = G1::random(&mut rng).to_affine();
+    let p = G1::random(&mut rng).to_affine();
+    let sum = (acc + p).to_affine();
+
+    // z: the Z-coordinate of the accumulator
+    // sum: expected acc + p
+    let run = |z: Fq, sum: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            // (X, Y, Z) = (x * z^2, y * z^3, z)
+            let [x, y] = ec_chip.load_two_private_fields(
+                region,
+                config,
+                &(acc.x * z.square()),
+                &(acc.y * z.square() * z),
+                offset,
+            )?;
+            let z = ec_chip.load_private_field(region, config, &z, offset)?;
+            let acc_jacobian = AssignedJacobianPoint::new(x, y, z);
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+
+            let res = ec_chip.mixed_add(region, config, &acc_jacobian, &p, offset)?;
+            let res = ec_chip.batch_jacobian_to_affine(region, config, &[res], offset)?;
+
+            // the same sum via the affine addition
+            let acc_affine = ec_chip.load_private_point(region, config, &acc, offset)?;
+            let affine_sum = ec_chip.point_add(region, config, &acc_affine, &p, offset)?;
+            region.constrain_equal(res[0].x.cell(), affine_sum.x.cell())?;
+            region.constrain_equal(res[0].y.cell(), affine_sum.y.cell())?;
+
+            let expected = ec_chip.load_private_point(region, config, &sum, offset)?;
+            region.constrain_equal(res[0].x.cell(), expected.x.cell())?;
+            region.constrain_equal(res[0].y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    for z in [Fq::one(), Fq::random(&mut rng)] {
+        run(z, sum).assert_satisfied();
+    }
+
+    // error case: a wrong sum
+    assert!(run(Fq::random(&mut rng), (sum + p).to_affine())
+        .verify()
+        .is_err());
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct ChallengeMulTestCircuit {
+    t: G1Affine,
+    // loads the challenge plus one
+    tampered: bool,
+}
+
+impl Circuit<Fq> for ChallengeMulTestCircuit {
+    // the chip, and a first phase column of the host circuit
+    type Config = (ECConfig<G1Affine, Fq>, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        let t = meta.advice_column();
+        meta.enable_equality(t);
+        (ECChip::configure_second_phase(meta), t)
+    }
+
+    fn synthesize(
+        &self,
+        (config, t_column): Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        // T is committed in the first phase
+        let (t_x, t_y) = layouter.assign_region(
+            || "test first phase",
+            |mut region| {
+                let x = region.assign_advice(|| "x", t_column, 0, || Value::known(self.t.x))?;
+                let y = region.assign_advice(|| "y", t_column, 1, || Value::known(self.t.y))?;
+                Ok((x, y))
+            },
+        )?;
+
+        let r = layouter.get_challenge(config.challenge().unwrap());
+        let r = if self.tampered {
+            r.map(|r| r + Fq::one())
+        } else {
+            r
+        };
+
+        layouter.assign_region(
+            || "test second phase",
+            |mut region| {
+                let mut offset = 0;
+                let x = t_x.copy_advice(|| "x", &mut region, config.a, offset)?;
+                let y = t_y.copy_advice(|| "y", &mut region, config.b, offset)?;
+                let t = AssignedECPoint::new(x, y, offset);
+                offset += 1;
+                ec_chip.enforce_on_curve(&mut region, &config, &t, &mut offset)?;
+
+                // r * T, with r < |Fq| < |Fr|
+                let r_cell = ec_chip.load_challenge(&mut region, &config, r, &mut offset)?;
+                let bits = ec_chip.decompose_field(&mut region, &config, &r_cell, &mut offset)?;
+                let res =
+                    ec_chip.point_mul_with_bits(&mut region, &config, &t, &bits, &mut offset)?;
+
+                let expected = r.map(|r| {
+                    let r = Fr::from_repr(r.to_repr()).unwrap();
+                    (self.t * r).to_affine()
+                });
+                let expected = ec_chip.assign_point_from_xy(
+                    &mut region,
+                    &config,
+                    expected.map(|p| p.x),
+                    expected.map(|p| p.y),
+                    &mut offset,
+                )?;
+                region.constrain_equal(res.x.cell(), expected.x.cell())?;
+                region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+                // pad the last two rows
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_challenge_mul() {
+    let k = 11;
+
+    let mut rng = test_rng();
+    let t = G1::random(&mut rng).to_affine();
+
+    {
+        let circuit = ChallengeMulTestCircuit { t, tampered: false };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: the scalar is not the challenge
+    {
+        let circuit = ChallengeMulTestCircuit { t, tampered: true };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures.iter().all(|f| matches!(
+            f,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if constraint.to_string().contains("challenge")
+        )));
+    }
+
+    // the challenge is only available in the second phase configuration
+    {
+        let mut meta = ConstraintSystem::<Fq>::default();
+        let config = ECChip::<G1Affine, Fq>::configure(&mut meta);
+        assert!(config.challenge().is_none());
+    }
+}
+
+#[test]
+fn test_point_mul_shared_bits() {
+    let k = 13;
+
+    let mut rng = test_rng();
+    let points = (0..3)
+        .map(|_| G1::random(&mut rng).to_affine())
+        .collect::<Vec<_>>();
+    let s = Fr::random(&mut rng);
+    let expected = points
+        .iter()
+        .fold(G1::identity(), |acc, p| acc + p * s)
+        .to_affine();
+
+    let run = |expected: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let points = ec_chip.load_private_points(region, config, &points, offset)?;
+            let bits = ec_chip.decompose_scalar(region, config, &s, offset)?;
+
+            let start = *offset;
+            let res = ec_chip.point_mul_shared_bits(region, config, &points, &bits, offset)?;
+            let shared_rows = *offset - start;
+
+            // the same sum via the joint double-then-add, with a bit copy per point
+            let start = *offset;
+            let mut msm = MsmAccumulator::new();
+            for p in points.iter() {
+                msm.push(p, &bits)?;
+            }
+            let joint = msm.finalize(ec_chip, region, config, offset)?;
+            let joint_rows = *offset - start;
+            assert!(shared_rows < joint_rows);
+
+            region.constrain_equal(res.x.cell(), joint.x.cell())?;
+            region.constrain_equal(res.y.cell(), joint.y.cell())?;
+
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    run(expected).assert_satisfied();
+
+    // error case: wrong result
+    assert!(run((expected + points[0]).to_affine()).verify().is_err());
+}
+
+#[test]
+fn test_batch_verifier() {
+    let k = 13;
+
+    // terms_a: the terms of the first gadget
+    // terms_b: the terms of the second gadget
+    // seed: overwrites the seed of the double-then-add loop, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run = |terms_a: &[(Fr, G1Affine)], terms_b: &[(Fr, G1Affine)], seed: Option<G1Affine>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            // refuses to finalize without terms
+            let empty = BatchVerifier::new();
+            assert!(empty.finalize(ec_chip, region, config, offset).is_err());
+
+            // the terms of both gadgets share one batch
+            let mut batch = BatchVerifier::new();
+            for (w, q) in terms_a.iter().chain(terms_b.iter()) {
+                let q = ec_chip.load_private_point(region, config, q, offset)?;
+                let w = ec_chip.decompose_scalar(region, config, w, offset)?;
+                batch.push(&w, &q)?;
+            }
+            assert_eq!(batch.len(), terms_a.len() + terms_b.len());
+            seed_row.set(*offset);
+            batch.finalize(ec_chip, region, config, offset)?;
+
+            if let Some(q) = seed {
+                overwrite_point(region, config, seed_row.get(), &q)?;
+            }
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let g = G1Affine::generator();
+    let h = G1::random(&mut rng).to_affine();
+
+    // the first gadget: x * G = X, weighted by w_a as w_a * x * G - w_a * X = O
+    let x = Fr::random(&mut rng);
+    let big_x = (g * x).to_affine();
+    let w_a = Fr::random(&mut rng);
+    let terms_a = vec![(w_a * x, g), (-w_a, big_x)];
+
+    // the second gadget: y * H = Y, weighted by w_b
+    let y = Fr::random(&mut rng);
+    let big_y = (h * y).to_affine();
+    let w_b = Fr::random(&mut rng);
+    let terms_b = vec![(w_b * y, h), (-w_b, big_y)];
+
+    run(&terms_a, &terms_b, None).assert_satisfied();
+
+    // error case: a single bad term of the second gadget, Y = (y + 1) * H
+    let mut terms_b = terms_b;
+    terms_b[1].1 = (h * (y + Fr::one())).to_affine();
+    assert!(run(&terms_a, &terms_b, None).verify().is_err());
+
+    // error case: the bad term with a seed Q that cancels it, i.e.,
+    // 2^256 * Q + sum_i w_i * Q_i = 2^256 * G; the seed is a constant
+    {
+        let sum = terms_a
+            .iter()
+            .chain(terms_b.iter())
+            .fold(G1::identity(), |acc, (w, q)| acc + q * w);
+        let two_to_256_inv = Fr::from(2).pow_vartime([256]).invert().unwrap();
+        let seed = (g - sum * two_to_256_inv).to_affine();
+
+        let failures = run(&terms_a, &terms_b, Some(seed)).verify().unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+}
+
+#[test]
+fn test_doubling_chain() {
+    let k = 11;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+
+    // corrupt: the index of a doubling whose witness is replaced by another point on curve
+    let run = |chain_len: usize, corrupt: Option<usize>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let p_assigned = ec_chip.load_private_point(region, config, &p, offset)?;
+
+            let chain = match corrupt {
+                None => ec_chip.doubling_chain(region, config, &p_assigned, chain_len, offset)?,
+                Some(i) => {
+                    // the doublings of p, with the i-th one off by p
+                    let mut witnesses = (1..=chain_len)
+                        .map(|j| (p * Fr::from(1u64 << j)).to_affine())
+                        .collect::<Vec<_>>();
+                    witnesses[i] = (witnesses[i] + p).to_affine();
+                    ec_chip.doubling_chain_with_witnesses(
+                        region,
+                        config,
+                        &p_assigned,
+                        &witnesses,
+                        offset,
+                    )?
+                }
+            };
+            assert_eq!(chain.len(), chain_len + 1);
+
+            // the last point of the chain is 2^k * p
+            let expected = ec_chip.point_mul_assigned(
+                region,
+                config,
+                &p_assigned,
+                &Fr::from(1u64 << chain_len),
+                offset,
+            )?;
+            let last = chain.last().unwrap();
+            region.constrain_equal(last.x.cell(), expected.x.cell())?;
+            region.constrain_equal(last.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    for chain_len in [0, 1, 8] {
+        run(chain_len, None).assert_satisfied();
+    }
+
+    // error case: a single corrupted link breaks the chain
+    for i in [0, 3, 7] {
+        assert!(run(8, Some(i)).verify().is_err());
+    }
+}
+
+#[test]
+fn test_uniform_layout() {
+    let k = 13;
+
+    // the advice cells that are assigned, per column
+    let layout = |p: G1Affine, s: Fr, a: Fq| {
+        let prover = mock_run(k, |ec_chip, region, config, offset| {
+            ec_chip.point_mul(region, config, &p, &s, offset)?;
+
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+            ec_chip.point_mul_assigned(region, config, &p, &s, offset)?;
+
+            let a = ec_chip.load_private_field(region, config, &a, offset)?;
+            ec_chip.decompose_field(region, config, &a, offset)?;
+
+            let bits = ec_chip.decompose_scalar(region, config, &s, offset)?;
+            let mut msm = MsmAccumulator::new();
+            msm.push(&p, &bits)?;
+            msm.push(&p, &bits)?;
+            msm.finalize(ec_chip, region, config, offset)?;
+
+            Ok(())
+        })
+        .unwrap();
+        prover
+            .advice()
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| matches!(cell, CellValue::Assigned(_)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut rng = test_rng();
+    let g = G1Affine::generator();
+    let p = G1::random(&mut rng).to_affine();
+    let expected = layout(p, Fr::random(&mut rng), Fq::random(&mut rng));
+
+    // extreme inputs, including the zero scalar, for which the results are
+    // the identity and the constraints fail, but the layout is the same
+    for (p, s, a) in [
+        (g, Fr::zero(), Fq::zero()),
+        (g, Fr::one(), Fq::one()),
+        (-g, -Fr::one(), -Fq::one()),
+        (
+            p,
+            Fr::from_u128(1 << 127).double(),
+            Fq::from_u128(u128::MAX),
+        ),
+        (p, Fr::zero(), -Fq::one()),
+    ] {
+        assert!(layout(p, s, a) == expected);
+    }
+}
+
+#[test]
+fn test_point_mul_with_bits_real_prover() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let q = G1::random(&mut rng).to_affine();
+    // almost every bit is set, so that almost every round adds p
+    let s = -Fr::one();
+
+    // p is public; the double-then-add runs over `base`, a copy of p or of q
+    let circuit = |swap: bool| {
+        move |ec_chip: &ECChip<G1Affine, Fq>,
+              region: &mut Region<'_, Fq>,
+              config: &ECConfig<G1Affine, Fq>,
+              offset: &mut usize|
+              -> Result<Vec<AssignedCell<Fq, Fq>>, Error> {
+            let p = ec_chip.load_private_point(region, config, &p, offset)?;
+            let q = ec_chip.load_private_point(region, config, &q, offset)?;
+            let bits = ec_chip.decompose_scalar(region, config, &s, offset)?;
+
+            let base = if swap { &q } else { &p };
+            let res = ec_chip.point_mul_with_bits(region, config, base, &bits, offset)?;
+
+            Ok(vec![p.x, p.y, res.x, res.y])
+        }
+    };
+    let instance = |res: G1Affine| vec![p.x, p.y, res.x, res.y];
+
+    // the keys are generated without the witness, so the copies of p into the
+    // conditional adds must not depend on the bits
+    assert!(real_prover_verifies(
+        k,
+        instance((p * s).to_affine()),
+        circuit(false),
+        circuit(false),
+    ));
+
+    // error case: the prover swaps p for q in the rounds, with the same layout
+    assert!(!real_prover_verifies(
+        k,
+        instance((q * s).to_affine()),
+        circuit(false),
+        circuit(true),
+    ));
+}
+
+// the expected outcome of a layouter-facing op
+type EccErrorCheck = fn(&Result<AssignedECPoint<G1Affine, Fq>, EccError>) -> bool;
+
+#[derive(Debug, Clone)]
+struct EccErrorTestCircuit {
+    // the inputs of `msm`; `load_point_from_instance` if None
+    msm: Option<(Vec<G1Affine>, Vec<Fr>)>,
+    check: EccErrorCheck,
+}
+
+impl Circuit<Fq> for EccErrorTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        let res = match &self.msm {
+            Some((points, scalars)) => ec_chip.msm(&mut layouter, &config, points, scalars),
+            None => ec_chip.load_point_from_instance(&mut layouter, &config, 0),
+        };
+        assert!((self.check)(&res), "unexpected result: {:?}", res.err());
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_ecc_error() {
+    let k = 13;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let q = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+    let t = Fr::random(&mut rng);
+
+    let run = |msm: Option<(Vec<G1Affine>, Vec<Fr>)>, instance: Vec<Fq>, check: EccErrorCheck| {
+        let circuit = EccErrorTestCircuit { msm, check };
+        MockProver::run(k, &circuit, vec![instance]).unwrap()
+    };
+
+    // the ops succeed on valid inputs
+    let prover = run(Some((vec![p, q], vec![s, t])), vec![], |res| res.is_ok());
+    prover.assert_satisfied();
+    let prover = run(None, vec![p.x, p.y], |res| res.is_ok());
+    prover.assert_satisfied();
+
+    // mismatched numbers of points and scalars
+    run(Some((vec![p, q], vec![s])), vec![], |res| {
+        matches!(
+            res,
+            Err(EccError::LengthMismatch {
+                expected: 2,
+                got: 1
+            })
+        )
+    });
+
+    // no point, an identity point, and a sum that is the identity
+    let is_identity_error: EccErrorCheck =
+        |res| matches!(res, Err(EccError::IdentityNotRepresentable));
+    run(Some((vec![], vec![])), vec![], is_identity_error);
+    run(
+        Some((vec![p, G1::identity().to_affine()], vec![s, t])),
+        vec![],
+        is_identity_error,
+    );
+    run(Some((vec![p, -p], vec![s, s])), vec![], is_identity_error);
+
+    // the identity as a public point
+    run(None, vec![Fq::zero(), Fq::zero()], is_identity_error);
+
+    // a region that runs out of rows is a plonk error
+    run(Some((vec![p; 16], vec![s; 16])), vec![], |res| {
+        matches!(res, Err(EccError::Plonk(_)))
+    });
+
+    // the gadget errors are synthesis errors of the proving system
+    assert!(matches!(
+        Error::from(EccError::IdentityNotRepresentable),
+        Error::Synthesis
+    ));
+    assert!(matches!(
+        Error::from(EccError::Plonk(Error::BoundsFailure)),
+        Error::BoundsFailure
+    ));
 }
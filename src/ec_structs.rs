@@ -2,9 +2,18 @@ use std::marker::PhantomData;
 
 use halo2_proofs::arithmetic::Field;
 use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::circuit::Value;
+use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
 
 use crate::util::leak;
+use crate::util::neg_generator_times_2_to_256;
+use crate::ArithOps;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
 
 #[derive(Debug, Clone)]
 pub struct AssignedECPoint<C, F>
@@ -40,4 +49,534 @@ where
     pub fn offset(&self) -> usize {
         self.offset
     }
+
+    /// The cell of the x-coordinate.
+    ///
+    /// A point has two coordinate cells of the same type, so a single
+    /// `AsRef<AssignedCell<F, F>>` would be ambiguous; use `x()` and `y()` instead.
+    pub fn x(&self) -> &AssignedCell<F, F> {
+        &self.x
+    }
+
+    /// The cell of the y-coordinate.
+    pub fn y(&self) -> &AssignedCell<F, F> {
+        &self.y
+    }
+
+    /// The values of the (x, y) coordinates, zipped without leaking them;
+    /// unknown if either value is unknown.
+    pub fn coordinates_value(&self) -> Value<(F, F)> {
+        self.x.value().zip(self.y.value()).map(|(x, y)| (*x, *y))
+    }
+}
+
+/// Destruct a point into its (x, y) coordinate cells, e.g., to hash them
+/// with a chip that works on raw field element cells.
+///
+/// ```
+/// use halo2_native_ecc::AssignedECPoint;
+/// use halo2_proofs::circuit::AssignedCell;
+/// use halo2curves::grumpkin::Fq;
+/// use halo2curves::grumpkin::G1Affine;
+///
+/// fn coordinates(p: AssignedECPoint<G1Affine, Fq>) -> [AssignedCell<Fq, Fq>; 2] {
+///     let (x, y) = p.into();
+///     [x, y]
+/// }
+/// ```
+impl<C, F> From<AssignedECPoint<C, F>> for (AssignedCell<F, F>, AssignedCell<F, F>)
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    fn from(p: AssignedECPoint<C, F>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+/// Build a point from a pair of (x, y) coordinate cells.
+///
+/// Returns a synthesis error if the two cells are not in a same row of a same region.
+/// The point is not constrained to be on curve; caller should call `enforce_on_curve`.
+///
+/// ```
+/// use halo2_native_ecc::AssignedECPoint;
+/// use halo2_proofs::circuit::AssignedCell;
+/// use halo2_proofs::plonk::Error;
+/// use halo2curves::grumpkin::Fq;
+/// use halo2curves::grumpkin::G1Affine;
+///
+/// fn point(
+///     x: AssignedCell<Fq, Fq>,
+///     y: AssignedCell<Fq, Fq>,
+/// ) -> Result<AssignedECPoint<G1Affine, Fq>, Error> {
+///     (x, y).try_into()
+/// }
+/// ```
+impl<C, F> TryFrom<(AssignedCell<F, F>, AssignedCell<F, F>)> for AssignedECPoint<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    type Error = Error;
+
+    fn try_from((x, y): (AssignedCell<F, F>, AssignedCell<F, F>)) -> Result<Self, Error> {
+        let (x_cell, y_cell) = (x.cell(), y.cell());
+        if x_cell.region_index != y_cell.region_index || x_cell.row_offset != y_cell.row_offset {
+            return Err(Error::Synthesis);
+        }
+        let offset = x_cell.row_offset;
+        Ok(Self::new(x, y, offset))
+    }
+}
+
+impl<C, F> AssignedECPoint<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Encode the point into 32 byte cells in the compressed format:
+    /// - the x-coordinate in little endian
+    /// - the parity of y as the most significant bit of the last byte
+    ///
+    /// Both coordinates are canonically decomposed, so the encoding is unique.
+    pub fn to_compressed_bytes(
+        &self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<[AssignedCell<F, F>; 32], Error> {
+        let x_bits = chip.decompose_field(region, config, &self.x, offset)?;
+        let y_bits = chip.decompose_field(region, config, &self.y, offset)?;
+
+        let mut bytes = vec![];
+        for i in 0..31 {
+            bytes.push(chip.compose_bits(region, config, &x_bits[8 * i..8 * i + 8], offset)?);
+        }
+        // the modulus has less than 255 bits, so the last bit of x is always 0
+        // and is replaced with the sign of y
+        let mut last_byte_bits = x_bits[248..255].to_vec();
+        last_byte_bits.push(y_bits[0].clone());
+        bytes.push(chip.compose_bits(region, config, &last_byte_bits, offset)?);
+
+        Ok(bytes.try_into().unwrap())
+    }
+
+    /// Decode a point from 32 byte cells in the compressed format.
+    /// Constraints
+    /// - each byte is in [0, 256)
+    /// - the x-coordinate is canonically encoded
+    /// - the point is on curve, and the parity of y matches the sign bit
+    ///
+    /// Returns a synthesis error if the bytes do not encode a valid point.
+    pub fn from_compressed_bytes(
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        bytes: &[AssignedCell<F, F>; 32],
+        offset: &mut usize,
+    ) -> Result<Self, Error> {
+        // decompose the bytes into bits
+        let mut bits = vec![];
+        let mut repr = [0u8; 32];
+        for (i, byte) in bytes.iter().enumerate() {
+            let byte_value = leak(&byte.value()).to_repr()[0];
+            repr[i] = byte_value;
+            let byte_bits = (0..8)
+                .map(|j| F::from(((byte_value >> j) & 1) as u64))
+                .collect::<Vec<_>>();
+            let (byte_bits, acc) = chip.assign_bit_blocks(region, config, &byte_bits, offset)?;
+            region.constrain_equal(byte.cell(), acc.cell())?;
+            bits.extend_from_slice(&byte_bits);
+        }
+
+        // recover the point
+        let sign = repr[31] >> 7 == 1;
+        repr[31] &= 0x7f;
+        let x = F::from_repr(repr);
+        let y = x.and_then(|x| (x.square() * x + C::b()).sqrt());
+        // the values are unknown when the floor planner measures the region;
+        // only known bytes can fail to decode
+        bytes[0]
+            .value()
+            .error_if_known_and(|_| bool::from(y.is_none()))?;
+        let x = x.unwrap_or(F::ZERO);
+        let y = y.unwrap_or(F::ZERO);
+        let y = if bool::from(y.is_odd()) == sign {
+            y
+        } else {
+            -y
+        };
+
+        let p =
+            chip.assign_point_from_xy(region, config, Value::known(x), Value::known(y), offset)?;
+        chip.enforce_on_curve(region, config, &p, offset)?;
+
+        // the bits of x are canonical
+        let x_bits = chip.decompose_field(region, config, &p.x, offset)?;
+        for (b, b_rec) in bits.iter().take(255).zip(x_bits.iter()) {
+            region.constrain_equal(b.cell(), b_rec.cell())?;
+        }
+        // the parity of y matches the sign
+        let y_bits = chip.decompose_field(region, config, &p.y, offset)?;
+        region.constrain_equal(bits[255].cell(), y_bits[0].cell())?;
+
+        Ok(p)
+    }
+}
+
+/// A point in Jacobian coordinates (X, Y, Z), that is the affine point
+/// (X / Z^2, Y / Z^3). The cells can be in any rows.
+#[derive(Debug, Clone)]
+pub struct AssignedJacobianPoint<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    pub(crate) x: AssignedCell<F, F>,
+    pub(crate) y: AssignedCell<F, F>,
+    pub(crate) z: AssignedCell<F, F>,
+    _phantom: PhantomData<C>,
+}
+
+impl<C, F> AssignedJacobianPoint<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    pub fn new(x: AssignedCell<F, F>, y: AssignedCell<F, F>, z: AssignedCell<F, F>) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            _phantom: PhantomData::default(),
+        }
+    }
+}
+
+/// A batch of independent assigned points, i.e., the bases of an MSM.
+#[derive(Debug, Clone)]
+pub struct PointBatch<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    pub(crate) points: Vec<AssignedECPoint<C, F>>,
+}
+
+impl<C, F> PointBatch<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Loads the points into the circuit as private inputs.
+    /// Constraints all points are on curve.
+    pub fn load(
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        points: &[C],
+        offset: &mut usize,
+    ) -> Result<Self, Error> {
+        let points = points
+            .iter()
+            .map(|p| chip.load_private_point(region, config, p, offset))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self { points })
+    }
+
+    pub fn points(&self) -> &[AssignedECPoint<C, F>] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Return the sum of all points in the batch.
+    ///
+    /// Caller must ensure no partial sum equals +/- the next point.
+    /// Returns a synthesis error if the batch is empty.
+    pub fn sum(
+        &self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        let (first, rest) = self.points.split_first().ok_or(Error::Synthesis)?;
+
+        let mut res = first.clone();
+        for p in rest {
+            res = chip.point_add(region, config, &res, p, offset)?;
+        }
+        Ok(res)
+    }
+
+    /// Return the batch [s_i * p_i].
+    ///
+    /// Returns a synthesis error if the numbers of points and scalars differ.
+    pub fn map_mul<S>(
+        &self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        scalars: &[S],
+        offset: &mut usize,
+    ) -> Result<Self, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        if self.points.len() != scalars.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let points = self
+            .points
+            .iter()
+            .zip(scalars.iter())
+            .map(|(p, s)| chip.point_mul_assigned(region, config, p, s, offset))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self { points })
+    }
+}
+
+/// An incremental MSM, for circuits that discover their (point, scalar) pairs
+/// one at a time.
+///
+/// The pairs are buffered, and `finalize` computes sum_i s_i * p_i with a single
+/// double-then-add loop that shares the doublings among all buffered pairs.
+/// `flush` forces the buffered pairs to be folded into a partial sum early,
+/// with a separate mul-and-add per pair.
+#[derive(Debug, Clone)]
+pub struct MsmAccumulator<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    pairs: Vec<(AssignedECPoint<C, F>, Vec<AssignedCell<F, F>>)>,
+    partial: Option<AssignedECPoint<C, F>>,
+}
+
+impl<C, F> Default for MsmAccumulator<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    fn default() -> Self {
+        Self {
+            pairs: vec![],
+            partial: None,
+        }
+    }
+}
+
+impl<C, F> MsmAccumulator<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer a point and its scalar as 256 little endian bit cells.
+    ///
+    /// Caller must check the point is on curve and the bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    pub fn push(
+        &mut self,
+        point: &AssignedECPoint<C, F>,
+        scalar_bits: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        // the offset generator correction is for 256 doublings
+        if scalar_bits.len() != 256 {
+            return Err(Error::Synthesis);
+        }
+        self.pairs.push((point.clone(), scalar_bits.to_vec()));
+        Ok(())
+    }
+
+    /// The number of buffered pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Fold the buffered pairs into the partial sum, one mul-and-add per pair.
+    ///
+    /// Caller must ensure no partial sum equals +/- the next product.
+    pub fn flush(
+        &mut self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        for (p, bits) in self.pairs.drain(..) {
+            let product = chip.point_mul_with_bits(region, config, &p, &bits, offset)?;
+            self.partial = Some(match &self.partial {
+                Some(partial) => chip.point_add(region, config, partial, &product, offset)?,
+                None => product,
+            });
+        }
+        Ok(())
+    }
+
+    /// Return sum_i s_i * p_i over all pushed pairs.
+    ///
+    /// The layout depends on the number of pairs and flushes, not on their values.
+    /// Caller must ensure no partial sum equals +/- the next product.
+    /// Returns a synthesis error if no pair was pushed.
+    pub fn finalize(
+        self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        if self.pairs.is_empty() {
+            return self.partial.ok_or(Error::Synthesis);
+        }
+
+        let pairs = self
+            .pairs
+            .iter()
+            .map(|(p, bits)| (p, bits.as_slice()))
+            .collect::<Vec<_>>();
+        let res = chip.joint_double_then_add(region, config, &pairs, offset)?;
+        let res = chip.subtract_offset_generator(region, config, &res, offset)?;
+
+        match &self.partial {
+            Some(partial) => chip.point_add(region, config, partial, &res, offset),
+            None => Ok(res),
+        }
+    }
+}
+
+/// A batch of verification equations sum_i w_i * q_i = O, for gadgets whose
+/// checks end with a random linear combination of points.
+///
+/// Gadgets push their (weight, point) terms during synthesis, and `finalize`
+/// checks all of them with a single double-then-add loop that shares the
+/// doublings among all terms. The loop starts at the generator, so the sum is
+/// the identity iff the loop ends at 2^256 * generator; since infinity is not
+/// representable, the result is compared with that constant instead of
+/// subtracting the offset generator.
+#[derive(Debug, Clone)]
+pub struct BatchVerifier<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    terms: Vec<(AssignedECPoint<C, F>, Vec<AssignedCell<F, F>>)>,
+}
+
+impl<C, F> Default for BatchVerifier<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    fn default() -> Self {
+        Self { terms: vec![] }
+    }
+}
+
+impl<C, F> BatchVerifier<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the term w * q, with the weight w as 256 little endian bit cells.
+    ///
+    /// Caller must check the point is on curve and the bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    pub fn push(
+        &mut self,
+        weight_bits: &[AssignedCell<F, F>],
+        point: &AssignedECPoint<C, F>,
+    ) -> Result<(), Error> {
+        // the offset generator correction is for 256 doublings
+        if weight_bits.len() != 256 {
+            return Err(Error::Synthesis);
+        }
+        self.terms.push((point.clone(), weight_bits.to_vec()));
+        Ok(())
+    }
+
+    /// The number of terms.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Enforce sum_i w_i * q_i = O over all pushed terms.
+    ///
+    /// Caller must ensure no partial sum of the loop equals +/- the next term,
+    /// which holds except with negligible probability for random weights.
+    /// Returns a synthesis error if no term was pushed.
+    pub fn finalize(
+        self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        if self.terms.is_empty() {
+            return Err(Error::Synthesis);
+        }
+
+        let terms = self
+            .terms
+            .iter()
+            .map(|(q, bits)| (q, bits.as_slice()))
+            .collect::<Vec<_>>();
+        let res = chip.joint_double_then_add(region, config, &terms, offset)?;
+
+        // 2^256 * generator is the negation of the offset generator correction
+        let (_, x, y) = neg_generator_times_2_to_256::<C, F>();
+        region.constrain_constant(res.x.cell(), x)?;
+        region.constrain_constant(res.y.cell(), -y)?;
+        Ok(())
+    }
+}
+
+/// The native intermediate values of a `point_mul`, computed by
+/// `ECChip::compute_point_mul_witness` and assigned by
+/// `NativeECOps::assign_point_mul_witness`.
+///
+/// The double-then-add loop starts from the generator and runs over the
+/// 256 bits of the scalar, most significant first; each round doubles the
+/// accumulator, then adds p if the bit is set.
+#[derive(Debug, Clone)]
+pub struct PointMulWitness<C>
+where
+    C: CurveAffine,
+{
+    /// the base point
+    pub p: C,
+    /// the bits of the scalar, in little endian
+    pub bits: Vec<bool>,
+    /// the accumulator after the doubling of each round
+    pub doubles: Vec<C>,
+    /// the accumulator after the conditional add of each round
+    pub adds: Vec<C>,
+    /// s * p, i.e., the last accumulator minus 2^256 * generator
+    pub res: C,
 }
@@ -0,0 +1,86 @@
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// The ECIES gadgets of the chip, on top of `NativeECOps`.
+pub trait EciesOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// The key encapsulation of ECIES for a recipient key PK and an ephemeral
+    /// secret k: returns the ephemeral key E = k * G, the shared point S = k * PK,
+    /// and the x-coordinate of S, for the caller to feed its KDF or hash chip.
+    ///
+    /// Both point muls read the same k bit cells, so E and S cannot be made with
+    /// different secrets. G is fixed to the generator.
+    ///
+    /// Caller must check PK is on curve and the k bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn ecies_encapsulate(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        pk: &AssignedECPoint<C, F>,
+        k_bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<
+        (
+            AssignedECPoint<C, F>,
+            AssignedECPoint<C, F>,
+            AssignedCell<F, F>,
+        ),
+        Error,
+    >;
+}
+
+impl<C, F> EciesOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// The key encapsulation of ECIES for a recipient key PK and an ephemeral
+    /// secret k: returns the ephemeral key E = k * G, the shared point S = k * PK,
+    /// and the x-coordinate of S, for the caller to feed its KDF or hash chip.
+    ///
+    /// Both point muls read the same k bit cells, so E and S cannot be made with
+    /// different secrets. G is fixed to the generator.
+    ///
+    /// Caller must check PK is on curve and the k bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256.
+    fn ecies_encapsulate(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        pk: &AssignedECPoint<C, F>,
+        k_bits: &[AssignedCell<F, F>],
+        offset: &mut usize,
+    ) -> Result<
+        (
+            AssignedECPoint<C, F>,
+            AssignedECPoint<C, F>,
+            AssignedCell<F, F>,
+        ),
+        Error,
+    > {
+        // E = k * G, with G fixed to the generator
+        let gen_assigned = self.load_generator(region, config, offset)?;
+        let e = self.point_mul_with_bits(region, config, &gen_assigned, k_bits, offset)?;
+
+        // S = k * PK
+        let s = self.point_mul_with_bits(region, config, pk, k_bits, offset)?;
+        let s_x = s.x.clone();
+
+        Ok((e, s, s_x))
+    }
+}
@@ -0,0 +1,60 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+
+use crate::test_circuit::mock_run_with_instance;
+use crate::EciesOps;
+use crate::NativeECOps;
+
+#[test]
+fn test_ecies_encapsulate() {
+    let k = 13;
+
+    // e: expected k * G
+    // s: expected k * pk
+    // the x-coordinate of S is public, in place of a KDF
+    let run = |r: Fr, pk: G1Affine, e: G1Affine, s: G1Affine, s_x: Fq| {
+        mock_run_with_instance(k, vec![s_x], |ec_chip, region, config, offset| {
+            let k_bits = ec_chip.decompose_scalar(region, config, &r, offset)?;
+            let pk = ec_chip.load_private_point(region, config, &pk, offset)?;
+            let (e_rec, s_rec, s_x) =
+                ec_chip.ecies_encapsulate(region, config, &pk, &k_bits, offset)?;
+
+            for (p, expected) in [(&e_rec, &e), (&s_rec, &s)] {
+                let expected = ec_chip.load_private_point(region, config, expected, offset)?;
+                region.constrain_equal(p.x.cell(), expected.x.cell())?;
+                region.constrain_equal(p.y.cell(), expected.y.cell())?;
+            }
+
+            Ok(vec![s_x])
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let sk = Fr::random(&mut rng);
+    let pk = (G1Affine::generator() * sk).to_affine();
+    let r = Fr::random(&mut rng);
+    let e = (G1Affine::generator() * r).to_affine();
+    let s = (pk * r).to_affine();
+
+    // the recipient derives the same shared point
+    assert_eq!((e * sk).to_affine(), s);
+
+    run(r, pk, e, s, s.x).assert_satisfied();
+
+    // error case: E and S made with different ephemeral secrets, for either k
+    {
+        let r_other = Fr::random(&mut rng);
+        let s_other = (pk * r_other).to_affine();
+        for k_witness in [r, r_other] {
+            assert!(run(k_witness, pk, e, s_other, s_other.x).verify().is_err());
+        }
+    }
+
+    // error case: the public x-coordinate is not the one of S
+    assert!(run(r, pk, e, s, e.x).verify().is_err());
+}
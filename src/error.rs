@@ -0,0 +1,66 @@
+use std::fmt;
+
+use halo2_proofs::plonk::Error;
+
+/// The error of the layouter-facing APIs of the chip.
+///
+/// The region-level ops of `NativeECOps` and `ArithOps` return `plonk::Error`,
+/// and are wrapped in `EccError::Plonk`. Converting back to `plonk::Error`, e.g.,
+/// via `?` in `Circuit::synthesize`, maps the gadget-specific variants to
+/// `Error::Synthesis`.
+#[derive(Debug)]
+pub enum EccError {
+    /// an error of the proving system, e.g., a region ran out of rows
+    Plonk(Error),
+    /// a point, or the result of an op, is the identity, which has no cell representation
+    IdentityNotRepresentable,
+    /// the lengths of two inputs that must match do not
+    LengthMismatch { expected: usize, got: usize },
+    /// a circuit uses fewer than `min_ratio * 2^k` rows, see `ECChip::assert_row_utilization`
+    LowRowUtilization { rows: usize, k: u32, min_ratio: f64 },
+}
+
+impl fmt::Display for EccError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EccError::Plonk(e) => write!(f, "{}", e),
+            EccError::IdentityNotRepresentable => {
+                write!(f, "the identity is not representable")
+            }
+            EccError::LengthMismatch { expected, got } => {
+                write!(f, "length mismatch: expected {}, got {}", expected, got)
+            }
+            EccError::LowRowUtilization { rows, k, min_ratio } => {
+                write!(
+                    f,
+                    "the circuit uses {} of 2^{} rows, a ratio below {}",
+                    rows, k, min_ratio
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for EccError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EccError::Plonk(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for EccError {
+    fn from(e: Error) -> Self {
+        EccError::Plonk(e)
+    }
+}
+
+impl From<EccError> for Error {
+    fn from(e: EccError) -> Self {
+        match e {
+            EccError::Plonk(e) => e,
+            _ => Error::Synthesis,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+
+use crate::config::KEY_IMAGE_DOMAIN;
+use crate::config::KEY_IMAGE_MAX_ITERATIONS;
+use crate::ArithOps;
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// The key image gadgets of the chip, on top of `NativeECOps`.
+pub trait KeyImageOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Derive the key image KI = sk * H(PK) of a key pair, where H is the
+    /// try-and-increment map to the curve, in at most `KEY_IMAGE_MAX_ITERATIONS` tries.
+    ///
+    /// The crate has no hash function, so PK is serialized as
+    /// u = x + `KEY_IMAGE_DOMAIN` + sign * 2^128, where the sign is 1 iff y is odd,
+    /// as in `load_compressed_pubkey`. The tag keeps every candidate u + i off the
+    /// x-coordinate of PK, so H(PK) is never +/- PK, whose discrete log is sk.
+    /// The candidates of PK and -PK are 2^128 apart, so they do not share H.
+    /// The serialization is not injective: PK and a point with the x-coordinate
+    /// x +/- 2^128 and the other sign share u, and then H. Callers that need a
+    /// random oracle H should hash PK with a hash chip and map the digest with
+    /// `hash_to_curve_try_and_increment` instead.
+    ///
+    /// Constraints PK = sk * G and KI = sk * H(PK) with the same sk bit cells.
+    /// Caller must check the sk bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256, or if none
+    /// of the tries is on curve.
+    fn derive_key_image(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        sk_bits: &[AssignedCell<F, F>],
+        pk: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error>;
+}
+
+impl<C, F> KeyImageOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Derive the key image KI = sk * H(PK) of a key pair, where H is the
+    /// try-and-increment map to the curve, in at most `KEY_IMAGE_MAX_ITERATIONS` tries.
+    ///
+    /// The crate has no hash function, so PK is serialized as
+    /// u = x + `KEY_IMAGE_DOMAIN` + sign * 2^128, where the sign is 1 iff y is odd,
+    /// as in `load_compressed_pubkey`. The tag keeps every candidate u + i off the
+    /// x-coordinate of PK, so H(PK) is never +/- PK, whose discrete log is sk.
+    /// The candidates of PK and -PK are 2^128 apart, so they do not share H.
+    /// The serialization is not injective: PK and a point with the x-coordinate
+    /// x +/- 2^128 and the other sign share u, and then H. Callers that need a
+    /// random oracle H should hash PK with a hash chip and map the digest with
+    /// `hash_to_curve_try_and_increment` instead.
+    ///
+    /// Constraints PK = sk * G and KI = sk * H(PK) with the same sk bit cells.
+    /// Caller must check the sk bits are binary.
+    /// Returns a synthesis error if the number of bits is not 256, or if none
+    /// of the tries is on curve.
+    fn derive_key_image(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        sk_bits: &[AssignedCell<F, F>],
+        pk: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        // PK = sk * G, with G fixed to the generator
+        let gen_assigned = self.load_generator(region, config, offset)?;
+        let pk_rec = self.point_mul_with_bits(region, config, &gen_assigned, sk_bits, offset)?;
+        region.constrain_equal(pk_rec.x.cell(), pk.x.cell())?;
+        region.constrain_equal(pk_rec.y.cell(), pk.y.cell())?;
+
+        // u = x + tag + sign * 2^128, with sign = 1 - is_even(y)
+        let two_to_128 = F::from_u128(1 << 127) * F::from(2);
+        let is_even = self.is_even(region, config, &pk.y, offset)?;
+        let sign = self.mul_const(region, config, &is_even, &-two_to_128, offset)?;
+        let sign = self.add_const(
+            region,
+            config,
+            &sign,
+            &(two_to_128 + F::from_u128(KEY_IMAGE_DOMAIN)),
+            offset,
+        )?;
+        let u = self.add_cells(region, config, &pk.x, &sign, offset)?;
+
+        // KI = sk * H(PK)
+        let h = self.hash_to_curve_try_and_increment(
+            region,
+            config,
+            &u,
+            KEY_IMAGE_MAX_ITERATIONS,
+            offset,
+        )?;
+        self.point_mul_with_bits(region, config, &h, sk_bits, offset)
+    }
+}
@@ -0,0 +1,77 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+
+use crate::test_circuit::mock_run;
+use crate::test_circuit::try_and_increment;
+use crate::KeyImageOps;
+use crate::NativeECOps;
+use crate::KEY_IMAGE_DOMAIN;
+
+#[test]
+fn test_derive_key_image() {
+    let k = 13;
+
+    let run = |sk: Fr, pk: G1Affine, expected: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let sk_bits = ec_chip.decompose_scalar(region, config, &sk, offset)?;
+            let pk = ec_chip.load_private_point(region, config, &pk, offset)?;
+            let key_image = ec_chip.derive_key_image(region, config, &sk_bits, &pk, offset)?;
+
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(key_image.x.cell(), expected.x.cell())?;
+            region.constrain_equal(key_image.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    // H(PK) over the serialization x + tag + sign * 2^128
+    let hash = |pk: G1Affine| {
+        let two_to_128 = Fq::from_u128(1 << 127).double();
+        let sign = Fq::from(bool::from(pk.y.is_odd()) as u64);
+        try_and_increment(pk.x + Fq::from_u128(KEY_IMAGE_DOMAIN) + sign * two_to_128).1
+    };
+
+    let mut rng = test_rng();
+    // a PK with an even y, whose serialization has no sign term
+    let (sk, pk) = loop {
+        let sk = Fr::random(&mut rng);
+        let pk = (G1Affine::generator() * sk).to_affine();
+        if bool::from(pk.y.is_even()) {
+            break (sk, pk);
+        }
+    };
+    let h = hash(pk);
+
+    // H(PK) is not +/- PK, whose discrete log is known to the prover
+    assert_ne!(h, pk);
+    assert_ne!(h, -pk);
+    let key_image = (h * sk).to_affine();
+
+    run(sk, pk, key_image).assert_satisfied();
+
+    // -PK has its own H(-PK), not H(PK)
+    {
+        let neg_h = hash(-pk);
+        assert_ne!(neg_h, h);
+        assert_ne!(neg_h, -h);
+        run(-sk, -pk, (neg_h * -sk).to_affine()).assert_satisfied();
+
+        // error case: the key image of -PK over H(PK), i.e., -KI
+        assert!(run(-sk, -pk, -key_image).verify().is_err());
+    }
+
+    // error case: the key image is computed with a different secret key than pk
+    {
+        let sk_other = Fr::random(&mut rng);
+        assert!(run(sk_other, pk, (h * sk_other).to_affine())
+            .verify()
+            .is_err());
+    }
+}
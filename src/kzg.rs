@@ -0,0 +1,79 @@
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+
+use crate::ArithOps;
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// The KZG gadgets of the chip, on top of `NativeECOps`.
+pub trait KzgOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Input a KZG commitment C and a claimed evaluation v,
+    /// Output C - v * G, the G1 point of the opening check
+    /// e(C - v * G, H) = e(W, (tau - z) * H), for a constant base G.
+    ///
+    /// The product is computed as v * (-G), with -G fixed to a constant, so no
+    /// negation is needed before the add. The cell v is decomposed canonically,
+    /// which is a valid scalar as the circuit field is smaller than the scalar field.
+    ///
+    /// Caller must check C is on curve, v != 0 and C != v * G.
+    /// Returns a synthesis error if G is the identity.
+    fn kzg_ec_part_verify(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        commitment: &AssignedECPoint<C, F>,
+        v: &AssignedCell<F, F>,
+        g: &C,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error>;
+}
+
+impl<C, F> KzgOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Input a KZG commitment C and a claimed evaluation v,
+    /// Output C - v * G, the G1 point of the opening check
+    /// e(C - v * G, H) = e(W, (tau - z) * H), for a constant base G.
+    ///
+    /// The product is computed as v * (-G), with -G fixed to a constant, so no
+    /// negation is needed before the add. The cell v is decomposed canonically,
+    /// which is a valid scalar as the circuit field is smaller than the scalar field.
+    ///
+    /// Caller must check C is on curve, v != 0 and C != v * G.
+    /// Returns a synthesis error if G is the identity.
+    fn kzg_ec_part_verify(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        commitment: &AssignedECPoint<C, F>,
+        v: &AssignedCell<F, F>,
+        g: &C,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        let v_bits = self.decompose_field(region, config, v, offset)?;
+
+        // -G is fixed to a constant
+        let neg_g = -*g;
+        let neg_g_coordinates = Option::<_>::from(neg_g.coordinates()).ok_or(Error::Synthesis)?;
+        let neg_g_assigned = self.load_private_point_unchecked(region, config, &neg_g, offset)?;
+        region.constrain_constant(neg_g_assigned.x.cell(), *neg_g_coordinates.x())?;
+        region.constrain_constant(neg_g_assigned.y.cell(), *neg_g_coordinates.y())?;
+
+        let neg_v_g = self.point_mul_with_bits(region, config, &neg_g_assigned, &v_bits, offset)?;
+        self.point_add(region, config, commitment, &neg_v_g, offset)
+    }
+}
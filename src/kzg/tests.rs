@@ -0,0 +1,92 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::plonk::Error;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::mock_run;
+use crate::test_circuit::overwrite_point;
+use crate::ArithOps;
+use crate::KzgOps;
+use crate::NativeECOps;
+
+#[test]
+fn test_kzg_ec_part_verify() {
+    let k = 13;
+
+    let mut rng = test_rng();
+    let g = G1::random(&mut rng).to_affine();
+    let commitment = G1::random(&mut rng).to_affine();
+    let v = Fq::random(&mut rng);
+    // the circuit field is smaller than the scalar field
+    let v_scalar = Fr::from_repr(v.to_repr()).unwrap();
+    let expected = (commitment - g * v_scalar).to_affine();
+
+    // seed: overwrites the seed of the double-then-add loop of v * (-G), at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run_with_seed = |v: Fq, seed: Option<G1Affine>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let commitment = ec_chip.load_private_point(region, config, &commitment, offset)?;
+            let v = ec_chip.load_private_field(region, config, &v, offset)?;
+
+            // the bits of v and -G come before the loop
+            let rows = ec_chip.dry_run_offset(|chip, region, config, offset| {
+                chip.decompose_field(region, config, &v, offset)?;
+                chip.load_private_point_unchecked(region, config, &-g, offset)
+            })?;
+            seed_row.set(*offset + rows);
+
+            let res = ec_chip.kzg_ec_part_verify(region, config, &commitment, &v, &g, offset)?;
+
+            if let Some(q) = seed {
+                overwrite_point(region, config, seed_row.get(), &q)?;
+            }
+
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+        .unwrap()
+    };
+    let run = |v: Fq| run_with_seed(v, None);
+
+    run(v).assert_satisfied();
+
+    // error case: the claimed evaluation is wrong
+    assert!(run(v + Fq::one()).verify().is_err());
+
+    // error case: a wrong evaluation v' and a seed Q that makes
+    // 2^256 * (Q - G) - v' * G = -v * G; the seed is a constant
+    {
+        let v_prime = v + Fq::one();
+        let v_prime_scalar = Fr::from_repr(v_prime.to_repr()).unwrap();
+
+        let gen = G1Affine::generator();
+        let two_to_256_inv = Fr::from(2).pow_vartime([256]).invert().unwrap();
+        let seed = (gen + g * ((v_prime_scalar - v_scalar) * two_to_256_inv)).to_affine();
+
+        let failures = run_with_seed(v_prime, Some(seed)).verify().unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+
+    // error case: G is the identity
+    {
+        let identity = G1::identity().to_affine();
+        let res = mock_run(k, |ec_chip, region, config, offset| {
+            let commitment = ec_chip.load_private_point(region, config, &commitment, offset)?;
+            let v = ec_chip.load_private_field(region, config, &v, offset)?;
+            ec_chip.kzg_ec_part_verify(region, config, &commitment, &v, &identity, offset)?;
+
+            Ok(())
+        });
+        assert!(matches!(res, Err(Error::Synthesis)));
+    }
+}
@@ -1,12 +1,89 @@
+// the path of `#[debug_verify_synthesis]` in the tests of the crate
+#[cfg(test)]
+extern crate self as halo2_native_ecc;
+
+mod accumulator;
 mod arith_gates;
 mod chip;
 mod config;
+#[cfg(feature = "debug_verify")]
+mod debug;
+mod dleq;
+mod dlog;
+#[cfg(feature = "dump_gates")]
+mod dump;
 mod ec_gates;
 mod ec_structs;
+mod ecies;
+mod error;
+mod key_image;
+mod kzg;
+mod musig2;
+mod pedersen;
+mod ring;
+#[cfg(test)]
+mod test_circuit;
+#[cfg(test)]
+mod test_vectors;
+mod threshold;
 mod util;
 
+pub use accumulator::EcAccumulator;
 pub use arith_gates::ArithOps;
+pub use chip::ChipMeta;
 pub use chip::ECChip;
+pub use chip::RowUtilization;
 pub use config::ECConfig;
+pub use config::DEFAULT_RANGE_WIDTH;
+pub use config::KEY_IMAGE_DOMAIN;
+pub use config::KEY_IMAGE_MAX_ITERATIONS;
+#[cfg(feature = "debug_verify")]
+pub use debug::debug_verify;
+#[cfg(feature = "debug_verify")]
+pub use debug::debug_verify_synthesize;
+#[cfg(feature = "debug_verify")]
+pub use debug_verify_synthesis::debug_verify_synthesis;
+pub use dleq::DleqOps;
+pub use dlog::prove_dlog_fixed_base;
+pub use dlog::DLogOps;
+pub use dlog::DLogProof;
+#[cfg(feature = "dump_gates")]
+pub use dump::GatePoly;
+#[cfg(feature = "dump_gates")]
+pub use dump::Term;
+#[cfg(feature = "dump_gates")]
+pub use dump::Variable;
 pub use ec_gates::NativeECOps;
 pub use ec_structs::AssignedECPoint;
+pub use ec_structs::AssignedJacobianPoint;
+pub use ec_structs::BatchVerifier;
+pub use ec_structs::MsmAccumulator;
+pub use ec_structs::PointBatch;
+pub use ec_structs::PointMulWitness;
+pub use ecies::EciesOps;
+pub use error::EccError;
+pub use key_image::KeyImageOps;
+pub use kzg::KzgOps;
+pub use musig2::MuSig2Ops;
+pub use musig2::MuSig2Signature;
+pub use pedersen::PedersenOpeningCircuit;
+pub use pedersen::PedersenOps;
+pub use ring::sign_ring;
+pub use ring::RingSigOps;
+pub use ring::RingSignature;
+pub use threshold::MultiPartyPoint;
+pub use threshold::ThresholdOps;
+pub use util::native_add;
+pub use util::native_double;
+pub use util::native_mul;
+
+/// The chip for Grumpkin over the BN256 scalar field, as in Nova and halo2 recursion.
+///
+/// The BN256 scalar field is the base field of Grumpkin, i.e.,
+/// `bn256::Fr` and `grumpkin::Fq` are the same type.
+#[cfg(feature = "bn256")]
+pub type Bn256ECChip = ECChip<halo2curves::grumpkin::G1Affine, halo2curves::bn256::Fr>;
+
+/// The config of [`Bn256ECChip`].
+#[cfg(feature = "bn256")]
+pub type Bn256ECConfig = ECConfig<halo2curves::grumpkin::G1Affine, halo2curves::bn256::Fr>;
@@ -0,0 +1,110 @@
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+
+use crate::AssignedECPoint;
+use crate::DLogOps;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::MsmAccumulator;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// An aggregate MuSig2 signature (R, s) with its challenge e, all assigned.
+///
+/// The crate has no transcript chip, so the challenge e = H(X, R, msg) over the
+/// aggregate key X is an input, as 256 little endian bit cells.
+#[derive(Debug, Clone)]
+pub struct MuSig2Signature<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    /// the aggregate nonce R
+    pub r: AssignedECPoint<C, F>,
+    /// the bits of the challenge e, in little endian
+    pub e_bits: Vec<AssignedCell<F, F>>,
+    /// the bits of the aggregate response s, in little endian
+    pub s_bits: Vec<AssignedCell<F, F>>,
+}
+
+/// The MuSig2 gadgets of the chip, on top of `NativeECOps`.
+pub trait MuSig2Ops<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Verifies an aggregate MuSig2 signature (R, s) with challenge e over the
+    /// signers' keys pk_i and their key aggregation coefficients a_i:
+    /// s * G = R + e * X, where X = sum_i a_i * pk_i.
+    ///
+    /// The aggregate key X is an MSM over the keys sharing the doublings, and the
+    /// Schnorr equation is checked with `verify_dlog_proof`.
+    /// Like the challenge, the coefficients a_i = H(L, pk_i) are inputs.
+    ///
+    /// Caller must check R and the keys are on curve and the bits are binary.
+    /// Returns a synthesis error if there is no key, the numbers of keys and
+    /// coefficients do not match, or any scalar is not 256 bits.
+    fn musig2_verify(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        sig: &MuSig2Signature<C, F>,
+        keys: &[AssignedECPoint<C, F>],
+        coeff_bits: &[Vec<AssignedCell<F, F>>],
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+}
+
+impl<C, F> MuSig2Ops<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Verifies an aggregate MuSig2 signature (R, s) with challenge e over the
+    /// signers' keys pk_i and their key aggregation coefficients a_i:
+    /// s * G = R + e * X, where X = sum_i a_i * pk_i.
+    ///
+    /// The aggregate key X is an MSM over the keys sharing the doublings, and the
+    /// Schnorr equation is checked with `verify_dlog_proof`.
+    /// Like the challenge, the coefficients a_i = H(L, pk_i) are inputs.
+    ///
+    /// Caller must check R and the keys are on curve and the bits are binary.
+    /// Returns a synthesis error if there is no key, the numbers of keys and
+    /// coefficients do not match, or any scalar is not 256 bits.
+    fn musig2_verify(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        sig: &MuSig2Signature<C, F>,
+        keys: &[AssignedECPoint<C, F>],
+        coeff_bits: &[Vec<AssignedCell<F, F>>],
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        if keys.is_empty() || keys.len() != coeff_bits.len() {
+            return Err(Error::Synthesis);
+        }
+
+        // X = sum_i a_i * pk_i
+        let mut msm = MsmAccumulator::new();
+        for (pk, a_bits) in keys.iter().zip(coeff_bits.iter()) {
+            msm.push(pk, a_bits)?;
+        }
+        let agg_key = msm.finalize(self, region, config, offset)?;
+
+        self.verify_dlog_proof(
+            region,
+            config,
+            &sig.r,
+            &agg_key,
+            &sig.e_bits,
+            &sig.s_bits,
+            offset,
+        )
+    }
+}
@@ -0,0 +1,105 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::plonk::Error;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::forged_seed;
+use crate::test_circuit::mock_run;
+use crate::test_circuit::overwrite_point;
+use crate::MuSig2Ops;
+use crate::MuSig2Signature;
+use crate::NativeECOps;
+
+#[test]
+fn test_musig2_verify() {
+    let k = 14;
+
+    // seed: overwrites the seed of the double-then-add loop of X, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run_with_seed = |r: G1Affine,
+                         keys: &[G1Affine],
+                         coefficients: &[Fr],
+                         e: Fr,
+                         s: Fr,
+                         seed: Option<G1Affine>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let e_bits = ec_chip.decompose_scalar(region, config, &e, offset)?;
+            let s_bits = ec_chip.decompose_scalar(region, config, &s, offset)?;
+            let r = ec_chip.load_private_point(region, config, &r, offset)?;
+            let keys = ec_chip.load_private_points(region, config, keys, offset)?;
+            let coeff_bits = coefficients
+                .iter()
+                .map(|a| ec_chip.decompose_scalar(region, config, a, offset))
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            // the aggregation of X comes first
+            seed_row.set(*offset);
+            let sig = MuSig2Signature { r, e_bits, s_bits };
+            ec_chip.musig2_verify(region, config, &sig, &keys, &coeff_bits, offset)?;
+
+            if let Some(q) = seed {
+                overwrite_point(region, config, seed_row.get(), &q)?;
+            }
+
+            Ok(())
+        })
+        .unwrap()
+    };
+    let run = |r, keys: &[G1Affine], coefficients: &[Fr], e, s| {
+        run_with_seed(r, keys, coefficients, e, s, None)
+    };
+
+    let mut rng = test_rng();
+    let n = 2;
+    let sks = (0..n).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+    let keys = sks
+        .iter()
+        .map(|sk| (G1Affine::generator() * sk).to_affine())
+        .collect::<Vec<_>>();
+    // key aggregation coefficients; stand in for the hash of (L, pk_i)
+    let coefficients = (0..n).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+    // aggregate nonce
+    let nonce = Fr::random(&mut rng);
+    let r = (G1Affine::generator() * nonce).to_affine();
+    // challenge; stands in for the hash of (X, R, msg)
+    let e = Fr::random(&mut rng);
+    // the sum of the partial responses s_i = k_i + e * a_i * sk_i
+    let s = sks
+        .iter()
+        .zip(coefficients.iter())
+        .fold(nonce, |s, (sk, a)| s + e * a * sk);
+
+    run(r, &keys, &coefficients, e, s).assert_satisfied();
+
+    // error case: the response does not match the challenge
+    assert!(run(r, &keys, &coefficients, e, s + Fr::one())
+        .verify()
+        .is_err());
+
+    // error case: the keys are aggregated with swapped coefficients
+    assert!(run(r, &keys, &[coefficients[1], coefficients[0]], e, s)
+        .verify()
+        .is_err());
+
+    // error case: a free response s, and a seed Q that makes the aggregate key
+    // X' = (s * G - R) / e; the seed is a constant
+    {
+        let s = Fr::random(&mut rng);
+        let agg_key = keys
+            .iter()
+            .zip(coefficients.iter())
+            .fold(G1::identity(), |acc, (pk, a)| acc + *pk * a);
+        let forged_key = (G1Affine::generator() * s - r) * e.invert().unwrap();
+        let seed = forged_seed(forged_key - agg_key);
+
+        let failures = run_with_seed(r, &keys, &coefficients, e, s, Some(seed))
+            .verify()
+            .unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+}
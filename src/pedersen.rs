@@ -0,0 +1,233 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Error;
+
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// A circuit that proves knowledge of an opening (v, r) of a public
+/// Pedersen commitment v * G + r * H, where G is the generator.
+///
+/// The public inputs are the coordinates (x, y) of the commitment.
+/// H is a constant of the circuit; it must not be the identity, and its
+/// discrete log to G must be unknown.
+#[derive(Clone, Debug)]
+pub struct PedersenOpeningCircuit<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// the committed value
+    pub v: C::ScalarExt,
+    /// the blinding factor
+    pub r: C::ScalarExt,
+    /// the second base
+    pub h: C,
+    _phantom: PhantomData<F>,
+}
+
+impl<C, F> PedersenOpeningCircuit<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    pub fn new(v: C::ScalarExt, r: C::ScalarExt, h: C) -> Self {
+        Self {
+            v,
+            r,
+            h,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The commitment v * G + r * H, computed natively.
+    pub fn commitment(&self) -> C {
+        (C::generator() * self.v + self.h * self.r).to_affine()
+    }
+
+    /// The public inputs of the circuit, i.e., the coordinates of the commitment.
+    pub fn instances(&self) -> Vec<F> {
+        let commitment = self.commitment().coordinates().unwrap();
+        vec![*commitment.x(), *commitment.y()]
+    }
+}
+
+impl<C, F, S> Circuit<F> for PedersenOpeningCircuit<C, F>
+where
+    C: CurveAffine<Base = F, ScalarExt = S>,
+    F: PrimeField<Repr = [u8; 32]>,
+    S: PrimeField<Repr = [u8; 32]>,
+{
+    type Config = ECConfig<C, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(S::ZERO, S::ZERO, self.h)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        ec_config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(ec_config.clone());
+
+        let commitment = layouter.assign_region(
+            || "pedersen opening",
+            |mut region| {
+                let mut offset = 0;
+                let v_bits =
+                    ec_chip.decompose_scalar(&mut region, &ec_config, &self.v, &mut offset)?;
+                let r_bits =
+                    ec_chip.decompose_scalar(&mut region, &ec_config, &self.r, &mut offset)?;
+
+                // H is fixed to a constant
+                let h_coordinates = self.h.coordinates().unwrap();
+                let h = ec_chip.load_private_point_unchecked(
+                    &mut region,
+                    &ec_config,
+                    &self.h,
+                    &mut offset,
+                )?;
+                region.constrain_constant(h.x.cell(), *h_coordinates.x())?;
+                region.constrain_constant(h.y.cell(), *h_coordinates.y())?;
+
+                let commitment = ec_chip.pedersen_commit(
+                    &mut region,
+                    &ec_config,
+                    &v_bits,
+                    &r_bits,
+                    &h,
+                    &mut offset,
+                )?;
+
+                ec_chip.pad(&mut region, &ec_config, &mut offset)?;
+
+                Ok(commitment)
+            },
+        )?;
+
+        // the commitment is public
+        layouter.constrain_instance(commitment.x.cell(), ec_config.instance, 0)?;
+        layouter.constrain_instance(commitment.y.cell(), ec_config.instance, 1)?;
+
+        Ok(())
+    }
+}
+
+/// The Pedersen commitment gadgets of the chip, on top of `NativeECOps`.
+pub trait PedersenOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Return the Pedersen commitment v * G + r * H, for scalars given as
+    /// 256 little endian bit cells, where G is the generator.
+    ///
+    /// G is fixed to the generator, and the two point muls share their doublings.
+    /// Caller must check H is on curve, the discrete log of H to G is unknown,
+    /// and the bits are binary.
+    /// Returns a synthesis error if the number of bits of v or r is not 256.
+    fn pedersen_commit(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        v_bits: &[AssignedCell<F, F>],
+        r_bits: &[AssignedCell<F, F>],
+        h: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error>;
+
+    /// Enforces c_ab == c_a + c_b for three assigned Pedersen commitments,
+    /// i.e., c_ab commits to the sums of the values and of the blinders of c_a and c_b.
+    ///
+    /// The sum is computed with `point_add` and compared to a copy of c_ab
+    /// with `assert_points_equal_gate`.
+    /// Caller must check c_a and c_b are on curve and c_a != +/- c_b.
+    fn assert_commitment_homomorphism(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        c_ab: &AssignedECPoint<C, F>,
+        c_a: &AssignedECPoint<C, F>,
+        c_b: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error>;
+}
+
+impl<C, F> PedersenOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Return the Pedersen commitment v * G + r * H, for scalars given as
+    /// 256 little endian bit cells, where G is the generator.
+    ///
+    /// G is fixed to the generator, and the two point muls share their doublings.
+    /// Caller must check H is on curve, the discrete log of H to G is unknown,
+    /// and the bits are binary.
+    /// Returns a synthesis error if the number of bits of v or r is not 256.
+    fn pedersen_commit(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        v_bits: &[AssignedCell<F, F>],
+        r_bits: &[AssignedCell<F, F>],
+        h: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error> {
+        // the offset generator correction is for 256 doublings
+        if v_bits.len() != 256 || r_bits.len() != 256 {
+            return Err(Error::Synthesis);
+        }
+
+        let gen_assigned = self.load_generator(region, config, offset)?;
+        let res = self.joint_double_then_add(
+            region,
+            config,
+            &[(&gen_assigned, v_bits), (h, r_bits)],
+            offset,
+        )?;
+
+        // now we subtract 2^256 * generator from res
+        self.subtract_offset_generator(region, config, &res, offset)
+    }
+
+    /// Enforces c_ab == c_a + c_b for three assigned Pedersen commitments,
+    /// i.e., c_ab commits to the sums of the values and of the blinders of c_a and c_b.
+    ///
+    /// The sum is computed with `point_add` and compared to a copy of c_ab
+    /// with `assert_points_equal_gate`.
+    /// Caller must check c_a and c_b are on curve and c_a != +/- c_b.
+    fn assert_commitment_homomorphism(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        c_ab: &AssignedECPoint<C, F>,
+        c_a: &AssignedECPoint<C, F>,
+        c_b: &AssignedECPoint<C, F>,
+        offset: &mut usize,
+    ) -> Result<(), Error> {
+        let sum = self.point_add(region, config, c_a, c_b, offset)?;
+        let c_ab = self.copy_point(region, config, c_ab, offset)?;
+        self.assert_points_equal_gate(region, config, &sum, &c_ab, offset)
+    }
+}
@@ -0,0 +1,133 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::plonk::create_proof;
+use halo2_proofs::plonk::keygen_pk;
+use halo2_proofs::plonk::keygen_vk;
+use halo2_proofs::plonk::verify_proof;
+use halo2_proofs::poly::commitment::ParamsProver;
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2_proofs::poly::kzg::multiopen::ProverSHPLONK;
+use halo2_proofs::poly::kzg::multiopen::VerifierSHPLONK;
+use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+use halo2_proofs::transcript::Blake2bRead;
+use halo2_proofs::transcript::Blake2bWrite;
+use halo2_proofs::transcript::Challenge255;
+use halo2_proofs::transcript::TranscriptReadBuffer;
+use halo2_proofs::transcript::TranscriptWriterBuffer;
+use halo2curves::bn256::Bn256;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::test_circuit::mock_run;
+use crate::NativeECOps;
+use crate::PedersenOpeningCircuit;
+use crate::PedersenOps;
+
+#[test]
+fn test_pedersen_opening() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let v = Fr::random(&mut rng);
+    let r = Fr::random(&mut rng);
+    let h = G1::random(&mut rng).to_affine();
+
+    let circuit = PedersenOpeningCircuit::<G1Affine, Fq>::new(v, r, h);
+    let instances = circuit.instances();
+
+    {
+        let prover = MockProver::run(k, &circuit, vec![instances.clone()]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: the opening is for a different commitment
+    {
+        let other = PedersenOpeningCircuit::<G1Affine, Fq>::new(v + Fr::one(), r, h);
+        let prover = MockProver::run(k, &other, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[test]
+fn test_pedersen_opening_real_prover() {
+    let k = 12;
+
+    let mut rng = test_rng();
+    let v = Fr::random(&mut rng);
+    let r = Fr::random(&mut rng);
+    let h = G1::random(&mut rng).to_affine();
+
+    // the circuit field of Grumpkin is the scalar field of BN256
+    let circuit = PedersenOpeningCircuit::<G1Affine, Fq>::new(v, r, h);
+    let instances = circuit.instances();
+
+    let params = ParamsKZG::<Bn256>::setup(k, &mut rng);
+    let vk = keygen_vk(&params, &circuit).unwrap();
+    let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        &params,
+        &pk,
+        &[circuit],
+        &[&[&instances]],
+        &mut rng,
+        &mut transcript,
+    )
+    .unwrap();
+    let proof = transcript.finalize();
+
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+    let strategy = SingleStrategy::new(&params);
+    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+        params.verifier_params(),
+        pk.get_vk(),
+        strategy,
+        &[&[&instances]],
+        &mut transcript,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_assert_commitment_homomorphism() {
+    let k = 6;
+
+    let run = |c_ab: G1Affine, c_a: G1Affine, c_b: G1Affine| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let c_ab = ec_chip.load_private_point(region, config, &c_ab, offset)?;
+            let c_a = ec_chip.load_private_point(region, config, &c_a, offset)?;
+            let c_b = ec_chip.load_private_point(region, config, &c_b, offset)?;
+            ec_chip.assert_commitment_homomorphism(region, config, &c_ab, &c_a, &c_b, offset)
+        })
+        .unwrap()
+    };
+
+    let mut rng = test_rng();
+    let g = G1Affine::generator();
+    let h = G1::random(&mut rng).to_affine();
+    let commit = |v: Fr, r: Fr| (g * v + h * r).to_affine();
+
+    let a = Fr::random(&mut rng);
+    let b = Fr::random(&mut rng);
+    let r_a = Fr::random(&mut rng);
+    let r_b = Fr::random(&mut rng);
+    let c_a = commit(a, r_a);
+    let c_b = commit(b, r_b);
+
+    run(commit(a + b, r_a + r_b), c_a, c_b).assert_satisfied();
+
+    // error case: c_ab commits to a different value
+    assert!(run(commit(a + b + Fr::one(), r_a + r_b), c_a, c_b)
+        .verify()
+        .is_err());
+
+    // error case: c_ab uses a different blinder
+    assert!(run(commit(a + b, r_a), c_a, c_b).verify().is_err());
+}
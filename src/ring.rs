@@ -0,0 +1,238 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::FromUniformBytes;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+use halo2_proofs::transcript::Blake2bWrite;
+use halo2_proofs::transcript::Challenge255;
+use halo2_proofs::transcript::Transcript;
+
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::MsmAccumulator;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// A Schnorr ring signature over public keys pk_0, ..., pk_{n-1}, in the
+/// style of Abe-Ohkubo-Suzuki; made by `sign_ring`.
+///
+/// For each member i, R_i = s_i * G + c_i * pk_i, and the challenges form a
+/// ring: c_{i+1 mod n} = H(msg, R_i), where G is the generator.
+#[derive(Debug, Clone)]
+pub struct RingSignature<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    /// the challenges c_0, ..., c_{n-1}
+    pub challenges: Vec<C::ScalarExt>,
+    /// the responses s_0, ..., s_{n-1}
+    pub responses: Vec<C::ScalarExt>,
+    _phantom: PhantomData<F>,
+}
+
+impl<C, F> RingSignature<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    pub fn new(challenges: Vec<C::ScalarExt>, responses: Vec<C::ScalarExt>) -> Self {
+        Self {
+            challenges,
+            responses,
+            _phantom: PhantomData::default(),
+        }
+    }
+
+    /// The number of ring members.
+    pub fn len(&self) -> usize {
+        self.challenges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.challenges.is_empty()
+    }
+
+    /// The commitments R_i = s_i * G + c_i * pk_i.
+    ///
+    /// Panics if the numbers of keys, challenges and responses do not match.
+    pub fn commitments(&self, pks: &[C]) -> Vec<C> {
+        assert_eq!(pks.len(), self.challenges.len());
+        assert_eq!(pks.len(), self.responses.len());
+        pks.iter()
+            .zip(self.challenges.iter().zip(self.responses.iter()))
+            .map(|(pk, (c, s))| (C::generator() * s + *pk * c).to_affine())
+            .collect()
+    }
+}
+
+impl<C, F> RingSignature<C, F>
+where
+    C: CurveAffine<Base = F>,
+    C::ScalarExt: FromUniformBytes<64>,
+    F: Field,
+{
+    /// The challenge H(msg, R), squeezed from a Blake2b transcript.
+    pub fn challenge(msg: &C::ScalarExt, r: &C) -> C::ScalarExt {
+        let mut transcript = Blake2bWrite::<_, C, Challenge255<C>>::init(vec![]);
+        transcript.common_scalar(*msg).unwrap();
+        transcript.common_point(*r).unwrap();
+        *transcript.squeeze_challenge_scalar::<()>()
+    }
+
+    /// Verify the signature natively: c_{i+1 mod n} = H(msg, R_i) for every i.
+    pub fn verify(&self, pks: &[C], msg: &C::ScalarExt) -> bool {
+        let n = pks.len();
+        if n == 0 || self.challenges.len() != n || self.responses.len() != n {
+            return false;
+        }
+        self.commitments(pks)
+            .iter()
+            .enumerate()
+            .all(|(i, r)| self.challenges[(i + 1) % n] == Self::challenge(msg, r))
+    }
+}
+
+/// Sign msg with the ring signature of `RingSignature`, as the member at
+/// `index` of the ring, whose secret key is sk.
+///
+/// The nonce and the responses of the other members are squeezed from a
+/// Blake2b transcript of sk, msg and the keys.
+/// Panics if index is out of the ring.
+pub fn sign_ring<C, F>(
+    sk: C::ScalarExt,
+    index: usize,
+    pks: &[C],
+    msg: &C::ScalarExt,
+) -> RingSignature<C, F>
+where
+    C: CurveAffine<Base = F>,
+    C::ScalarExt: FromUniformBytes<64>,
+    F: Field,
+{
+    let n = pks.len();
+    assert!(index < n);
+    let g = C::generator();
+
+    let mut transcript = Blake2bWrite::<_, C, Challenge255<C>>::init(vec![]);
+    transcript.common_scalar(sk).unwrap();
+    transcript.common_scalar(*msg).unwrap();
+    for pk in pks {
+        transcript.common_point(*pk).unwrap();
+    }
+    let k = *transcript.squeeze_challenge_scalar::<()>();
+
+    // walk the ring from the signer, starting with R_index = k * G
+    let mut challenges = vec![C::ScalarExt::ZERO; n];
+    let mut responses = vec![C::ScalarExt::ZERO; n];
+    let mut r = (g * k).to_affine();
+    for j in 1..n {
+        let i = (index + j) % n;
+        challenges[i] = RingSignature::<C, F>::challenge(msg, &r);
+        responses[i] = *transcript.squeeze_challenge_scalar::<()>();
+        r = (g * responses[i] + pks[i] * challenges[i]).to_affine();
+    }
+
+    // close the ring: s_index * G + c_index * pk_index = k * G
+    challenges[index] = RingSignature::<C, F>::challenge(msg, &r);
+    responses[index] = k - challenges[index] * sk;
+    RingSignature::new(challenges, responses)
+}
+
+/// The ring signature gadgets of the chip, on top of `NativeECOps`.
+pub trait RingSigOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Verifies a Schnorr ring signature over the keys pk_0, ..., pk_{n-1},
+    /// as made by `sign_ring`: computes R_i = s_i * G + c_i * pk_i for each
+    /// member, with G fixed to the generator. The two point muls of a member
+    /// share their doublings, so the cost grows linearly with n.
+    ///
+    /// As in `verify_dlog_fixed_base`, the circuit has no hash, so the challenges
+    /// of the signature are witnesses; returns the commitments R_i and the bits
+    /// of the challenges c_i, for the caller to bind the ring
+    /// c_{i+1 mod n} = H(msg, R_i), e.g., by exposing them and checking
+    /// `RingSignature::challenge` outside the circuit.
+    ///
+    /// Caller must check the keys are on curve.
+    /// Returns a synthesis error if there is no key, or the numbers of keys,
+    /// challenges and responses do not match.
+    fn ring_sig_verify<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        pks: &[AssignedECPoint<C, F>],
+        sig: &RingSignature<C, F>,
+        offset: &mut usize,
+    ) -> Result<(Vec<AssignedECPoint<C, F>>, Vec<Vec<AssignedCell<F, F>>>), Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
+}
+
+impl<C, F> RingSigOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Verifies a Schnorr ring signature over the keys pk_0, ..., pk_{n-1},
+    /// as made by `sign_ring`: computes R_i = s_i * G + c_i * pk_i for each
+    /// member, with G fixed to the generator. The two point muls of a member
+    /// share their doublings, so the cost grows linearly with n.
+    ///
+    /// As in `verify_dlog_fixed_base`, the circuit has no hash, so the challenges
+    /// of the signature are witnesses; returns the commitments R_i and the bits
+    /// of the challenges c_i, for the caller to bind the ring
+    /// c_{i+1 mod n} = H(msg, R_i), e.g., by exposing them and checking
+    /// `RingSignature::challenge` outside the circuit.
+    ///
+    /// Caller must check the keys are on curve.
+    /// Returns a synthesis error if there is no key, or the numbers of keys,
+    /// challenges and responses do not match.
+    fn ring_sig_verify<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        pks: &[AssignedECPoint<C, F>],
+        sig: &RingSignature<C, F>,
+        offset: &mut usize,
+    ) -> Result<(Vec<AssignedECPoint<C, F>>, Vec<Vec<AssignedCell<F, F>>>), Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        if pks.is_empty() || pks.len() != sig.challenges.len() || pks.len() != sig.responses.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let g = self.load_generator(region, config, offset)?;
+        let mut commitments = vec![];
+        let mut challenge_bits = vec![];
+        for (pk, (c, s)) in pks
+            .iter()
+            .zip(sig.challenges.iter().zip(sig.responses.iter()))
+        {
+            let c_bits = self.decompose_scalar(region, config, c, offset)?;
+            let s_bits = self.decompose_scalar(region, config, s, offset)?;
+
+            // R_i = s_i * G + c_i * pk_i
+            let mut msm = MsmAccumulator::new();
+            msm.push(&g, &s_bits)?;
+            msm.push(pk, &c_bits)?;
+            commitments.push(msm.finalize(self, region, config, offset)?);
+            challenge_bits.push(c_bits);
+        }
+
+        Ok((commitments, challenge_bits))
+    }
+}
@@ -0,0 +1,134 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+
+use crate::sign_ring;
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::forged_seed;
+use crate::test_circuit::mock_run_with_instance;
+use crate::test_circuit::overwrite_point;
+use crate::util::field_decompose_u128;
+use crate::NativeECOps;
+use crate::RingSigOps;
+use crate::RingSignature;
+
+// the public inputs with the challenges recomputed from the commitments
+fn ring_sig_instance(msg: &Fr, commitments: &[G1Affine]) -> Vec<Fq> {
+    let n = commitments.len();
+    let mut instance = vec![];
+    for r in commitments {
+        instance.push(r.x);
+        instance.push(r.y);
+    }
+    for i in 0..n {
+        let c = RingSignature::<G1Affine, Fq>::challenge(msg, &commitments[(i + n - 1) % n]);
+        let (high, low) = field_decompose_u128(&c);
+        instance.push(Fq::from_u128(low));
+        instance.push(Fq::from_u128(high));
+    }
+    instance
+}
+
+#[test]
+fn test_ring_sig_verify() {
+    let k = 14;
+    let n = 4;
+
+    let mut rng = test_rng();
+    let sks = (0..n).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+    let pks = sks
+        .iter()
+        .map(|sk| (G1Affine::generator() * sk).to_affine())
+        .collect::<Vec<_>>();
+    let msg = Fr::random(&mut rng);
+
+    // R_i and c_i are public, so that c_{i+1} = H(msg, R_i) is checked by the verifier
+    // seed: overwrites the seed of the double-then-add loop of R_0, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run_with_seed = |sig: &RingSignature<G1Affine, Fq>,
+                         msg: &Fr,
+                         commitments: &[G1Affine],
+                         seed: Option<G1Affine>| {
+        let instance = ring_sig_instance(msg, commitments);
+        mock_run_with_instance(k, instance, |ec_chip, region, config, offset| {
+            let pks = ec_chip.load_private_points(region, config, &pks, offset)?;
+
+            // G, c_0 and s_0 come before the loop
+            let rows = ec_chip.dry_run_offset(|chip, region, config, offset| {
+                chip.load_private_point_unchecked(region, config, &G1Affine::generator(), offset)?;
+                chip.decompose_scalar(region, config, &sig.challenges[0], offset)?;
+                chip.decompose_scalar(region, config, &sig.responses[0], offset)
+            })?;
+            seed_row.set(*offset + rows);
+
+            let (commitments, challenge_bits) =
+                ec_chip.ring_sig_verify(region, config, &pks, sig, offset)?;
+            if let Some(q) = seed {
+                overwrite_point(region, config, seed_row.get(), &q)?;
+            }
+
+            let mut public = vec![];
+            for r in commitments {
+                public.push(r.x);
+                public.push(r.y);
+            }
+            for bits in challenge_bits.iter() {
+                let c = ec_chip.compose_u128_pair(region, config, bits, offset)?;
+                public.push(c.0);
+                public.push(c.1);
+            }
+
+            Ok(public)
+        })
+        .unwrap()
+    };
+    let run = |sig: &RingSignature<G1Affine, Fq>, msg: &Fr| {
+        run_with_seed(sig, msg, &sig.commitments(&pks), None)
+    };
+
+    let sig = sign_ring(sks[2], 2, &pks, &msg);
+    assert!(sig.verify(&pks, &msg));
+
+    run(&sig, &msg).assert_satisfied();
+
+    // error case: a tampered response, or another message
+    {
+        let mut responses = sig.responses.clone();
+        responses[1] += Fr::one();
+        let tampered = RingSignature::new(sig.challenges.clone(), responses);
+        assert!(!tampered.verify(&pks, &msg));
+        assert!(run(&tampered, &msg).verify().is_err());
+    }
+    {
+        let other = Fr::random(&mut rng);
+        assert!(!sig.verify(&pks, &other));
+        assert!(run(&sig, &other).verify().is_err());
+    }
+
+    // error case: a signer out of the ring, in the place of member 2
+    {
+        let forged = sign_ring(Fr::random(&mut rng), 2, &pks, &msg);
+        assert!(!forged.verify(&pks, &msg));
+        assert!(run(&forged, &msg).verify().is_err());
+    }
+
+    // error case: a signer out of the ring, in the place of member 0, and a
+    // seed Q that shifts R_0 to the commitment that closes the ring; the seed
+    // is a constant
+    {
+        let sk = Fr::random(&mut rng);
+        let forged = sign_ring(sk, 0, &pks, &msg);
+        let mut ring = pks.clone();
+        ring[0] = (G1Affine::generator() * sk).to_affine();
+        let seed = forged_seed((ring[0] - pks[0]) * forged.challenges[0]);
+
+        let failures = run_with_seed(&forged, &msg, &forged.commitments(&ring), Some(seed))
+            .verify()
+            .unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+}
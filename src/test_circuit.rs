@@ -0,0 +1,233 @@
+use ark_std::test_rng;
+use halo2_proofs::circuit::AssignedCell;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::circuit::Value;
+use halo2_proofs::dev::FailureLocation;
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::dev::VerifyFailure;
+use halo2_proofs::halo2curves::ff::Field;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::plonk::create_proof;
+use halo2_proofs::plonk::keygen_pk;
+use halo2_proofs::plonk::keygen_vk;
+use halo2_proofs::plonk::verify_proof;
+use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Error;
+use halo2_proofs::poly::commitment::ParamsProver;
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2_proofs::poly::kzg::multiopen::ProverSHPLONK;
+use halo2_proofs::poly::kzg::multiopen::VerifierSHPLONK;
+use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+use halo2_proofs::transcript::Blake2bRead;
+use halo2_proofs::transcript::Blake2bWrite;
+use halo2_proofs::transcript::Challenge255;
+use halo2_proofs::transcript::TranscriptReadBuffer;
+use halo2_proofs::transcript::TranscriptWriterBuffer;
+use halo2curves::bn256::Bn256;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::ECChip;
+use crate::ECConfig;
+use crate::NativeECOps;
+
+/// A test circuit of the chip over Grumpkin, driven by a closure.
+///
+/// `synthesize` assigns a single region from offset 0, which is padded afterwards.
+/// The cells it returns are constrained to the instance column, in order.
+#[derive(Clone)]
+pub(crate) struct TestCircuit<S> {
+    synthesize: S,
+}
+
+impl<S> Circuit<Fq> for TestCircuit<S>
+where
+    S: Fn(
+            &ECChip<G1Affine, Fq>,
+            &mut Region<'_, Fq>,
+            &ECConfig<G1Affine, Fq>,
+            &mut usize,
+        ) -> Result<Vec<AssignedCell<Fq, Fq>>, Error>
+        + Clone,
+{
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        let public = layouter.assign_region(
+            || "test circuit",
+            |mut region| {
+                let mut offset = 0;
+                let public = (self.synthesize)(&ec_chip, &mut region, &config, &mut offset)?;
+
+                // pad the last two rows
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(public)
+            },
+        )?;
+
+        for (i, cell) in public.iter().enumerate() {
+            layouter.constrain_instance(cell.cell(), config.instance, i)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Run the mock prover on a `TestCircuit` without public inputs.
+///
+/// Returns the synthesis error of the circuit, if any.
+pub(crate) fn mock_run<S>(k: u32, synthesize: S) -> Result<MockProver<Fq>, Error>
+where
+    S: Fn(
+            &ECChip<G1Affine, Fq>,
+            &mut Region<'_, Fq>,
+            &ECConfig<G1Affine, Fq>,
+            &mut usize,
+        ) -> Result<(), Error>
+        + Clone,
+{
+    mock_run_with_instance(k, vec![], move |ec_chip, region, config, offset| {
+        synthesize(ec_chip, region, config, offset).map(|()| vec![])
+    })
+}
+
+/// Run the mock prover on a `TestCircuit` whose returned cells equal `instance`.
+///
+/// Returns the synthesis error of the circuit, if any.
+pub(crate) fn mock_run_with_instance<S>(
+    k: u32,
+    instance: Vec<Fq>,
+    synthesize: S,
+) -> Result<MockProver<Fq>, Error>
+where
+    S: Fn(
+            &ECChip<G1Affine, Fq>,
+            &mut Region<'_, Fq>,
+            &ECConfig<G1Affine, Fq>,
+            &mut usize,
+        ) -> Result<Vec<AssignedCell<Fq, Fq>>, Error>
+        + Clone,
+{
+    MockProver::run(k, &TestCircuit { synthesize }, vec![instance])
+}
+
+/// Generate the keys of the `TestCircuit` of `keygen`, create a real proof of
+/// the `TestCircuit` of `prove` for `instance`, and return whether it verifies.
+///
+/// Unlike the mock prover, the keys are generated without the witness, and the
+/// proof is only checked against the copy constraints of `keygen`.
+pub(crate) fn real_prover_verifies<K, P>(k: u32, instance: Vec<Fq>, keygen: K, prove: P) -> bool
+where
+    K: Fn(
+            &ECChip<G1Affine, Fq>,
+            &mut Region<'_, Fq>,
+            &ECConfig<G1Affine, Fq>,
+            &mut usize,
+        ) -> Result<Vec<AssignedCell<Fq, Fq>>, Error>
+        + Clone,
+    P: Fn(
+            &ECChip<G1Affine, Fq>,
+            &mut Region<'_, Fq>,
+            &ECConfig<G1Affine, Fq>,
+            &mut usize,
+        ) -> Result<Vec<AssignedCell<Fq, Fq>>, Error>
+        + Clone,
+{
+    let mut rng = test_rng();
+
+    // the circuit field of Grumpkin is the scalar field of BN256
+    let params = ParamsKZG::<Bn256>::setup(k, &mut rng);
+    let keygen = TestCircuit { synthesize: keygen };
+    let vk = keygen_vk(&params, &keygen).unwrap();
+    let pk = keygen_pk(&params, vk, &keygen).unwrap();
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        &params,
+        &pk,
+        &[TestCircuit { synthesize: prove }],
+        &[&[&instance]],
+        &mut rng,
+        &mut transcript,
+    )
+    .unwrap();
+    let proof = transcript.finalize();
+
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+    let strategy = SingleStrategy::new(&params);
+    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+        params.verifier_params(),
+        pk.get_vk(),
+        strategy,
+        &[&[&instance]],
+        &mut transcript,
+    )
+    .is_ok()
+}
+
+/// Overwrite the point cells of a row, e.g., the seed of a double-then-add loop.
+pub(crate) fn overwrite_point(
+    region: &mut Region<'_, Fq>,
+    config: &ECConfig<G1Affine, Fq>,
+    row: usize,
+    p: &G1Affine,
+) -> Result<(), Error> {
+    region.assign_advice(|| "x", config.a, row, || Value::known(p.x))?;
+    region.assign_advice(|| "y", config.b, row, || Value::known(p.y))?;
+    Ok(())
+}
+
+/// The seed Q of a double-then-add loop that shifts its result by diff,
+/// i.e., 2^256 * (Q - G) = diff for the generator G.
+pub(crate) fn forged_seed(diff: G1) -> G1Affine {
+    let two_to_256_inv = Fr::from(2).pow_vartime([256]).invert().unwrap();
+    (G1Affine::generator() + diff * two_to_256_inv).to_affine()
+}
+
+/// Whether a copy constraint fails at the row of the region.
+pub(crate) fn copy_fails_at(failures: &[VerifyFailure], row: usize) -> bool {
+    failures.iter().any(|f| {
+        matches!(
+            f,
+            VerifyFailure::Permutation {
+                location: FailureLocation::InRegion { offset, .. },
+                ..
+            } if *offset == row
+        )
+    })
+}
+
+/// The index of the first candidate u + i that is on curve, and the point with an even y.
+pub(crate) fn try_and_increment(u: Fq) -> (usize, G1Affine) {
+    (0..)
+        .find_map(|i| {
+            let x = u + Fq::from(i as u64);
+            let y = Option::<Fq>::from((x.square() * x - Fq::from(17)).sqrt())?;
+            let y = if bool::from(y.is_odd()) { -y } else { y };
+            Some((i, G1Affine { x, y }))
+        })
+        .unwrap()
+}
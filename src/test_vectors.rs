@@ -0,0 +1,290 @@
+//! Fixed test vectors for Grumpkin, generated once with an independent
+//! implementation of the curve arithmetic and checked in as decimal strings.
+//!
+//! Unlike the random cases from `test_rng`, the vectors do not depend on how
+//! halo2curves samples points and scalars. The points are small multiples of
+//! the generator G = (1, sqrt(-16)), noted next to each vector.
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::util::neg_generator_times_2_to_256;
+
+/// The decimal coordinates (x, y) of a point.
+pub(crate) type Point = (&'static str, &'static str);
+
+/// Inputs p1, p2, s and the expected outputs of the ops of `ECTestCircuit`.
+pub(crate) struct EcOpsVector {
+    pub(crate) name: &'static str,
+    pub(crate) s: &'static str,
+    pub(crate) p1: Point,
+    pub(crate) p2: Point,
+    /// p1 + p2
+    pub(crate) add: Point,
+    /// 2 * p1
+    pub(crate) double: Point,
+    /// s * p1
+    pub(crate) mul: Point,
+}
+
+/// A point and its compressed encoding, as of `to_compressed_bytes`,
+/// in hex.
+pub(crate) struct CompressionVector {
+    pub(crate) name: &'static str,
+    pub(crate) p: Point,
+    pub(crate) bytes: &'static str,
+}
+
+pub(crate) const EC_OPS_VECTORS: [EcOpsVector; 5] = [
+    // p1 = G, p2 = 2 * G
+    EcOpsVector {
+        name: "generator",
+        s: "1",
+        p1: (
+            "1",
+            "17631683881184975370165255887551781615748388533673675138860",
+        ),
+        p2: (
+            "3078034153852398078128400807926804309327113743808504829582559963737223069694",
+            "12696890884641142049456609402511852099066095483298083855939691685001536962732",
+        ),
+        add: (
+            "18660890509582237958343981571981920822503400000196279471655180441138020044621",
+            "8902249110305491597038405103722863701255802573786510474664632793109847672620",
+        ),
+        double: (
+            "3078034153852398078128400807926804309327113743808504829582559963737223069694",
+            "12696890884641142049456609402511852099066095483298083855939691685001536962732",
+        ),
+        mul: (
+            "1",
+            "17631683881184975370165255887551781615748388533673675138860",
+        ),
+    },
+    // p1 = 3 * G, p2 = 5 * G
+    EcOpsVector {
+        name: "small multiples",
+        s: "7",
+        p1: (
+            "18660890509582237958343981571981920822503400000196279471655180441138020044621",
+            "8902249110305491597038405103722863701255802573786510474664632793109847672620",
+        ),
+        p2: (
+            "12229279139087521908560794489267966517139449915173592433539394009359081620359",
+            "12096995292699515952722386974733884667125946823386040531322131902193094989869",
+        ),
+        add: (
+            "17008428109567670989314200379993653625463613100747689177956746834041752428857",
+            "21069826815265665839147512552335544122156802746529233879219401506404950060212",
+        ),
+        double: (
+            "7786041651712952323865544323473263410636019205571843419816422718643402458906",
+            "18036943110916577130921083970592721762498477202928970540848920468708869100152",
+        ),
+        mul: (
+            "12198680473762183054642285761968604022123447102803937523643582242848260033989",
+            "10824195256677522695079638286248221638048087606480795164747450390291772886999",
+        ),
+    },
+    // p1 = 0xdeadbeef * G, p2 = G
+    EcOpsVector {
+        name: "scalar is the order minus one",
+        s: "21888242871839275222246405745257275088696311157297823662689037894645226208582",
+        p1: (
+            "19830729731677149605327879633658771229398685872107416297162474183180389211780",
+            "6666860038695965016973607896606584521274238943709739923292579620256406060090",
+        ),
+        p2: (
+            "1",
+            "17631683881184975370165255887551781615748388533673675138860",
+        ),
+        add: (
+            "7396904096516008447375557485035163798580765874787795475059644508401670977067",
+            "12540516111772307001960461612102599027343701700328034457332401704901796739359",
+        ),
+        double: (
+            "19062341389523507977223830722885073828080814791585941493690055123113412879762",
+            "4503140355108238172793215462101671166322836809979408283040088297901615410453",
+        ),
+        mul: (
+            "19830729731677149605327879633658771229398685872107416297162474183180389211780",
+            "15221382833143310205272797848650690567274125456706294420405624566319402435527",
+        ),
+    },
+    // p1 = 0x0123456789abcdef * G, p2 = 0xfedcba9876543210 * G
+    EcOpsVector {
+        name: "scalar of 253 ones",
+        s: "14474011154664524427946373126085988481658748083205070504932198000989141204991",
+        p1: (
+            "636026829078381981206606980217456241058454274427925907282731613348998894244",
+            "4223811190675803291730635004922948542596411615528540031594972544848138195728",
+        ),
+        p2: (
+            "10383489044432155352165205172932841408455049512899476611748570275259189175664",
+            "14268866360877159076695769156077348047272335400618505560713975658646736115315",
+        ),
+        add: (
+            "12474770152605627038848268943824844180911728700467412863567683695457143324905",
+            "4626899900811801488310456053511955944828200741704029398253721755289924889401",
+        ),
+        double: (
+            "14568339588977340060101765473118226111564028721621260633599333678973278087731",
+            "18156621701549945012753931584675231591249359172333309286093655389009620902393",
+        ),
+        mul: (
+            "4992608439646017130054748169341793479087170156219072545612848526921116403250",
+            "14651624223989422286905626962494722653878455086194835717578521866849020991827",
+        ),
+    },
+    // p1 = 12345 * G, p2 = 678 * G
+    EcOpsVector {
+        name: "scalar of a single high bit",
+        s: "14474011154664524427946373126085988481658748083205070504932198000989141204992",
+        p1: (
+            "12983840661618469667233340731503097704297377111146975658191126072435762649408",
+            "12645901681510652395728541071285820781853167265721931988194496645906048137939",
+        ),
+        p2: (
+            "16308465116510580000590930688529681553682543557823756048772493214140746037672",
+            "2229373781383975936774137239419585685809109474284860728489763827087589579856",
+        ),
+        add: (
+            "15952803945221271658410855975145295239816780966000510179390929332837584232482",
+            "11899059169914712534503652509955096060937314483099955528588519920296730161154",
+        ),
+        double: (
+            "10682935694889448900705222258160822808993086371159466620664452837052900499283",
+            "4707507172303194640523410720907202468909081947837434373769938212045725808285",
+        ),
+        mul: (
+            "15599057645961922076630775321929051930744457274454604855531398429657558003772",
+            "3974456735140296125291679172104153977875594264774427029332832059185406483125",
+        ),
+    },
+];
+
+pub(crate) const COMPRESSION_VECTORS: [CompressionVector; 5] = [
+    // y is even
+    CompressionVector {
+        name: "generator",
+        p: (
+            "1",
+            "17631683881184975370165255887551781615748388533673675138860",
+        ),
+        bytes: "0100000000000000000000000000000000000000000000000000000000000000",
+    },
+    // y is odd
+    CompressionVector {
+        name: "negated generator",
+        p: (
+            "1",
+            "21888242871839275204614721864072299718383108512864252727949815652902133356757",
+        ),
+        bytes: "0100000000000000000000000000000000000000000000000000000000000080",
+    },
+    // y is even
+    CompressionVector {
+        name: "2 * generator",
+        p: (
+            "3078034153852398078128400807926804309327113743808504829582559963737223069694",
+            "12696890884641142049456609402511852099066095483298083855939691685001536962732",
+        ),
+        bytes: "feffbfcd88c68b6dd4131e31aa4ca7196d3036aacd49ebdd85faaa27081bce06",
+    },
+    // y is even
+    CompressionVector {
+        name: "0xdeadbeef * generator",
+        p: (
+            "19830729731677149605327879633658771229398685872107416297162474183180389211780",
+            "6666860038695965016973607896606584521274238943709739923292579620256406060090",
+        ),
+        bytes: "845e78e6fc0d229c9a72550751682d069a584832d592f9c92050feee96cbd72b",
+    },
+    // y is odd
+    CompressionVector {
+        name: "12345 * generator",
+        p: (
+            "12983840661618469667233340731503097704297377111146975658191126072435762649408",
+            "12645901681510652395728541071285820781853167265721931988194496645906048137939",
+        ),
+        bytes: "40d975b776e4becc44d396faf03cbbbccd154176c1e1298719858c6ae897b49c",
+    },
+];
+
+/// 2^256 mod the group order.
+pub(crate) const TWO_TO_256: &str =
+    "6350874878119819312338956282401532409788428879151445726012394534686998597021";
+
+/// -(2^256 * G), the offset generator correction of the point muls.
+pub(crate) const NEG_GENERATOR_TIMES_2_TO_256: Point = (
+    "18292374296067206172215749431916515128228165256807037435601971767767562625877",
+    "8411761026004062292626067694055242675827541323706122037355419552115320964415",
+);
+
+pub(crate) fn fq(s: &str) -> Fq {
+    Fq::from_str_vartime(s).unwrap()
+}
+
+pub(crate) fn fr(s: &str) -> Fr {
+    Fr::from_str_vartime(s).unwrap()
+}
+
+/// Panics if the point is not on curve.
+pub(crate) fn point(p: &Point) -> G1Affine {
+    G1Affine::from_xy(fq(p.0), fq(p.1)).unwrap()
+}
+
+pub(crate) fn bytes(s: &str) -> [u8; 32] {
+    let mut res = [0u8; 32];
+    for (i, byte) in res.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+    }
+    res
+}
+
+// each vector against the native arithmetic of halo2curves, one assertion per op
+#[test]
+fn test_native_vectors() {
+    assert_eq!(G1Affine::generator(), point(&EC_OPS_VECTORS[0].p1));
+
+    for v in EC_OPS_VECTORS.iter() {
+        let (p1, p2) = (point(&v.p1), point(&v.p2));
+        assert_eq!((p1 + p2).to_affine(), point(&v.add), "{}: add", v.name);
+        assert_eq!(
+            G1::from(p1).double().to_affine(),
+            point(&v.double),
+            "{}: double",
+            v.name
+        );
+        assert_eq!((p1 * fr(v.s)).to_affine(), point(&v.mul), "{}: mul", v.name);
+    }
+
+    for v in COMPRESSION_VECTORS.iter() {
+        let p = point(&v.p);
+        let mut expected = p.x.to_repr();
+        if bool::from(p.y.is_odd()) {
+            expected[31] |= 0x80;
+        }
+        assert_eq!(expected, bytes(v.bytes), "{}: compression", v.name);
+    }
+
+    let two_to_256 = Fr::from(2).pow_vartime([256]);
+    assert_eq!(two_to_256, fr(TWO_TO_256), "2^256 mod the group order");
+    assert_eq!(
+        neg_generator_times_2_to_256::<G1Affine, Fq>().0,
+        point(&NEG_GENERATOR_TIMES_2_TO_256),
+        "offset generator"
+    );
+    assert_eq!(
+        (-(G1::generator() * two_to_256)).to_affine(),
+        point(&NEG_GENERATOR_TIMES_2_TO_256),
+        "offset generator"
+    );
+}
@@ -0,0 +1,180 @@
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::Region;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Error;
+
+use crate::util::to_le_bits;
+use crate::AssignedECPoint;
+use crate::ECChip;
+use crate::ECConfig;
+use crate::MsmAccumulator;
+use crate::NativeECOps;
+
+#[cfg(test)]
+mod tests;
+
+/// The shares s_i of the parties of an n-of-m threshold scheme, e.g., the
+/// partial multiplications of a threshold ECDSA signature (Gennaro-Goldfeder).
+/// Party i holds the evaluation at i of a secret polynomial of degree n - 1,
+/// for i in [1, m].
+#[derive(Debug, Clone)]
+pub struct MultiPartyPoint<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: Field,
+{
+    /// the threshold n, i.e., the number of shares that recover the secret
+    pub threshold: usize,
+    /// the number of parties m
+    pub num_parties: usize,
+    /// the indices of the parties that contribute a share
+    pub indices: Vec<u64>,
+    /// the shares, in the order of the indices
+    pub shares: Vec<AssignedECPoint<C, F>>,
+}
+
+impl<C, F> MultiPartyPoint<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// The Lagrange coefficients at 0 of the contributing parties:
+    ///  lambda_i = prod_{j != i} j / (j - i)
+    ///
+    /// Returns None if an index is repeated.
+    pub fn lagrange_coefficients(&self) -> Option<Vec<C::ScalarExt>> {
+        let mut indices = self.indices.clone();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.len() != self.indices.len() {
+            return None;
+        }
+
+        let coefficients = self
+            .indices
+            .iter()
+            .map(|&i| {
+                let xi = C::ScalarExt::from(i);
+                self.indices
+                    .iter()
+                    .filter(|&&j| j != i)
+                    .fold(C::ScalarExt::ONE, |acc, &j| {
+                        let xj = C::ScalarExt::from(j);
+                        acc * xj * (xj - xi).invert().unwrap()
+                    })
+            })
+            .collect();
+        Some(coefficients)
+    }
+
+    /// Return the secret times the base of the shares, i.e., sum_i lambda_i * s_i.
+    ///
+    /// Caller must check the shares are on curve, and no partial sum equals
+    /// +/- the next product.
+    /// Returns a synthesis error if there are fewer shares than the threshold,
+    /// the numbers of shares and indices do not match, or an index is repeated
+    /// or not in [1, m].
+    pub fn combine<S>(
+        &self,
+        chip: &ECChip<C, F>,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        if self.shares.len() < self.threshold
+            || self.shares.len() != self.indices.len()
+            || self
+                .indices
+                .iter()
+                .any(|&i| i == 0 || i > self.num_parties as u64)
+        {
+            return Err(Error::Synthesis);
+        }
+        let coefficients = self.lagrange_coefficients().ok_or(Error::Synthesis)?;
+
+        chip.combine_shares(region, config, &self.shares, &coefficients, offset)
+    }
+}
+
+/// The threshold gadgets of the chip, on top of `NativeECOps`.
+pub trait ThresholdOps<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField,
+{
+    /// Input the shares s_i of the parties of a threshold scheme and their
+    /// Lagrange coefficients lambda_i at 0,
+    /// Output the combination sum_i lambda_i * s_i, as in the verification of a
+    /// threshold ECDSA signature (Gennaro-Goldfeder).
+    ///
+    /// The coefficients are public parameters of the signer set, so their bits
+    /// are fixed to constants. They are scalars mod the group order, which is
+    /// larger than the circuit field. The products share the doublings of an MSM.
+    ///
+    /// Caller must check the shares are on curve, and no partial sum equals
+    /// +/- the next product.
+    /// Returns a synthesis error if there is no share, or the numbers of shares
+    /// and coefficients do not match.
+    fn combine_shares<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        shares: &[AssignedECPoint<C, F>],
+        coefficients: &[C::ScalarExt],
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>;
+}
+
+impl<C, F> ThresholdOps<C, F> for ECChip<C, F>
+where
+    C: CurveAffine<Base = F>,
+    F: PrimeField<Repr = [u8; 32]>,
+{
+    /// Input the shares s_i of the parties of a threshold scheme and their
+    /// Lagrange coefficients lambda_i at 0,
+    /// Output the combination sum_i lambda_i * s_i, as in the verification of a
+    /// threshold ECDSA signature (Gennaro-Goldfeder).
+    ///
+    /// The coefficients are public parameters of the signer set, so their bits
+    /// are fixed to constants. They are scalars mod the group order, which is
+    /// larger than the circuit field. The products share the doublings of an MSM.
+    ///
+    /// Caller must check the shares are on curve, and no partial sum equals
+    /// +/- the next product.
+    /// Returns a synthesis error if there is no share, or the numbers of shares
+    /// and coefficients do not match.
+    fn combine_shares<S>(
+        &self,
+        region: &mut Region<F>,
+        config: &ECConfig<C, F>,
+        shares: &[AssignedECPoint<C, F>],
+        coefficients: &[C::ScalarExt],
+        offset: &mut usize,
+    ) -> Result<AssignedECPoint<C, F>, Error>
+    where
+        S: PrimeField<Repr = [u8; 32]>,
+        C: CurveAffine<ScalarExt = S>,
+    {
+        if shares.is_empty() || shares.len() != coefficients.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let mut msm = MsmAccumulator::new();
+        for (share, lambda) in shares.iter().zip(coefficients.iter()) {
+            let bits = self.decompose_scalar(region, config, lambda, offset)?;
+            for (bit, b) in bits.iter().zip(to_le_bits(lambda)) {
+                region.constrain_constant(bit.cell(), F::from(b as u64))?;
+            }
+            msm.push(share, &bits)?;
+        }
+        msm.finalize(self, region, config, offset)
+    }
+}
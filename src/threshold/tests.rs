@@ -0,0 +1,102 @@
+use ark_std::test_rng;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+use crate::test_circuit::copy_fails_at;
+use crate::test_circuit::forged_seed;
+use crate::test_circuit::mock_run;
+use crate::test_circuit::overwrite_point;
+use crate::MultiPartyPoint;
+use crate::NativeECOps;
+
+#[test]
+fn test_combine_shares() {
+    let k = 12;
+
+    // shares: the contributing parties and their shares
+    // seed: overwrites the seed of the double-then-add loop of the MSM, at seed_row
+    let seed_row = std::cell::Cell::new(0);
+    let run_with_seed = |shares: &[(u64, G1Affine)], expected: G1Affine, seed: Option<G1Affine>| {
+        mock_run(k, |ec_chip, region, config, offset| {
+            let assigned_shares = shares
+                .iter()
+                .map(|(_, s)| ec_chip.load_private_point(region, config, s, offset))
+                .collect::<Result<Vec<_>, _>>()?;
+            let point = MultiPartyPoint {
+                threshold: 2,
+                num_parties: 3,
+                indices: shares.iter().map(|(i, _)| *i).collect(),
+                shares: assigned_shares,
+            };
+
+            // the bits of the coefficients come before the loop
+            if let Some(coefficients) = point.lagrange_coefficients() {
+                let rows = ec_chip.dry_run_offset(|chip, region, config, offset| {
+                    for lambda in coefficients.iter() {
+                        chip.decompose_scalar(region, config, lambda, offset)?;
+                    }
+                    Ok(())
+                })?;
+                seed_row.set(*offset + rows);
+            }
+
+            let res = point.combine(ec_chip, region, config, offset)?;
+            if let Some(q) = seed {
+                overwrite_point(region, config, seed_row.get(), &q)?;
+            }
+
+            let expected = ec_chip.load_private_point(region, config, &expected, offset)?;
+            region.constrain_equal(res.x.cell(), expected.x.cell())?;
+            region.constrain_equal(res.y.cell(), expected.y.cell())?;
+
+            Ok(())
+        })
+    };
+    let run = |shares: &[(u64, G1Affine)], expected| run_with_seed(shares, expected, None);
+
+    // a 2-of-3 sharing of the secret a0 via f(x) = a0 + a1 * x
+    let mut rng = test_rng();
+    let (a0, a1) = (Fr::random(&mut rng), Fr::random(&mut rng));
+    let r = G1::random(&mut rng).to_affine();
+    let share = |i: u64| (i, (r * (a0 + a1 * Fr::from(i))).to_affine());
+    let expected = (r * a0).to_affine();
+
+    for parties in [[1, 2], [1, 3], [3, 2]] {
+        let shares = parties.iter().map(|&i| share(i)).collect::<Vec<_>>();
+        run(&shares, expected).unwrap().assert_satisfied();
+    }
+
+    // error case: a share of a wrong party
+    assert!(run(&[share(1), (3, share(2).1)], expected)
+        .unwrap()
+        .verify()
+        .is_err());
+
+    // error case: fewer shares than the threshold, a repeated party, or a party out of range
+    for shares in [
+        vec![share(1)],
+        vec![share(1), share(1)],
+        vec![share(1), share(4)],
+    ] {
+        assert!(run(&shares, expected).is_err());
+    }
+
+    // error case: a share of a wrong party, and a seed Q that shifts the
+    // combination to the secret; the seed is a constant
+    {
+        // lambda_1 = 3 / (3 - 1) and lambda_3 = 1 / (1 - 3)
+        let two_inv = Fr::from(2).invert().unwrap();
+        let combination = share(1).1 * (Fr::from(3) * two_inv) - share(2).1 * two_inv;
+        let seed = forged_seed(expected - combination);
+
+        let failures = run_with_seed(&[share(1), (3, share(2).1)], expected, Some(seed))
+            .unwrap()
+            .verify()
+            .unwrap_err();
+        assert!(copy_fails_at(&failures, seed_row.get()));
+    }
+}
@@ -1,7 +1,9 @@
 use std::u128;
 
 use halo2_proofs::circuit::Value;
+use halo2_proofs::halo2curves::ff::Field;
 use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::Curve;
 use halo2curves::CurveAffine;
 
 pub(crate) fn leak<T: Copy + Default>(a: &Value<&T>) -> T {
@@ -14,9 +16,10 @@ pub(crate) fn leak<T: Copy + Default>(a: &Value<&T>) -> T {
 /// store the high and low in base field.
 pub(crate) fn field_decompose_u128<S>(e: &S) -> (u128, u128)
 where
-    S: PrimeField<Repr = [u8; 32]>,
+    S: PrimeField,
 {
     let repr = e.to_repr();
+    let repr = repr.as_ref();
     let high = u128::from_le_bytes(repr[16..].try_into().unwrap());
     let low = u128::from_le_bytes(repr[..16].try_into().unwrap());
     (high, low)
@@ -36,7 +39,6 @@ where
     (high, low)
 }
 
-#[allow(dead_code)]
 pub(crate) fn to_le_bits<F: PrimeField<Repr = [u8; 32]>>(e: &F) -> Vec<bool> {
     let mut res = vec![];
     let repr = e.to_repr();
@@ -70,6 +72,72 @@ pub(crate) fn decompose_u128(a: &u128) -> Vec<u64> {
         .collect()
 }
 
+/// Divide a 256 bit integer (high, low) by a non-zero divisor (high, low),
+/// return the quotient and the remainder (high, low).
+/// The quotient is computed by repeated subtraction, so it is expected to be small.
+pub(crate) fn u256_div_rem_small(a: (u128, u128), d: (u128, u128)) -> (u64, (u128, u128)) {
+    let mut q = 0;
+    let mut rem = a;
+    while rem >= d {
+        let (low, borrow) = rem.1.overflowing_sub(d.1);
+        rem = (rem.0 - d.0 - borrow as u128, low);
+        q += 1;
+    }
+    (q, rem)
+}
+
+/// Split a 256 bit integer (high, low) into little endian u64 limbs.
+pub(crate) fn u256_to_u64_limbs(a: (u128, u128)) -> [u64; 4] {
+    [
+        a.1 as u64,
+        (a.1 >> 64) as u64,
+        a.0 as u64,
+        (a.0 >> 64) as u64,
+    ]
+}
+
+/// Return a * b mod 2^256, over little endian u64 limbs.
+pub(crate) fn u256_mul_wrapping(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut res = [0u64; 4];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 - i {
+            // at most (2^64 - 1) + (2^64 - 1)^2 + (2^64 - 1) = 2^128 - 1
+            let t = res[i + j] as u128 + a[i] as u128 * b[j] as u128 + carry;
+            res[i + j] = t as u64;
+            carry = t >> 64;
+        }
+    }
+    res
+}
+
+/// Return a - b mod 2^256, over little endian u64 limbs.
+pub(crate) fn u256_sub_wrapping(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut res = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d, borrow_1) = a[i].overflowing_sub(b[i]);
+        let (d, borrow_2) = d.overflowing_sub(borrow as u64);
+        res[i] = d;
+        borrow = borrow_1 || borrow_2;
+    }
+    res
+}
+
+/// Return the inverse of an odd a mod 2^256, over little endian u64 limbs.
+///
+/// Newton iteration x <- x * (2 - a * x) doubles the number of correct bits,
+/// starting from x = a, which is correct mod 2^3 for an odd a.
+pub(crate) fn u256_inv_odd(a: &[u64; 4]) -> [u64; 4] {
+    debug_assert_eq!(a[0] & 1, 1);
+    let two = [2, 0, 0, 0];
+    let mut x = *a;
+    for _ in 0..7 {
+        x = u256_mul_wrapping(&x, &u256_sub_wrapping(&two, &u256_mul_wrapping(a, &x)));
+    }
+    x
+}
+
 #[inline]
 // hardcoded value for `-2^256 * generator` for Grumpkin curve
 pub(crate) fn neg_generator_times_2_to_256<C, F>() -> (C, F, F)
@@ -88,17 +156,68 @@ where
     (C::from_xy(x, y).unwrap(), x, y)
 }
 
+/// Native reference of `NativeECOps::point_add`: returns p1 + p2.
+///
+/// The circuit does not represent the point at infinity, and the addition
+/// gate does not hold for p1 = +/- p2. Returns `None` for those inputs,
+/// i.e., whenever the circuit cannot compute the sum.
+pub fn native_add<C: CurveAffine>(p1: &C, p2: &C) -> Option<C> {
+    let c1 = Option::<_>::from(p1.coordinates())?;
+    let c2 = Option::<_>::from(p2.coordinates())?;
+    if c1.x() == c2.x() {
+        return None;
+    }
+    Some((*p1 + *p2).to_affine())
+}
+
+/// Native reference of `NativeECOps::point_double`: returns 2 * p.
+///
+/// Returns `None` if p is the point at infinity, or if 2 * p is,
+/// i.e., p has order 2.
+pub fn native_double<C: CurveAffine>(p: &C) -> Option<C> {
+    let c = Option::<_>::from(p.coordinates())?;
+    if bool::from(c.y().is_zero()) {
+        return None;
+    }
+    Some((*p + *p).to_affine())
+}
+
+/// Native reference of `NativeECOps::point_mul`: returns p * s.
+///
+/// Returns `None` if p or p * s is the point at infinity; the latter
+/// includes s = 0. The circuit starts from an offset generator, so an
+/// intermediate sum may also hit the identity; this happens with negligible
+/// probability for a random point and is not modeled here.
+pub fn native_mul<C: CurveAffine>(p: &C, s: &C::ScalarExt) -> Option<C> {
+    Option::<_>::from(p.coordinates())?;
+    let res = (*p * *s).to_affine();
+    Option::<_>::from(res.coordinates())?;
+    Some(res)
+}
+
 #[cfg(test)]
 mod test {
     use halo2_proofs::arithmetic::Field;
+    use halo2_proofs::halo2curves::group::Curve;
+    use halo2_proofs::halo2curves::group::Group;
     use halo2curves::grumpkin::Fq;
     use halo2curves::grumpkin::Fr;
+    use halo2curves::grumpkin::G1Affine;
+    use halo2curves::grumpkin::G1;
 
+    use crate::test_vectors::point;
+    use crate::test_vectors::NEG_GENERATOR_TIMES_2_TO_256;
     use crate::util::byte_to_le_bits;
     use crate::util::to_le_bits;
 
     use super::decompose_u128;
     use super::field_decompose;
+    use super::field_decompose_u128;
+    use super::neg_generator_times_2_to_256;
+    use super::u256_inv_odd;
+    use super::u256_mul_wrapping;
+    use super::u256_sub_wrapping;
+    use super::u256_to_u64_limbs;
 
     #[test]
     fn test_to_bites() {
@@ -134,6 +253,18 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_u256_inv_odd() {
+        let mut rng = ark_std::test_rng();
+        for _ in 0..10 {
+            let (high, low) = field_decompose_u128(&Fr::random(&mut rng));
+            let a = u256_to_u64_limbs((high, low | 1));
+            let a_inv = u256_inv_odd(&a);
+            assert_eq!(u256_mul_wrapping(&a, &a_inv), [1, 0, 0, 0]);
+        }
+        assert_eq!(u256_sub_wrapping(&[0; 4], &[1, 0, 0, 0]), [u64::MAX; 4]);
+    }
+
     #[test]
     fn test_field_decom() {
         let mut rng = ark_std::test_rng();
@@ -150,4 +281,22 @@ mod test {
         // println!("{:?}", bits);
         // panic!()
     }
+
+    #[test]
+    fn test_neg_generator_times_2_to_256() {
+        let (p, x, y) = neg_generator_times_2_to_256::<G1Affine, Fq>();
+
+        // -(2^256 * generator), via a native scalar mul
+        let two_to_256 = Fr::from(2).pow_vartime([256]);
+        let expected = (-(G1::generator() * two_to_256)).to_affine();
+        assert_eq!(p, expected);
+        assert_eq!((x, y), (expected.x, expected.y));
+
+        // and via 256 doublings, which does not reduce 2^256 mod the group order
+        let doubled = (0..256).fold(G1::generator(), |acc, _| acc.double());
+        assert_eq!(p, (-doubled).to_affine());
+
+        // and the fixed vector
+        assert_eq!(p, point(&NEG_GENERATOR_TIMES_2_TO_256));
+    }
 }
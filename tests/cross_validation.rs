@@ -0,0 +1,212 @@
+//! Cross-validation of the point mul trace against an independent model of
+//! double-and-add, written with the affine formulas over the base field only.
+//!
+//! Every intermediate of `ECChip::compute_point_mul_witness`, i.e., each
+//! doubling, each conditional add and the bits, is compared with the model for
+//! a few hundred random cases. A handful of model traces are then assigned via
+//! `NativeECOps::assign_point_mul_witness` and checked by the mock prover, so the
+//! gates accept the model rather than only agreeing on the final result.
+
+use ark_std::test_rng;
+use halo2_native_ecc::ECChip;
+use halo2_native_ecc::ECConfig;
+use halo2_native_ecc::NativeECOps;
+use halo2_native_ecc::PointMulWitness;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::halo2curves::group::prime::PrimeCurveAffine;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Error;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+// the model: affine points (x, y) on y^2 = x^3 - 17, never the identity
+type Point = (Fq, Fq);
+
+fn model_add(p: Point, q: Point) -> Point {
+    assert_ne!(p.0, q.0, "model: no affine sum");
+    let slope = (q.1 - p.1) * (q.0 - p.0).invert().unwrap();
+    let x = slope.square() - p.0 - q.0;
+    (x, slope * (p.0 - x) - p.1)
+}
+
+fn model_double(p: Point) -> Point {
+    let slope = (p.0.square() * Fq::from(3)) * (p.1.double()).invert().unwrap();
+    let x = slope.square() - p.0.double();
+    (x, slope * (p.0 - x) - p.1)
+}
+
+// the little endian bits of the canonical encoding of s
+fn model_bits(s: &Fr) -> Vec<bool> {
+    s.to_repr()
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect()
+}
+
+// the trace of double-and-add from the generator, most significant bit first,
+// and the result with 2^256 * generator subtracted
+fn model_mul(p: Point, s: &Fr) -> (Vec<bool>, Vec<Point>, Vec<Point>, Point) {
+    let generator = G1Affine::generator();
+    let generator = (generator.x, generator.y);
+
+    let bits = model_bits(s);
+    let mut doubles = vec![];
+    let mut adds = vec![];
+    let mut acc = generator;
+    for &bit in bits.iter().rev() {
+        acc = model_double(acc);
+        doubles.push(acc);
+        if bit {
+            acc = model_add(acc, p);
+        }
+        adds.push(acc);
+    }
+
+    let offset = (0..256).fold(generator, |acc, _| model_double(acc));
+    let res = model_add(acc, (offset.0, -offset.1));
+    (bits, doubles, adds, res)
+}
+
+fn to_point(p: &G1Affine) -> Point {
+    (p.x, p.y)
+}
+
+fn from_point(p: Point) -> G1Affine {
+    let p = G1Affine { x: p.0, y: p.1 };
+    assert!(bool::from(p.is_on_curve()));
+    p
+}
+
+#[test]
+fn test_point_mul_trace_against_model() {
+    let mut rng = test_rng();
+
+    for _ in 0..200 {
+        let p = G1::random(&mut rng).to_affine();
+        let s = Fr::random(&mut rng);
+
+        let witness = ECChip::<G1Affine, Fq>::compute_point_mul_witness(&p, &s);
+        let (bits, doubles, adds, res) = model_mul(to_point(&p), &s);
+
+        assert_eq!(witness.bits, bits);
+        for i in 0..256 {
+            assert_eq!(
+                to_point(&witness.doubles[i]),
+                doubles[i],
+                "double of round {i}"
+            );
+            assert_eq!(to_point(&witness.adds[i]), adds[i], "add of round {i}");
+        }
+        assert_eq!(to_point(&witness.res), res);
+        assert_eq!(witness.res, (p * s).to_affine());
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ModelTraceTestCircuit {
+    witness: PointMulWitness<G1Affine>,
+}
+
+impl Circuit<Fq> for ModelTraceTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test model trace circuit",
+            |mut region| {
+                let mut offset = 0;
+                let res = ec_chip.assign_point_mul_witness(
+                    &mut region,
+                    &config,
+                    &self.witness,
+                    &mut offset,
+                )?;
+
+                let expected = ec_chip.load_private_point(
+                    &mut region,
+                    &config,
+                    &self.witness.res,
+                    &mut offset,
+                )?;
+                region.constrain_equal(res.x().cell(), expected.x().cell())?;
+                region.constrain_equal(res.y().cell(), expected.y().cell())?;
+
+                // pad the last two rows
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_model_trace_is_accepted() {
+    let k = 11;
+
+    let mut rng = test_rng();
+    for _ in 0..3 {
+        let p = G1::random(&mut rng).to_affine();
+        let s = Fr::random(&mut rng);
+        let (bits, doubles, adds, res) = model_mul(to_point(&p), &s);
+
+        let witness = PointMulWitness {
+            p,
+            bits,
+            doubles: doubles.into_iter().map(from_point).collect(),
+            adds: adds.into_iter().map(from_point).collect(),
+            res: from_point(res),
+        };
+        let circuit = ModelTraceTestCircuit { witness };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: the model trace with the bits of two adjacent rounds swapped,
+    // i.e., an off-by-one in the bit order
+    {
+        let p = G1::random(&mut rng).to_affine();
+        let s = Fr::random(&mut rng);
+        let (mut bits, doubles, adds, res) = model_mul(to_point(&p), &s);
+        let i = (1..256).rev().find(|&i| bits[i] != bits[i - 1]).unwrap();
+        bits.swap(i, i - 1);
+
+        let witness = PointMulWitness {
+            p,
+            bits,
+            doubles: doubles.into_iter().map(from_point).collect(),
+            adds: adds.into_iter().map(from_point).collect(),
+            res: from_point(res),
+        };
+        let circuit = ModelTraceTestCircuit { witness };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
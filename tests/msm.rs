@@ -0,0 +1,147 @@
+//! The MSM of `MsmAccumulator` against the native sum of scalar muls,
+//! for random inputs from a fixed seed.
+//!
+//! No arkworks curve matches halo2curves' Grumpkin, so the reference is the
+//! straightforward `sum_i s_i * p_i` of halo2curves.
+
+use ark_std::test_rng;
+use halo2_native_ecc::ECChip;
+use halo2_native_ecc::ECConfig;
+use halo2_native_ecc::MsmAccumulator;
+use halo2_native_ecc::NativeECOps;
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::circuit::SimpleFloorPlanner;
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::halo2curves::group::Curve;
+use halo2_proofs::halo2curves::group::Group;
+use halo2_proofs::plonk::Circuit;
+use halo2_proofs::plonk::ConstraintSystem;
+use halo2_proofs::plonk::Error;
+use halo2curves::grumpkin::Fq;
+use halo2curves::grumpkin::Fr;
+use halo2curves::grumpkin::G1Affine;
+use halo2curves::grumpkin::G1;
+
+#[derive(Default, Debug, Clone)]
+struct MsmTestCircuit {
+    points: Vec<G1Affine>,
+    scalars: Vec<Fr>,
+    expected: G1Affine,
+}
+
+impl Circuit<Fq> for MsmTestCircuit {
+    type Config = ECConfig<G1Affine, Fq>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fq>) -> Self::Config {
+        ECChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fq>,
+    ) -> Result<(), Error> {
+        let ec_chip = ECChip::construct(config.clone());
+
+        layouter.assign_region(
+            || "test msm circuit",
+            |mut region| {
+                let mut offset = 0;
+                let mut msm = MsmAccumulator::new();
+                for (p, s) in self.points.iter().zip(self.scalars.iter()) {
+                    let p = ec_chip.load_private_point(&mut region, &config, p, &mut offset)?;
+                    let bits = ec_chip.decompose_scalar(&mut region, &config, s, &mut offset)?;
+                    msm.push(&p, &bits)?;
+                }
+                let res = msm.finalize(&ec_chip, &mut region, &config, &mut offset)?;
+
+                let expected = ec_chip.load_private_point(
+                    &mut region,
+                    &config,
+                    &self.expected,
+                    &mut offset,
+                )?;
+                region.constrain_equal(res.x().cell(), expected.x().cell())?;
+                region.constrain_equal(res.y().cell(), expected.y().cell())?;
+
+                // pad the last two rows
+                ec_chip.pad(&mut region, &config, &mut offset)?;
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+// sum_i s_i * p_i
+fn native_msm(points: &[G1Affine], scalars: &[Fr]) -> G1Affine {
+    points
+        .iter()
+        .zip(scalars.iter())
+        .fold(G1::identity(), |acc, (p, s)| acc + p * s)
+        .to_affine()
+}
+
+fn run_msm(k: u32, points: Vec<G1Affine>, scalars: Vec<Fr>) {
+    let expected = native_msm(&points, &scalars);
+
+    {
+        let circuit = MsmTestCircuit {
+            points: points.clone(),
+            scalars: scalars.clone(),
+            expected,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // error case: wrong result
+    {
+        let circuit = MsmTestCircuit {
+            points: points.clone(),
+            scalars,
+            expected: (expected + points[0]).to_affine(),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[test]
+fn test_msm_random() {
+    let mut rng = test_rng();
+
+    for (n, k) in [(1, 12), (2, 12), (5, 13), (16, 15)] {
+        let points = (0..n)
+            .map(|_| G1::random(&mut rng).to_affine())
+            .collect::<Vec<_>>();
+        let scalars = (0..n).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+        run_msm(k, points, scalars);
+    }
+}
+
+#[test]
+fn test_msm_coinciding_points() {
+    let k = 13;
+
+    let mut rng = test_rng();
+    let p = G1::random(&mut rng).to_affine();
+    let q = G1::random(&mut rng).to_affine();
+    let s = Fr::random(&mut rng);
+    let t = Fr::random(&mut rng);
+
+    // the same point with different scalars
+    run_msm(k, vec![p, p], vec![s, t]);
+    // the same point with the same scalar
+    run_msm(k, vec![p, p], vec![s, s]);
+    // two coinciding points among others
+    run_msm(k, vec![p, q, p, q, p], vec![s, t, t, s, s]);
+}